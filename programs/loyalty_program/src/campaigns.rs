@@ -0,0 +1,60 @@
+//! Merchant promotional campaigns.
+//!
+//! A campaign passed to an issuing instruction adds `bonus_bps` of the base
+//! reward on top of the consumer's tier reward, while it is inside its window
+//! and matches the product filter. Bonuses are capped by the remaining budget.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::math::apply_bps;
+use crate::state::Campaign;
+
+/// Bonus points for an issuance of `base_amount`, debited from the campaign budget.
+///
+/// Campaigns outside their window, filtered to another product, or out of
+/// budget add nothing.
+pub fn take_campaign_bonus(
+    campaign: &mut Campaign,
+    base_amount: u64,
+    product_id_hash: Option<&[u8; 32]>,
+    now: i64,
+) -> Result<u64> {
+    if !campaign.is_active(now) || !campaign.applies_to(product_id_hash) {
+        return Ok(0);
+    }
+
+    let remaining_budget = campaign.budget.saturating_sub(campaign.bonus_issued);
+    let bonus = apply_bps(base_amount, campaign.bonus_bps)?.min(remaining_budget);
+
+    campaign.bonus_issued = campaign
+        .bonus_issued
+        .checked_add(bonus)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    Ok(bonus)
+}
+
+/// Return to the campaign budget the bonus share of `clawed_back` reward points.
+///
+/// `bonus` is the part of the purchase's `reward` that came from the campaign;
+/// it is returned in proportion to how much of the reward was clawed back.
+pub fn return_campaign_bonus(
+    campaign: &mut Campaign,
+    bonus: u64,
+    clawed_back: u64,
+    reward: u64,
+) -> Result<u64> {
+    if reward == 0 {
+        return Ok(0);
+    }
+
+    let returned = (bonus as u128)
+        .checked_mul(clawed_back.min(reward) as u128)
+        .map(|value| value / reward as u128)
+        .ok_or(LoyaltyError::ArithmeticOverflow)? as u64;
+
+    campaign.bonus_issued = campaign.bonus_issued.saturating_sub(returned);
+
+    Ok(returned)
+}
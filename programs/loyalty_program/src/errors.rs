@@ -53,6 +53,8 @@ pub enum LoyaltyError {
     #[msg("SOL deposit amount must be greater than zero")]
     InsufficientSolDeposit,
     
+    /// Deprecated: deposits report `ExceedsMaxSupply` through the shared mint policy.
+    /// Kept so the error codes after it stay stable.
     #[msg("Deposit would mint points exceeding maximum token supply")]
     DepositExceedsMaxSupply,
     
@@ -61,4 +63,212 @@ pub enum LoyaltyError {
     
     #[msg("Invalid SOL to points ratio - must be greater than zero")]
     InvalidRatio,
+
+    #[msg("Product is not available for purchase")]
+    ProductUnavailable,
+
+    #[msg("Product does not accept this payment method")]
+    PaymentMethodNotAccepted,
+
+    #[msg("Product must have a SOL price or a points price")]
+    InvalidProductPrice,
+
+    #[msg("Max supply cannot be lowered below the current supply")]
+    MaxSupplyBelowCurrentSupply,
+
+    #[msg("Platform is already paused")]
+    PlatformAlreadyPaused,
+
+    #[msg("Platform is not paused")]
+    PlatformNotPaused,
+
+    #[msg("This operation is currently paused")]
+    OperationPaused,
+
+    #[msg("Paused operations mask contains unknown bits")]
+    InvalidPausedOperations,
+
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[msg("Signer is not the pending admin")]
+    UnauthorizedPendingAdmin,
+
+    #[msg("Invalid admin address")]
+    InvalidAdmin,
+
+    #[msg("Merchant is already authorized")]
+    MerchantAlreadyAuthorized,
+
+    #[msg("Mint allowance cannot be below the amount already minted")]
+    InvalidMintAllowance,
+
+    #[msg("Treasury balance is insufficient for this withdrawal")]
+    InsufficientTreasuryBalance,
+
+    #[msg("Not enough points lots were supplied to cover this spend")]
+    MissingPointsLots,
+
+    #[msg("Points lot does not belong to this consumer or is out of order")]
+    InvalidPointsLot,
+
+    /// Deprecated: spends close expired lots themselves instead of failing.
+    /// Kept so the error codes after it stay stable.
+    #[msg("Points lot has expired - run expire_points first")]
+    PointsLotExpired,
+
+    #[msg("Points lot has not expired yet")]
+    PointsNotExpired,
+
+    /// Deprecated: expire_points closes the lot and records the points it
+    /// can't burn on the profile instead of failing.
+    /// Kept so the error codes after it stay stable.
+    #[msg("Consumer token account has not delegated to the platform for expiry burns")]
+    ExpiryDelegateMissing,
+
+    #[msg("Expiry period must not be negative")]
+    InvalidExpiryPeriod,
+
+    #[msg("Tier thresholds must increase and multipliers must be at least 1x and non-decreasing")]
+    InvalidTierConfig,
+
+    #[msg("Campaign must end after it starts and after the current time")]
+    InvalidCampaignWindow,
+
+    #[msg("Campaign bonus and budget must be greater than zero")]
+    InvalidCampaignBonus,
+
+    #[msg("Consumers cannot refer themselves")]
+    SelfReferral,
+
+    #[msg("Referrer must be registered before the first purchase")]
+    ReferralAfterFirstPurchase,
+
+    #[msg("Referrer token account is missing or does not belong to the referrer")]
+    InvalidReferrerAccount,
+
+    #[msg("Purchase has already been refunded")]
+    AlreadyRefunded,
+
+    /// Deprecated: refunds claw back what is burnable and record the rest as a shortfall.
+    /// Kept so the error codes after it stay stable.
+    #[msg("Customer no longer holds the purchase reward or has revoked the platform delegate")]
+    RefundClawbackUnavailable,
+
+    #[msg("Points portion must be non-zero and worth less than the SOL price")]
+    InvalidPaymentSplit,
+
+    #[msg("Merchant cash-out is disabled")]
+    CashOutDisabled,
+
+    #[msg("Basis points value cannot exceed 10000")]
+    InvalidBasisPoints,
+
+    #[msg("Volume fee tiers must have increasing thresholds and discounts of at most 10000 bps")]
+    InvalidVolumeFeeTiers,
+
+    #[msg("Payment mint is not enabled")]
+    PaymentMintDisabled,
+
+    #[msg("Payment token account does not match the purchase's payment mint or owner")]
+    InvalidPaymentAccount,
+
+    #[msg("Mint extensions must be known flags and require the Token-2022 program")]
+    InvalidMintExtensions,
+
+    #[msg("Consumers cannot gift points to themselves")]
+    SelfGift,
+
+    #[msg("Consumer account is too new to gift points")]
+    GiftAccountTooNew,
+
+    #[msg("Gift would exceed the sender's daily gifting cap")]
+    GiftDailyCapExceeded,
+
+    #[msg("Gift minimum account age cannot be negative")]
+    InvalidGiftConfig,
+
+    #[msg("Batch must contain between 1 and 18 entries")]
+    InvalidBatchSize,
+
+    #[msg("Remaining accounts must be a token account, profile and next lot per batch entry, one entry per consumer")]
+    InvalidBatchAccounts,
+
+    #[msg("Signer is neither the merchant nor one of its delegates")]
+    UnauthorizedDelegate,
+
+    #[msg("Delegate must differ from the merchant and expire in the future")]
+    InvalidDelegate,
+
+    #[msg("Merchant delegate has expired")]
+    DelegateExpired,
+
+    #[msg("Mint exceeds the delegate's per-transaction cap")]
+    DelegateTxCapExceeded,
+
+    #[msg("Mint exceeds the delegate's daily cap")]
+    DelegateDailyCapExceeded,
+
+    #[msg("Merchant delegate does not hold enough lamports to pay the mint fee and rent")]
+    InsufficientDelegateFeeBalance,
+
+    #[msg("Points were already minted for this purchase reference")]
+    DuplicatePurchaseReference,
+
+    #[msg("Mint receipt account does not match the merchant and purchase reference")]
+    InvalidMintReceipt,
+
+    #[msg("Purchase retention period cannot be negative")]
+    InvalidRetentionPeriod,
+
+    #[msg("Purchase has already been marked final")]
+    PurchaseAlreadyFinal,
+
+    #[msg("Purchase record is within its retention period and not yet final")]
+    PurchaseRecordRetained,
+
+    #[msg("Purchase status does not allow this transition")]
+    InvalidPurchaseStatus,
+
+    #[msg("Dispute window cannot be negative")]
+    InvalidDisputeWindow,
+
+    #[msg("Dispute window for this purchase has closed")]
+    DisputeWindowClosed,
+
+    #[msg("Purchase has an open dispute or an ordered refund")]
+    PurchaseDisputed,
+
+    #[msg("The permanent delegate already burns expired points; no approval is needed")]
+    ExpiryDelegateNotNeeded,
+
+    #[msg("Merchant has an ordered refund to settle first")]
+    RefundOutstanding,
+
+    #[msg("Cash-out exceeds the points redeemed at this merchant")]
+    CashOutExceedsRedeemed,
+
+    #[msg("Buyback rate would pay more per point than minting costs")]
+    BuybackAboveMintPrice,
+
+    #[msg("Gifts of non-transferable points are disabled")]
+    NonTransferableGiftsDisabled,
+
+    #[msg("Consumer profile account is not the consumer's profile PDA")]
+    InvalidConsumerProfile,
+
+    #[msg("Metaplex metadata account and program are required and must be the Metaplex accounts")]
+    InvalidMetadataAccount,
+
+    #[msg("Campaign account is not the campaign recorded on the purchase")]
+    InvalidCampaign,
+
+    #[msg("Refund would leave more points cashed out than redeemed at this merchant")]
+    RefundedPointsCashedOut,
+
+    #[msg("Purchase is still within its dispute window")]
+    DisputeWindowOpen,
+
+    #[msg("Price is above the maximum the customer agreed to pay")]
+    PriceAboveMax,
 }
@@ -0,0 +1,220 @@
+//! Mint fee schedule shared by every instruction that charges for minting.
+//!
+//! A fee is the sum of a flat base fee, a per-1000-units rate and a
+//! basis-point rate on the points' SOL value. Merchants with an admin-set
+//! override use it in place of the platform schedule, and the highest volume
+//! tier reached by the merchant's `total_minted` discounts the result.
+//! Purchases paid in an SPL payment mint are charged the same fee, converted
+//! into that mint through points.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::math::{apply_bps, lamports_to_points, points_to_lamports, points_to_tokens, BPS_DENOMINATOR};
+use crate::state::{FeeSchedule, MerchantRecord, PaymentMint, PlatformState, VolumeFeeTier};
+
+/// Protocol fee in lamports for `merchant_record` minting `amount` points
+pub fn mint_fee(
+    platform_state: &PlatformState,
+    merchant_record: &MerchantRecord,
+    amount: u64,
+) -> Result<u64> {
+    let schedule = merchant_record
+        .fee_override
+        .unwrap_or_else(|| platform_state.fee_schedule());
+    let discount_bps = volume_discount_bps(
+        &platform_state.volume_fee_tiers,
+        merchant_record.total_minted,
+    );
+
+    schedule_fee(
+        &schedule,
+        discount_bps,
+        amount,
+        platform_state.sol_to_points_ratio,
+        platform_state.token_decimals,
+    )
+}
+
+/// Protocol fee in `payment_mint` units, converted from the lamport fee via points
+pub fn mint_fee_in_tokens(
+    platform_state: &PlatformState,
+    merchant_record: &MerchantRecord,
+    payment_mint: &PaymentMint,
+    amount: u64,
+) -> Result<u64> {
+    let fee_lamports = mint_fee(platform_state, merchant_record, amount)?;
+    let fee_points = lamports_to_points(
+        fee_lamports,
+        platform_state.sol_to_points_ratio,
+        platform_state.token_decimals,
+    )?;
+
+    points_to_tokens(
+        fee_points,
+        payment_mint.points_per_token,
+        platform_state.token_decimals,
+        payment_mint.decimals,
+    )
+}
+
+/// Discount of the highest enabled tier reached by `total_minted`
+pub fn volume_discount_bps(tiers: &[VolumeFeeTier], total_minted: u64) -> u16 {
+    tiers
+        .iter()
+        .filter(|tier| tier.min_total_minted > 0 && total_minted >= tier.min_total_minted)
+        .map(|tier| tier.discount_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Fee for `amount` under `schedule`, less `discount_bps`
+pub fn schedule_fee(
+    schedule: &FeeSchedule,
+    discount_bps: u16,
+    amount: u64,
+    sol_to_points_ratio: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let points_in_thousands = amount.div_ceil(1000); // Round up
+    let variable_fee = points_in_thousands
+        .checked_mul(schedule.rate_per_thousand)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    let bps_fee = if schedule.rate_bps > 0 {
+        let points_value = points_to_lamports(amount, sol_to_points_ratio, decimals)?;
+        apply_bps(points_value, schedule.rate_bps)?
+    } else {
+        0
+    };
+
+    let fee = schedule
+        .base_fee
+        .checked_add(variable_fee)
+        .and_then(|fee| fee.checked_add(bps_fee))
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    let discount = apply_bps(fee, discount_bps.min(BPS_DENOMINATOR as u16))?;
+    Ok(fee - discount)
+}
+
+/// Validate a fee schedule's basis-point rate
+pub fn validate_schedule(schedule: &FeeSchedule) -> Result<()> {
+    require!(
+        schedule.rate_bps as u64 <= BPS_DENOMINATOR,
+        LoyaltyError::InvalidBasisPoints
+    );
+    Ok(())
+}
+
+/// Validate that cash-out never pays more per point than minting it costs.
+///
+/// A point cashed out at `buyback_ratio` less the spread must be worth no more
+/// than a point deposited at `sol_to_points_ratio` plus the smallest basis-point
+/// fee the platform schedule can charge, i.e. after the deepest volume discount.
+/// Flat and per-1000 fees are ignored since they shrink per point with volume.
+pub fn validate_buyback(platform_state: &PlatformState) -> Result<()> {
+    if platform_state.buyback_ratio == 0 {
+        return Ok(()); // Cash-out disabled
+    }
+    require!(
+        buyback_within_mint_price(
+            platform_state.buyback_ratio,
+            platform_state.cash_out_spread_bps,
+            platform_state.sol_to_points_ratio,
+            platform_state.mint_fee_bps,
+            volume_discount_bps(&platform_state.volume_fee_tiers, u64::MAX),
+        ),
+        LoyaltyError::BuybackAboveMintPrice
+    );
+    Ok(())
+}
+
+/// `(1 - spread) / buyback_ratio <= (1 + discounted mint fee) / sol_to_points_ratio`
+pub fn buyback_within_mint_price(
+    buyback_ratio: u64,
+    spread_bps: u16,
+    sol_to_points_ratio: u64,
+    mint_fee_bps: u16,
+    max_discount_bps: u16,
+) -> bool {
+    let bps = BPS_DENOMINATOR as u128;
+    let min_fee_bps = mint_fee_bps as u128
+        * (bps - (max_discount_bps as u128).min(bps))
+        / bps;
+    let payout_side = (bps - (spread_bps as u128).min(bps)) * sol_to_points_ratio as u128;
+    let cost_side = (bps + min_fee_bps) * buyback_ratio as u128;
+    payout_side <= cost_side
+}
+
+/// Validate volume tiers: enabled thresholds increase and discounts are at most 100%
+pub fn validate_volume_tiers(tiers: &[VolumeFeeTier]) -> Result<()> {
+    let mut previous = 0;
+    for tier in tiers.iter().filter(|tier| tier.min_total_minted > 0) {
+        require!(
+            tier.min_total_minted > previous && tier.discount_bps as u64 <= BPS_DENOMINATOR,
+            LoyaltyError::InvalidVolumeFeeTiers
+        );
+        previous = tier.min_total_minted;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAT: FeeSchedule = FeeSchedule {
+        base_fee: 5_000,
+        rate_per_thousand: 1_000,
+        rate_bps: 0,
+    };
+
+    #[test]
+    fn matches_legacy_formula() {
+        // base + ceil(amount / 1000) * rate
+        assert_eq!(schedule_fee(&FLAT, 0, 1_000, 100, 6).unwrap(), 6_000);
+        assert_eq!(schedule_fee(&FLAT, 0, 1_001, 100, 6).unwrap(), 7_000);
+    }
+
+    #[test]
+    fn adds_basis_point_fee_on_points_value() {
+        let schedule = FeeSchedule {
+            base_fee: 0,
+            rate_per_thousand: 0,
+            rate_bps: 100,
+        };
+        // 100 points at 100 points/SOL are worth 1 SOL; 1% = 0.01 SOL
+        assert_eq!(
+            schedule_fee(&schedule, 0, 100_000_000, 100, 6).unwrap(),
+            10_000_000
+        );
+    }
+
+    #[test]
+    fn applies_highest_volume_discount() {
+        let tiers = [
+            VolumeFeeTier { min_total_minted: 1_000, discount_bps: 1_000 },
+            VolumeFeeTier { min_total_minted: 10_000, discount_bps: 2_500 },
+            VolumeFeeTier::default(),
+        ];
+        assert_eq!(volume_discount_bps(&tiers, 999), 0);
+        assert_eq!(volume_discount_bps(&tiers, 5_000), 1_000);
+        assert_eq!(volume_discount_bps(&tiers, 10_000), 2_500);
+        assert_eq!(schedule_fee(&FLAT, 2_500, 1_000, 100, 6).unwrap(), 4_500);
+        assert!(validate_volume_tiers(&tiers).is_ok());
+        assert!(validate_volume_tiers(&[tiers[1], tiers[0]]).is_err());
+    }
+
+    #[test]
+    fn caps_buyback_at_the_mint_price() {
+        // Deposit at 100 points/SOL: buying back at 100 is break-even, 99 pays more
+        assert!(buyback_within_mint_price(100, 0, 100, 0, 0));
+        assert!(!buyback_within_mint_price(99, 0, 100, 0, 0));
+        // A 5% spread or a 5% mint fee covers a 95 points/SOL buyback
+        assert!(buyback_within_mint_price(95, 500, 100, 0, 0));
+        assert!(buyback_within_mint_price(96, 0, 100, 500, 0));
+        // ...unless the deepest volume tier discounts that fee away
+        assert!(!buyback_within_mint_price(96, 0, 100, 500, 10_000));
+    }
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    /// The proposed admin accepting the role
+    pub new_admin: Signer<'info>,
+
+    /// Platform state - verify pending admin
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.pending_admin.is_some() @ LoyaltyError::NoPendingAdmin,
+        constraint = platform_state.pending_admin == Some(new_admin.key()) @ LoyaltyError::UnauthorizedPendingAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+    let previous_admin = platform_state.admin;
+
+    platform_state.admin = ctx.accounts.new_admin.key();
+    platform_state.pending_admin = None;
+
+    msg!(
+        "Admin transfer accepted: {} -> {}",
+        previous_admin,
+        platform_state.admin
+    );
+
+    emit!(AdminTransferAccepted {
+        previous_admin,
+        new_admin: platform_state.admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AdminTransferAccepted {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,270 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+
+use crate::errors::LoyaltyError;
+use crate::fees::mint_fee;
+use crate::lots::{create_lot_account, load_or_create_profile, open_lot, store_account};
+use crate::math::format_points;
+use crate::policy::{check_mint_limits, record_mint};
+use crate::tiers::{boosted_reward, record_earned};
+use crate::state::{MerchantRecord, PlatformState, Treasury};
+
+/// Remaining accounts per entry: token account, consumer profile, next points lot
+pub const ACCOUNTS_PER_ENTRY: usize = 3;
+
+/// Accounts a transaction may lock
+const MAX_TRANSACTION_ACCOUNTS: usize = 64;
+
+/// Accounts every batch locks besides its entries: the named accounts and the program
+const FIXED_ACCOUNTS: usize = 8;
+
+/// Most entries a single batch may credit - as many as fit the account limit (18).
+/// A full batch needs a versioned transaction with an address lookup table and a
+/// raised compute budget; long purchase references lower the practical count.
+pub const MAX_BATCH_ENTRIES: usize = (MAX_TRANSACTION_ACCOUNTS - FIXED_ACCOUNTS) / ACCOUNTS_PER_ENTRY;
+
+/// One consumer credit within a batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchMintEntry {
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub purchase_reference: String,
+}
+
+#[derive(Accounts)]
+pub struct BatchMintPoints<'info> {
+    /// The authorized merchant issuing points
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Protocol treasury PDA - receives the aggregated minting fee
+    #[account(
+        mut,
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// Platform state - for mint authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_MINT) @ LoyaltyError::OperationPaused
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// The loyalty token mint
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Credit many consumers at once. Each entry passes its consumer's token
+/// account, profile and next points lot as remaining accounts, in entry order;
+/// missing profiles and the lots are created at the merchant's expense. Like
+/// `mint_points`, every entry gets its consumer's tier multiplier and an
+/// expiring lot. Campaigns and mint receipts don't apply to batches - credits
+/// that need a campaign bonus or duplicate-reference protection go through
+/// `mint_points`.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchMintPoints<'info>>,
+    entries: Vec<BatchMintEntry>,
+) -> Result<()> {
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_BATCH_ENTRIES,
+        LoyaltyError::InvalidBatchSize
+    );
+    require!(
+        ctx.remaining_accounts.len() == entries.len() * ACCOUNTS_PER_ENTRY,
+        LoyaltyError::InvalidBatchAccounts
+    );
+
+    for (index, entry) in entries.iter().enumerate() {
+        require!(entry.amount > 0, LoyaltyError::InvalidAmount);
+        require!(
+            entry.purchase_reference.len() <= 64,
+            LoyaltyError::ReferenceTooLong
+        );
+        // A consumer's next lot can only be opened once per transaction
+        require!(
+            entries[..index].iter().all(|other| other.consumer != entry.consumer),
+            LoyaltyError::InvalidBatchAccounts
+        );
+    }
+
+    let merchant_info = ctx.accounts.merchant.to_account_info();
+    let system_program_info = ctx.accounts.system_program.to_account_info();
+    let tier_config = ctx.accounts.platform_state.tier_config;
+    let expiry_seconds = ctx.accounts.platform_state.points_expiry_seconds;
+
+    // PASS 1: check each entry's accounts and apply its consumer's tier multiplier
+    let mut credits = Vec::with_capacity(entries.len());
+    let mut total_amount: u64 = 0;
+    for (entry, accounts) in entries
+        .iter()
+        .zip(ctx.remaining_accounts.chunks_exact(ACCOUNTS_PER_ENTRY))
+    {
+        let token_account_info = &accounts[0];
+        require_keys_eq!(
+            *token_account_info.owner,
+            ctx.accounts.token_program.key(),
+            LoyaltyError::InvalidBatchAccounts
+        );
+        let consumer_token_account = InterfaceAccount::<TokenAccount>::try_from(token_account_info)?;
+        require!(
+            consumer_token_account.mint == ctx.accounts.token_mint.key()
+                && consumer_token_account.owner == entry.consumer,
+            LoyaltyError::InvalidBatchAccounts
+        );
+
+        let profile = load_or_create_profile(
+            &accounts[1],
+            entry.consumer,
+            &merchant_info,
+            &system_program_info,
+        )?;
+        let amount = boosted_reward(&profile, &tier_config, entry.amount)?;
+        total_amount = total_amount
+            .checked_add(amount)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+        credits.push((profile, amount));
+    }
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    let merchant_record = &mut ctx.accounts.merchant_record;
+
+    // Enforce max supply and merchant allowance once for the whole batch
+    check_mint_limits(platform_state, merchant_record, total_amount)?;
+
+    // One fee for the batch total, so volume discounts apply to the aggregate
+    let total_fee = mint_fee(platform_state, merchant_record, total_amount)?;
+
+    msg!("Protocol fee calculation: entries={}, points={}, total_fee={}",
+        entries.len(), format_points(total_amount, platform_state.token_decimals), total_fee);
+
+    // ATOMIC STEP 1: Transfer protocol fee from merchant to treasury
+    anchor_lang::solana_program::program::invoke(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.merchant.key(),
+            &ctx.accounts.protocol_treasury.key(),
+            total_fee,
+        ),
+        &[
+            merchant_info.clone(),
+            ctx.accounts.protocol_treasury.to_account_info(),
+            system_program_info.clone(),
+        ],
+    )?;
+
+    msg!("Protocol fee paid: {} lamports", total_fee);
+
+    // ATOMIC STEP 2 (PASS 2): Mint each entry using PDA authority and record it as a lot
+    let seeds = &[
+        PlatformState::SEED,
+        &[platform_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    let now = Clock::get()?.unix_timestamp;
+
+    for (index, ((entry, accounts), (mut profile, amount))) in entries
+        .into_iter()
+        .zip(ctx.remaining_accounts.chunks_exact(ACCOUNTS_PER_ENTRY))
+        .zip(credits)
+        .enumerate()
+    {
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: accounts[0].clone(),
+                    authority: platform_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        let tier = profile.tier;
+        let (mut lot, lot_bump) = create_lot_account(
+            &accounts[2],
+            &profile,
+            &merchant_info,
+            &system_program_info,
+        )?;
+        open_lot(&mut profile, &mut lot, lot_bump, amount, expiry_seconds, now)?;
+        record_earned(&mut profile, &tier_config, amount, now)?;
+        store_account(&accounts[2], &lot)?;
+        store_account(&accounts[1], &profile)?;
+
+        emit!(BatchPointsIssued {
+            merchant: ctx.accounts.merchant.key(),
+            consumer: entry.consumer,
+            base_amount: entry.amount,
+            amount,
+            tier,
+            purchase_reference: entry.purchase_reference,
+            entry_index: index as u16,
+            lot_index: lot.index,
+            expires_at: lot.expires_at,
+            timestamp: now,
+        });
+    }
+
+    // Update state
+    record_mint(platform_state, merchant_record, total_amount)?;
+    platform_state.total_fees_collected = platform_state.total_fees_collected
+        .checked_add(total_fee)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    merchant_record.total_fees_paid = merchant_record
+        .total_fees_paid
+        .checked_add(total_fee)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Batch minted {} points to {} consumers by merchant {}",
+        format_points(total_amount, platform_state.token_decimals),
+        ctx.remaining_accounts.len() / ACCOUNTS_PER_ENTRY,
+        ctx.accounts.merchant.key()
+    );
+
+    Ok(())
+}
+
+#[event]
+pub struct BatchPointsIssued {
+    pub merchant: Pubkey,
+    pub consumer: Pubkey,
+    /// Amount requested by the merchant
+    pub base_amount: u64,
+    /// Amount minted after the consumer's tier multiplier
+    pub amount: u64,
+    /// Consumer's tier before this issuance
+    pub tier: u8,
+    pub purchase_reference: String,
+    /// Position of the entry within its batch
+    pub entry_index: u16,
+    /// Lot recording this issuance
+    pub lot_index: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
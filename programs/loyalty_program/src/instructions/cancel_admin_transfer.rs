@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    /// Current platform admin
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin,
+        constraint = platform_state.pending_admin.is_some() @ LoyaltyError::NoPendingAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+    let cancelled_admin = platform_state
+        .pending_admin
+        .take()
+        .ok_or(LoyaltyError::NoPendingAdmin)?;
+
+    msg!("Admin transfer to {} cancelled", cancelled_admin);
+
+    emit!(AdminTransferCancelled {
+        admin: ctx.accounts.admin.key(),
+        cancelled_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub cancelled_admin: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::state::Campaign;
+
+#[derive(Accounts)]
+pub struct CloseCampaign<'info> {
+    /// The merchant that created the campaign - receives the rent back
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Campaign PDA to close
+    #[account(
+        mut,
+        close = merchant,
+        seeds = [Campaign::SEED, merchant.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump,
+        has_one = merchant
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+pub fn handler(ctx: Context<CloseCampaign>) -> Result<()> {
+    let campaign = &ctx.accounts.campaign;
+
+    msg!(
+        "Campaign {} closed by merchant {}: {} of {} bonus points issued",
+        campaign.campaign_id,
+        campaign.merchant,
+        campaign.bonus_issued,
+        campaign.budget
+    );
+
+    emit!(CampaignClosed {
+        merchant: campaign.merchant,
+        campaign: campaign.key(),
+        campaign_id: campaign.campaign_id,
+        bonus_issued: campaign.bonus_issued,
+        budget: campaign.budget,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct CampaignClosed {
+    pub merchant: Pubkey,
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub bonus_issued: u64,
+    pub budget: u64,
+    pub timestamp: i64,
+}
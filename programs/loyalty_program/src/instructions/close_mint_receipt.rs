@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MintReceipt;
+
+#[derive(Accounts)]
+pub struct CloseMintReceipt<'info> {
+    /// The merchant that issued the points - receives the rent
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Receipt to close - its purchase reference can be minted again afterwards
+    #[account(
+        mut,
+        close = merchant,
+        seeds = [MintReceipt::SEED, merchant.key().as_ref(), &mint_receipt.reference_hash],
+        bump = mint_receipt.bump,
+        has_one = merchant
+    )]
+    pub mint_receipt: Account<'info, MintReceipt>,
+}
+
+pub fn handler(ctx: Context<CloseMintReceipt>) -> Result<()> {
+    let mint_receipt = &ctx.accounts.mint_receipt;
+
+    msg!(
+        "Merchant {} closed mint receipt {}",
+        mint_receipt.merchant,
+        mint_receipt.key()
+    );
+
+    emit!(MintReceiptClosed {
+        merchant: mint_receipt.merchant,
+        reference_hash: mint_receipt.reference_hash,
+        consumer: mint_receipt.consumer,
+        amount: mint_receipt.amount,
+        minted_at: mint_receipt.minted_at,
+        closed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MintReceiptClosed {
+    pub merchant: Pubkey,
+    pub reference_hash: [u8; 32],
+    pub consumer: Pubkey,
+    pub amount: u64,
+    pub minted_at: i64,
+    pub closed_at: i64,
+}
@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{PlatformState, PurchaseRecord};
+
+#[derive(Accounts)]
+pub struct ClosePurchaseRecord<'info> {
+    /// The customer who paid the record's rent - receives it back
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// Platform state - purchase retention period
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Purchase record to close
+    #[account(
+        mut,
+        close = customer,
+        seeds = [
+            PurchaseRecord::SEED,
+            customer.key().as_ref(),
+            &purchase_record.index.to_le_bytes()
+        ],
+        bump = purchase_record.bump,
+        has_one = customer,
+        constraint = !purchase_record.has_open_dispute() @ LoyaltyError::PurchaseDisputed
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+}
+
+pub fn handler(ctx: Context<ClosePurchaseRecord>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let purchase_record = &ctx.accounts.purchase_record;
+    let retention_seconds = ctx.accounts.platform_state.purchase_retention_seconds;
+
+    // Closable once the merchant marks it final, or after the retention period
+    let retention_elapsed = retention_seconds > 0
+        && now
+            >= purchase_record
+                .purchased_at
+                .checked_add(retention_seconds)
+                .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    require!(
+        purchase_record.finalized_at != 0 || retention_elapsed,
+        LoyaltyError::PurchaseRecordRetained
+    );
+
+    msg!(
+        "Customer {} closed purchase record {} (index {})",
+        purchase_record.customer,
+        purchase_record.key(),
+        purchase_record.index
+    );
+
+    // Carries the full record so indexers keep the history after the account is gone
+    emit!(PurchaseRecordClosed {
+        purchase_record: purchase_record.key(),
+        customer: purchase_record.customer,
+        merchant: purchase_record.merchant,
+        index: purchase_record.index,
+        product_id_hash: purchase_record.product_id_hash,
+        payment_type: purchase_record.payment_type,
+        payment_mint: purchase_record.payment_mint,
+        amount_paid: purchase_record.amount_paid,
+        points_paid: purchase_record.points_paid,
+        points_earned: purchase_record.points_earned,
+        clawback_shortfall: purchase_record.clawback_shortfall,
+        purchased_at: purchase_record.purchased_at,
+        finalized_at: purchase_record.finalized_at,
+        status: purchase_record.status,
+        fulfilled_at: purchase_record.fulfilled_at,
+        disputed_at: purchase_record.disputed_at,
+        closed_at: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PurchaseRecordClosed {
+    pub purchase_record: Pubkey,
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub index: u64,
+    pub product_id_hash: [u8; 32],
+    pub payment_type: u8,
+    pub payment_mint: Pubkey,
+    pub amount_paid: u64,
+    pub points_paid: u64,
+    pub points_earned: u64,
+    pub clawback_shortfall: u64,
+    pub purchased_at: i64,
+    pub finalized_at: i64,
+    pub status: u8,
+    pub fulfilled_at: i64,
+    pub disputed_at: i64,
+    pub closed_at: i64,
+}
@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{Campaign, MerchantRecord, PlatformState};
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct CreateCampaign<'info> {
+    /// The authorized merchant running the campaign
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Platform state
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// Campaign PDA
+    #[account(
+        init,
+        payer = merchant,
+        space = 8 + Campaign::INIT_SPACE,
+        seeds = [Campaign::SEED, merchant.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateCampaign>,
+    campaign_id: u64,
+    start_at: i64,
+    end_at: i64,
+    product_id_hash: Option<[u8; 32]>,
+    bonus_bps: u16,
+    budget: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        end_at > start_at && end_at > now,
+        LoyaltyError::InvalidCampaignWindow
+    );
+    require!(
+        bonus_bps > 0 && budget > 0,
+        LoyaltyError::InvalidCampaignBonus
+    );
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.merchant = ctx.accounts.merchant.key();
+    campaign.campaign_id = campaign_id;
+    campaign.start_at = start_at;
+    campaign.end_at = end_at;
+    campaign.product_id_hash = product_id_hash;
+    campaign.bonus_bps = bonus_bps;
+    campaign.budget = budget;
+    campaign.bonus_issued = 0;
+    campaign.created_at = now;
+    campaign.bump = ctx.bumps.campaign;
+
+    msg!(
+        "Campaign {} created by merchant {}: {}..{}, bonus_bps={}, budget={}",
+        campaign_id,
+        campaign.merchant,
+        start_at,
+        end_at,
+        bonus_bps,
+        budget
+    );
+
+    emit!(CampaignCreated {
+        merchant: campaign.merchant,
+        campaign: campaign.key(),
+        campaign_id,
+        start_at,
+        end_at,
+        product_id_hash,
+        bonus_bps,
+        budget,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct CampaignCreated {
+    pub merchant: Pubkey,
+    pub campaign: Pubkey,
+    pub campaign_id: u64,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub product_id_hash: Option<[u8; 32]>,
+    pub bonus_bps: u16,
+    pub budget: u64,
+    pub timestamp: i64,
+}
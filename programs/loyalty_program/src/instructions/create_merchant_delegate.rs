@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantDelegate, MerchantRecord};
+
+#[derive(Accounts)]
+pub struct CreateMerchantDelegate<'info> {
+    /// The merchant authorizing the delegate - pays rent and the fee deposit
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// The staff wallet or POS device being authorized
+    /// CHECK: This is the delegate's wallet address
+    #[account(constraint = delegate.key() != merchant.key() @ LoyaltyError::InvalidDelegate)]
+    pub delegate: UncheckedAccount<'info>,
+
+    /// Delegate PDA - caps, usage and the lamports that fund its mint fees
+    #[account(
+        init,
+        payer = merchant,
+        space = 8 + MerchantDelegate::INIT_SPACE,
+        seeds = [MerchantDelegate::SEED, merchant.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub merchant_delegate: Account<'info, MerchantDelegate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateMerchantDelegate>,
+    per_tx_cap: u64,
+    daily_cap: u64,
+    expires_at: i64,
+    fee_deposit: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        expires_at == 0 || expires_at > now,
+        LoyaltyError::InvalidDelegate
+    );
+
+    // Delegates can't sign for the merchant's SOL, so mint fees come from this deposit
+    if fee_deposit > 0 {
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.merchant.key(),
+                &ctx.accounts.merchant_delegate.key(),
+                fee_deposit,
+            ),
+            &[
+                ctx.accounts.merchant.to_account_info(),
+                ctx.accounts.merchant_delegate.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    let merchant_delegate = &mut ctx.accounts.merchant_delegate;
+    merchant_delegate.merchant = ctx.accounts.merchant.key();
+    merchant_delegate.delegate = ctx.accounts.delegate.key();
+    merchant_delegate.per_tx_cap = per_tx_cap;
+    merchant_delegate.daily_cap = daily_cap;
+    merchant_delegate.expires_at = expires_at;
+    merchant_delegate.day = 0;
+    merchant_delegate.minted_today = 0;
+    merchant_delegate.total_minted = 0;
+    merchant_delegate.created_at = now;
+    merchant_delegate.bump = ctx.bumps.merchant_delegate;
+
+    msg!(
+        "Merchant {} authorized delegate {} (per-tx cap {}, daily cap {}, expires at {})",
+        merchant_delegate.merchant,
+        merchant_delegate.delegate,
+        per_tx_cap,
+        daily_cap,
+        expires_at
+    );
+
+    emit!(MerchantDelegateCreated {
+        merchant: merchant_delegate.merchant,
+        delegate: merchant_delegate.delegate,
+        per_tx_cap,
+        daily_cap,
+        expires_at,
+        fee_deposit,
+        created_at: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantDelegateCreated {
+    pub merchant: Pubkey,
+    pub delegate: Pubkey,
+    pub per_tx_cap: u64,
+    pub daily_cap: u64,
+    pub expires_at: i64,
+    pub fee_deposit: u64,
+    pub created_at: i64,
+}
@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantRecord, PlatformState, Product};
+
+#[derive(Accounts)]
+#[instruction(product_id_hash: [u8; 32])]
+pub struct CreateProduct<'info> {
+    /// The authorized merchant listing the product
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Platform state
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// Product PDA - authoritative pricing for purchases
+    #[account(
+        init,
+        payer = merchant,
+        space = 8 + Product::INIT_SPACE,
+        seeds = [Product::SEED, merchant.key().as_ref(), &product_id_hash],
+        bump
+    )]
+    pub product: Account<'info, Product>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateProduct>,
+    product_id_hash: [u8; 32],
+    price_sol: u64,
+    price_points: u64,
+    loyalty_points_reward: u64,
+) -> Result<()> {
+    require!(
+        price_sol > 0 || price_points > 0,
+        LoyaltyError::InvalidProductPrice
+    );
+
+    let product = &mut ctx.accounts.product;
+    let clock = Clock::get()?;
+
+    product.merchant = ctx.accounts.merchant.key();
+    product.product_id_hash = product_id_hash;
+    product.price_sol = price_sol;
+    product.price_points = price_points;
+    product.loyalty_points_reward = loyalty_points_reward;
+    product.is_available = true;
+    product.created_at = clock.unix_timestamp;
+    product.updated_at = clock.unix_timestamp;
+    product.bump = ctx.bumps.product;
+
+    msg!(
+        "Product listed: merchant={}, product_hash={:?}, price_sol={}, price_points={}, reward={}",
+        ctx.accounts.merchant.key(),
+        &product_id_hash[..8],
+        price_sol,
+        price_points,
+        loyalty_points_reward
+    );
+
+    emit!(ProductCreated {
+        merchant: ctx.accounts.merchant.key(),
+        product_id_hash,
+        price_sol,
+        price_points,
+        loyalty_points_reward,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProductCreated {
+    pub merchant: Pubkey,
+    pub product_id_hash: [u8; 32],
+    pub price_sol: u64,
+    pub price_points: u64,
+    pub loyalty_points_reward: u64,
+    pub timestamp: i64,
+}
@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
 
 use crate::errors::LoyaltyError;
-use crate::state::{MerchantRecord, PlatformState};
+use crate::math::{format_points, lamports_to_points};
+use crate::policy::{check_mint_limits, record_mint};
+use crate::state::{MerchantRecord, PlatformState, Treasury};
 
 #[derive(Accounts)]
 pub struct DepositSol<'info> {
@@ -11,20 +13,21 @@ pub struct DepositSol<'info> {
     #[account(mut)]
     pub merchant: Signer<'info>,
 
-    /// Protocol treasury - receives the deposited SOL
-    /// CHECK: This is validated against platform_state
+    /// Protocol treasury PDA - receives the deposited SOL
     #[account(
         mut,
-        constraint = protocol_treasury.key() == platform_state.protocol_treasury @ LoyaltyError::InvalidTreasury
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
     )]
-    pub protocol_treasury: AccountInfo<'info>,
+    pub protocol_treasury: Account<'info, Treasury>,
 
     /// Platform state - for mint authority and ratio config
     #[account(
         mut,
         seeds = [PlatformState::SEED],
         bump = platform_state.bump,
-        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_MINT | PlatformState::PAUSE_DEPOSIT) @ LoyaltyError::OperationPaused
     )]
     pub platform_state: Account<'info, PlatformState>,
 
@@ -33,7 +36,8 @@ pub struct DepositSol<'info> {
         mut,
         seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
         bump = merchant_record.bump,
-        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
     )]
     pub merchant_record: Account<'info, MerchantRecord>,
 
@@ -43,18 +47,19 @@ pub struct DepositSol<'info> {
         seeds = [b"loyalty_mint"],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     /// Merchant's associated token account for loyalty tokens
     #[account(
         init_if_needed,
         payer = merchant,
         associated_token::mint = token_mint,
-        associated_token::authority = merchant
+        associated_token::authority = merchant,
+        associated_token::token_program = token_program
     )]
-    pub merchant_token_account: Account<'info, TokenAccount>,
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -74,27 +79,16 @@ pub fn handler(
     // token_decimals adjusts for token precision (e.g. 6 decimals)
     //
     // Formula: points = (sol_amount * ratio * 10^decimals) / LAMPORTS_PER_SOL
-    let decimals_multiplier = 10u64.pow(platform_state.token_decimals as u32);
-    let points_amount = sol_amount
-        .checked_mul(platform_state.sol_to_points_ratio)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?
-        .checked_mul(decimals_multiplier)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?
-        .checked_div(anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    let points_amount = lamports_to_points(
+        sol_amount,
+        platform_state.sol_to_points_ratio,
+        platform_state.token_decimals,
+    )?;
 
     require!(points_amount > 0, LoyaltyError::InvalidAmount);
 
-    // Check max supply
-    let new_supply = platform_state
-        .current_supply
-        .checked_add(points_amount)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
-
-    require!(
-        new_supply <= platform_state.max_supply,
-        LoyaltyError::DepositExceedsMaxSupply
-    );
+    // Enforce max supply and merchant allowance
+    check_mint_limits(platform_state, merchant_record, points_amount)?;
 
     // STEP 1: Transfer SOL from merchant to protocol treasury
     anchor_lang::solana_program::program::invoke(
@@ -133,19 +127,17 @@ pub fn handler(
     )?;
 
     // Update state
-    platform_state.current_supply = new_supply;
-
-    merchant_record.total_minted = merchant_record
-        .total_minted
-        .checked_add(points_amount)
+    record_mint(platform_state, merchant_record, points_amount)?;
+    platform_state.total_sol_deposited = platform_state
+        .total_sol_deposited
+        .checked_add(sol_amount)
         .ok_or(LoyaltyError::ArithmeticOverflow)?;
 
     msg!(
-        "Merchant {} deposited {} lamports ({} SOL), received {} loyalty points",
+        "Merchant {} deposited {} lamports, received {} loyalty points",
         ctx.accounts.merchant.key(),
         sol_amount,
-        sol_amount as f64 / 1_000_000_000.0,
-        points_amount / 1_000_000
+        format_points(points_amount, platform_state.token_decimals)
     );
 
     emit!(SolDeposited {
@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::LoyaltyError;
+use crate::math::{format_points, tokens_to_points};
+use crate::policy::{check_mint_limits, record_mint};
+use crate::state::{MerchantRecord, PaymentMint, PlatformState, Treasury};
+
+#[derive(Accounts)]
+pub struct DepositToken<'info> {
+    /// The merchant depositing payment tokens
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Protocol treasury PDA - owns the account receiving the deposit
+    #[account(
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// Platform state - for mint authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_MINT | PlatformState::PAUSE_DEPOSIT) @ LoyaltyError::OperationPaused
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// Allowlist entry for the deposited mint - source of the ratio
+    #[account(
+        mut,
+        seeds = [PaymentMint::SEED, payment_token_mint.key().as_ref()],
+        bump = payment_mint.bump,
+        constraint = payment_mint.is_enabled @ LoyaltyError::PaymentMintDisabled
+    )]
+    pub payment_mint: Account<'info, PaymentMint>,
+
+    /// The SPL mint being deposited
+    pub payment_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Merchant's token account for the payment mint
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = merchant,
+        associated_token::token_program = payment_token_program
+    )]
+    pub merchant_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Treasury's token account for the payment mint
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = protocol_treasury,
+        associated_token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The loyalty token mint
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Merchant's associated token account for loyalty tokens
+    #[account(
+        init_if_needed,
+        payer = merchant,
+        associated_token::mint = token_mint,
+        associated_token::authority = merchant,
+        associated_token::token_program = token_program
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DepositToken>, token_amount: u64) -> Result<()> {
+    require!(token_amount > 0, LoyaltyError::InvalidAmount);
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    let payment_mint = &mut ctx.accounts.payment_mint;
+
+    // points = token_amount * points_per_token * 10^points_decimals / 10^token_decimals
+    let points_amount = tokens_to_points(
+        token_amount,
+        payment_mint.points_per_token,
+        platform_state.token_decimals,
+        payment_mint.decimals,
+    )?;
+
+    require!(points_amount > 0, LoyaltyError::InvalidAmount);
+
+    // Enforce max supply and merchant allowance
+    check_mint_limits(platform_state, merchant_record, points_amount)?;
+
+    // STEP 1: Transfer payment tokens from merchant to the treasury's account
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.payment_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.merchant_payment_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to: ctx.accounts.treasury_payment_account.to_account_info(),
+                authority: ctx.accounts.merchant.to_account_info(),
+            },
+        ),
+        token_amount,
+        payment_mint.decimals,
+    )?;
+
+    msg!("Merchant deposited {} units of {} to treasury", token_amount, payment_mint.mint);
+
+    // STEP 2: Mint loyalty points to merchant's token account
+    let seeds = &[
+        PlatformState::SEED,
+        &[platform_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.merchant_token_account.to_account_info(),
+                authority: platform_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        points_amount,
+    )?;
+
+    // Update state
+    record_mint(platform_state, merchant_record, points_amount)?;
+    payment_mint.total_deposited = payment_mint
+        .total_deposited
+        .checked_add(token_amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Merchant {} deposited {} tokens, received {} loyalty points",
+        ctx.accounts.merchant.key(),
+        token_amount,
+        format_points(points_amount, platform_state.token_decimals)
+    );
+
+    emit!(TokenDeposited {
+        merchant: ctx.accounts.merchant.key(),
+        payment_mint: payment_mint.mint,
+        token_amount,
+        points_minted: points_amount,
+        points_per_token: payment_mint.points_per_token,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TokenDeposited {
+    pub merchant: Pubkey,
+    pub payment_mint: Pubkey,
+    pub token_amount: u64,
+    pub points_minted: u64,
+    pub points_per_token: u64,
+    pub timestamp: i64,
+}
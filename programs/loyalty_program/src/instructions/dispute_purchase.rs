@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{PlatformState, PurchaseRecord};
+
+#[derive(Accounts)]
+pub struct DisputePurchase<'info> {
+    /// The customer who made the purchase
+    pub customer: Signer<'info>,
+
+    /// Platform state - dispute window
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Purchase being disputed - confirmed or fulfilled, never disputed before and not yet final
+    #[account(
+        mut,
+        has_one = customer,
+        constraint = (purchase_record.status == PurchaseRecord::STATUS_CONFIRMED
+            || purchase_record.status == PurchaseRecord::STATUS_FULFILLED)
+            && purchase_record.disputed_at == 0 @ LoyaltyError::InvalidPurchaseStatus,
+        constraint = purchase_record.finalized_at == 0 @ LoyaltyError::PurchaseAlreadyFinal
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+}
+
+pub fn handler(ctx: Context<DisputePurchase>, reason_code: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let window_seconds = ctx.accounts.platform_state.dispute_window_seconds;
+    let purchase_record = &mut ctx.accounts.purchase_record;
+
+    // Window runs from the purchase; a window of 0 disables disputes
+    let window_end = purchase_record
+        .purchased_at
+        .checked_add(window_seconds)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    require!(
+        window_seconds > 0 && now < window_end,
+        LoyaltyError::DisputeWindowClosed
+    );
+
+    purchase_record.status = PurchaseRecord::STATUS_DISPUTED;
+    purchase_record.disputed_at = now;
+
+    msg!(
+        "Customer {} disputed purchase {} (reason {})",
+        purchase_record.customer,
+        purchase_record.key(),
+        reason_code
+    );
+
+    emit!(PurchaseDisputed {
+        purchase_record: purchase_record.key(),
+        customer: purchase_record.customer,
+        merchant: purchase_record.merchant,
+        reason_code,
+        disputed_at: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PurchaseDisputed {
+    pub purchase_record: Pubkey,
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub reason_code: u8,
+    pub disputed_at: i64,
+}
@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::LoyaltyError;
+use crate::lots::{burn_expired, platform_burnable};
+use crate::math::format_points;
+use crate::state::{ConsumerProfile, PlatformState, PointsLot};
+
+#[derive(Accounts)]
+pub struct ExpirePoints<'info> {
+    /// Anyone may run the crank - receives the closed lot's rent
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    /// Platform state - delegate authority for the burn
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// The loyalty token mint (mut because we burn tokens)
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// The consumer whose points expire
+    /// CHECK: This is the consumer's wallet address
+    pub consumer: UncheckedAccount<'info>,
+
+    /// Consumer profile - FIFO lot pointers
+    #[account(
+        mut,
+        seeds = [ConsumerProfile::SEED, consumer.key().as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Account<'info, ConsumerProfile>,
+
+    /// The consumer's oldest open lot - lots expire in FIFO order
+    #[account(
+        mut,
+        close = cranker,
+        seeds = [
+            PointsLot::SEED,
+            consumer.key().as_ref(),
+            &consumer_profile.head_lot_index.to_le_bytes()
+        ],
+        bump = points_lot.bump
+    )]
+    pub points_lot: Account<'info, PointsLot>,
+
+    /// Consumer's token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = consumer,
+        associated_token::token_program = token_program
+    )]
+    pub consumer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ExpirePoints>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.points_lot.is_expired(now),
+        LoyaltyError::PointsNotExpired
+    );
+
+    let expired_amount = ctx.accounts.points_lot.remaining;
+
+    // The lot closes either way; whatever the platform can't burn (no permanent
+    // delegate or approval, or points moved out) stays owed on the profile
+    let platform_state = &mut ctx.accounts.platform_state;
+    let burnable = platform_burnable(platform_state, &ctx.accounts.consumer_token_account);
+    let seeds = &[
+        PlatformState::SEED,
+        &[platform_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    let platform_info = platform_state.to_account_info();
+    let burn_amount = burn_expired(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.consumer_token_account,
+        &platform_info,
+        signer_seeds,
+        platform_state,
+        &mut ctx.accounts.consumer_profile,
+        expired_amount,
+        burnable,
+    )?;
+
+    // Advance the FIFO head past the closed lot
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.lot_balance = consumer_profile
+        .lot_balance
+        .checked_sub(expired_amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    consumer_profile.head_lot_index = consumer_profile
+        .head_lot_index
+        .checked_add(1)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Expired lot {} for consumer {}: {} points burned, {} still owed",
+        ctx.accounts.points_lot.index,
+        ctx.accounts.consumer.key(),
+        format_points(burn_amount, platform_state.token_decimals),
+        format_points(consumer_profile.expired_unburned, platform_state.token_decimals)
+    );
+
+    emit!(PointsExpired {
+        consumer: ctx.accounts.consumer.key(),
+        lot_index: ctx.accounts.points_lot.index,
+        expired_amount,
+        burned_amount: burn_amount,
+        unburned_amount: consumer_profile.expired_unburned,
+        cranker: ctx.accounts.cranker.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PointsExpired {
+    pub consumer: Pubkey,
+    pub lot_index: u64,
+    /// Points left in the lot when it expired
+    pub expired_amount: u64,
+    /// Points actually burned, including any owed from earlier expiries
+    pub burned_amount: u64,
+    /// Expired points still owed on the profile after this burn
+    pub unburned_amount: u64,
+    pub cranker: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{PlatformState, PurchaseRecord};
+
+#[derive(Accounts)]
+pub struct FinalizePurchase<'info> {
+    /// The merchant that sold the product
+    pub merchant: Signer<'info>,
+
+    /// Platform state - dispute window
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Purchase to mark final - the customer may close it afterwards. Only
+    /// fulfilled orders with no dispute outstanding can be finalized
+    #[account(
+        mut,
+        has_one = merchant,
+        constraint = purchase_record.finalized_at == 0 @ LoyaltyError::PurchaseAlreadyFinal,
+        constraint = purchase_record.status == PurchaseRecord::STATUS_FULFILLED @ LoyaltyError::InvalidPurchaseStatus
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+}
+
+pub fn handler(ctx: Context<FinalizePurchase>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let window_seconds = ctx.accounts.platform_state.dispute_window_seconds;
+    let purchase_record = &mut ctx.accounts.purchase_record;
+
+    // Finalizing ends the customer's right to dispute, so it waits out the window
+    let window_end = purchase_record
+        .purchased_at
+        .checked_add(window_seconds)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    require!(now >= window_end, LoyaltyError::DisputeWindowOpen);
+
+    purchase_record.finalized_at = now;
+
+    msg!(
+        "Merchant {} marked purchase {} final",
+        purchase_record.merchant,
+        purchase_record.key()
+    );
+
+    emit!(PurchaseFinalized {
+        purchase_record: purchase_record.key(),
+        customer: purchase_record.customer,
+        merchant: purchase_record.merchant,
+        finalized_at: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PurchaseFinalized {
+    pub purchase_record: Pubkey,
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub finalized_at: i64,
+}
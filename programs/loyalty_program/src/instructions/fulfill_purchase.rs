@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PurchaseRecord;
+
+#[derive(Accounts)]
+pub struct FulfillPurchase<'info> {
+    /// The merchant that sold the product
+    pub merchant: Signer<'info>,
+
+    /// Purchase being fulfilled - must still be confirmed
+    #[account(
+        mut,
+        has_one = merchant,
+        constraint = purchase_record.status == PurchaseRecord::STATUS_CONFIRMED @ LoyaltyError::InvalidPurchaseStatus
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+}
+
+pub fn handler(ctx: Context<FulfillPurchase>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    purchase_record.status = PurchaseRecord::STATUS_FULFILLED;
+    purchase_record.fulfilled_at = now;
+
+    msg!(
+        "Merchant {} fulfilled purchase {}",
+        purchase_record.merchant,
+        purchase_record.key()
+    );
+
+    emit!(PurchaseFulfilled {
+        purchase_record: purchase_record.key(),
+        customer: purchase_record.customer,
+        merchant: purchase_record.merchant,
+        fulfilled_at: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PurchaseFulfilled {
+    pub purchase_record: Pubkey,
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub fulfilled_at: i64,
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::pda::available_lamports;
+use crate::state::{MerchantDelegate, MerchantRecord};
+
+#[derive(Accounts)]
+pub struct FundMerchantDelegate<'info> {
+    /// The merchant topping up the delegate's fee deposit
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// The delegate wallet whose deposit is topped up
+    /// CHECK: This is the delegate's wallet address
+    pub delegate: UncheckedAccount<'info>,
+
+    /// Delegate PDA - receives the lamports that fund its mint fees
+    #[account(
+        mut,
+        seeds = [MerchantDelegate::SEED, merchant.key().as_ref(), delegate.key().as_ref()],
+        bump = merchant_delegate.bump,
+        has_one = merchant,
+        has_one = delegate
+    )]
+    pub merchant_delegate: Account<'info, MerchantDelegate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FundMerchantDelegate>, amount: u64) -> Result<()> {
+    require!(amount > 0, LoyaltyError::InvalidAmount);
+
+    anchor_lang::solana_program::program::invoke(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.merchant.key(),
+            &ctx.accounts.merchant_delegate.key(),
+            amount,
+        ),
+        &[
+            ctx.accounts.merchant.to_account_info(),
+            ctx.accounts.merchant_delegate.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    let fee_balance = available_lamports(&ctx.accounts.merchant_delegate.to_account_info())?;
+
+    msg!(
+        "Merchant {} topped up delegate {} with {} lamports (fee balance {})",
+        ctx.accounts.merchant.key(),
+        ctx.accounts.delegate.key(),
+        amount,
+        fee_balance
+    );
+
+    emit!(MerchantDelegateFunded {
+        merchant: ctx.accounts.merchant.key(),
+        delegate: ctx.accounts.delegate.key(),
+        amount,
+        fee_balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantDelegateFunded {
+    pub merchant: Pubkey,
+    pub delegate: Pubkey,
+    pub amount: u64,
+    /// Lamports available for mint fees after the top-up
+    pub fee_balance: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,286 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{burn, mint_to, transfer_checked, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::LoyaltyError;
+use crate::lots::{burn_expired, consume_lots, open_lot_until};
+use crate::math::{format_points, SECONDS_PER_DAY};
+use crate::state::{ConsumerProfile, PlatformState, PointsLot, Treasury};
+
+#[derive(Accounts)]
+pub struct GiftPoints<'info> {
+    /// The consumer gifting points - pays the gift fee
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// The consumer receiving the gift
+    /// CHECK: This is the recipient's wallet address
+    #[account(constraint = recipient.key() != sender.key() @ LoyaltyError::SelfGift)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Platform state - gift settings and mint authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_GIFT) @ LoyaltyError::OperationPaused,
+        constraint = !platform_state.has_mint_extension(PlatformState::EXT_NON_TRANSFERABLE)
+            || platform_state.allow_non_transferable_gifts @ LoyaltyError::NonTransferableGiftsDisabled
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Protocol treasury PDA - receives the gift fee
+    #[account(
+        mut,
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// The loyalty token mint
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Sender's token account
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = sender,
+        associated_token::token_program = token_program
+    )]
+    pub sender_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient's token account (created if needed)
+    #[account(
+        init_if_needed,
+        payer = sender,
+        associated_token::mint = token_mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Sender profile - account age, daily gift tally and open lots
+    #[account(
+        mut,
+        seeds = [ConsumerProfile::SEED, sender.key().as_ref()],
+        bump = sender_profile.bump
+    )]
+    pub sender_profile: Account<'info, ConsumerProfile>,
+
+    /// Recipient profile - tracks the gifted lot
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, recipient.key().as_ref()],
+        bump
+    )]
+    pub recipient_profile: Account<'info, ConsumerProfile>,
+
+    /// New lot holding the gift for the recipient, expiring with the sender's points
+    #[account(
+        init,
+        payer = sender,
+        space = 8 + PointsLot::INIT_SPACE,
+        seeds = [
+            PointsLot::SEED,
+            recipient.key().as_ref(),
+            &recipient_profile.next_lot_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub recipient_lot: Account<'info, PointsLot>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, GiftPoints<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, LoyaltyError::InvalidAmount);
+    require!(
+        ctx.accounts.sender_token_account.amount >= amount,
+        LoyaltyError::InsufficientBalance
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let gift_fee = ctx.accounts.platform_state.gift_fee;
+    let daily_cap = ctx.accounts.platform_state.gift_daily_cap;
+    let min_account_age = ctx.accounts.platform_state.gift_min_account_age;
+
+    // Account age counts from the sender's first earn
+    let sender_profile = &mut ctx.accounts.sender_profile;
+    require!(
+        sender_profile.first_earned_at > 0
+            && now.saturating_sub(sender_profile.first_earned_at) >= min_account_age,
+        LoyaltyError::GiftAccountTooNew
+    );
+
+    // Daily tally resets on the first gift of a new UTC day
+    let today = now / SECONDS_PER_DAY;
+    if sender_profile.gift_day != today {
+        sender_profile.gift_day = today;
+        sender_profile.gifted_today = 0;
+    }
+    let gifted_today = sender_profile
+        .gifted_today
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    require!(
+        daily_cap == 0 || gifted_today <= daily_cap,
+        LoyaltyError::GiftDailyCapExceeded
+    );
+    sender_profile.gifted_today = gifted_today;
+
+    // Gifted points leave the sender's lots oldest-first (open lots are passed as remaining accounts)
+    let lots = consume_lots(
+        sender_profile,
+        ctx.remaining_accounts,
+        amount,
+        &ctx.accounts.sender.to_account_info(),
+        now,
+    )?;
+
+    // STEP 1: Pay the gift fee to the protocol treasury
+    if gift_fee > 0 {
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.sender.key(),
+                &ctx.accounts.protocol_treasury.key(),
+                gift_fee,
+            ),
+            &[
+                ctx.accounts.sender.to_account_info(),
+                ctx.accounts.protocol_treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        let platform_state = &mut ctx.accounts.platform_state;
+        platform_state.total_fees_collected = platform_state
+            .total_fees_collected
+            .checked_add(gift_fee)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    }
+
+    // Expired lots reached by this spend are burned with the sender's signature
+    let expired_burned = burn_expired(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.sender_token_account,
+        &ctx.accounts.sender.to_account_info(),
+        &[],
+        &mut ctx.accounts.platform_state,
+        &mut ctx.accounts.sender_profile,
+        lots.expired,
+        ctx.accounts.sender_token_account.amount,
+    )?;
+    require!(
+        ctx.accounts.sender_token_account.amount - expired_burned >= amount,
+        LoyaltyError::InsufficientBalance
+    );
+
+    // STEP 2: Move the points to the recipient
+    if ctx.accounts.platform_state.has_mint_extension(PlatformState::EXT_NON_TRANSFERABLE) {
+        // Non-transferable points are burned from the sender and re-minted to the recipient
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.sender_token_account.to_account_info(),
+                    authority: ctx.accounts.sender.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let seeds = &[
+            PlatformState::SEED,
+            &[ctx.accounts.platform_state.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.platform_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    } else {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.sender_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: ctx.accounts.sender.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
+
+    // The recipient's lot keeps the earliest expiry of the lots the gift came from
+    let recipient_profile = &mut ctx.accounts.recipient_profile;
+    recipient_profile.ensure_initialized(ctx.accounts.recipient.key(), ctx.bumps.recipient_profile);
+    open_lot_until(
+        recipient_profile,
+        &mut ctx.accounts.recipient_lot,
+        ctx.bumps.recipient_lot,
+        amount,
+        lots.earliest_expiry,
+        now,
+    )?;
+
+    msg!(
+        "Consumer {} gifted {} points to {} (fee {} lamports)",
+        ctx.accounts.sender.key(),
+        format_points(amount, ctx.accounts.platform_state.token_decimals),
+        ctx.accounts.recipient.key(),
+        gift_fee
+    );
+
+    emit!(PointsGifted {
+        sender: ctx.accounts.sender.key(),
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+        fee_paid: gift_fee,
+        gifted_today,
+        lot_index: ctx.accounts.recipient_lot.index,
+        expires_at: ctx.accounts.recipient_lot.expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PointsGifted {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub fee_paid: u64,
+    pub gifted_today: u64,
+    /// Recipient lot holding the gift
+    pub lot_index: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
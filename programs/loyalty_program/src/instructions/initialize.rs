@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token};
+use anchor_spl::token_interface::TokenInterface;
 
 use crate::errors::LoyaltyError;
-use crate::state::PlatformState;
-
+use crate::loyalty_mint::create_loyalty_mint;
+use crate::math::MAX_DECIMALS;
+use crate::state::{PlatformState, TierConfig, Treasury, VolumeFeeTier};
 
 #[derive(Accounts)]
 pub struct InitializePlatform<'info> {
@@ -11,10 +12,6 @@ pub struct InitializePlatform<'info> {
     #[account(mut)]
     pub admin: Signer<'info>,
 
-    /// Protocol treasury for collecting fees
-    /// CHECK: This is the treasury wallet address
-    pub protocol_treasury: AccountInfo<'info>,
-
     /// Platform state PDA - stores global config
     #[account(
         init,
@@ -25,19 +22,26 @@ pub struct InitializePlatform<'info> {
     )]
     pub platform_state: Account<'info, PlatformState>,
 
-    /// The SPL token mint for loyalty points
+    /// Protocol treasury PDA - program-owned vault for fees and deposits
     #[account(
         init,
         payer = admin,
-        mint::decimals = 6,
-        mint::authority = platform_state,
-        mint::freeze_authority = platform_state,
+        space = 8 + Treasury::INIT_SPACE,
+        seeds = [Treasury::SEED],
+        bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// The loyalty token mint, created in the handler under `token_program`
+    /// CHECK: PDA verified by seeds; created and initialized by `create_loyalty_mint`
+    #[account(
+        mut,
         seeds = [b"loyalty_mint"],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: UncheckedAccount<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
@@ -49,9 +53,10 @@ pub fn handler(
     base_mint_fee: u64,
     fee_rate_per_thousand: u64,
     sol_to_points_ratio: u64,
+    mint_extensions: u8,
 ) -> Result<()> {
     require!(
-        token_decimals <= 9,
+        token_decimals <= MAX_DECIMALS,
         LoyaltyError::InvalidDecimals
     );
     require!(
@@ -59,6 +64,17 @@ pub fn handler(
         LoyaltyError::InvalidRatio
     );
 
+    create_loyalty_mint(
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        ctx.bumps.token_mint,
+        &ctx.accounts.platform_state.key(),
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        token_decimals,
+        mint_extensions,
+    )?;
+
     let platform_state = &mut ctx.accounts.platform_state;
 
     platform_state.admin = ctx.accounts.admin.key();
@@ -72,14 +88,42 @@ pub fn handler(
     platform_state.base_mint_fee = base_mint_fee;
     platform_state.fee_rate_per_thousand = fee_rate_per_thousand;
     platform_state.total_fees_collected = 0;
+    platform_state.total_sol_deposited = 0;
+    platform_state.total_withdrawn = 0;
     platform_state.sol_to_points_ratio = sol_to_points_ratio;
     platform_state.bump = ctx.bumps.platform_state;
 
+    ctx.accounts.protocol_treasury.bump = ctx.bumps.protocol_treasury;
+    platform_state.paused_operations = 0;
+    platform_state.pause_reason = 0;
+    platform_state.pending_admin = None;
+    platform_state.points_expiry_seconds = 0;
+    platform_state.tier_config = TierConfig::DISABLED;
+    platform_state.referrer_bonus = 0;
+    platform_state.referee_bonus = 0;
+    platform_state.buyback_ratio = 0;
+    platform_state.cash_out_spread_bps = 0;
+    platform_state.total_cashed_out = 0;
+    platform_state.mint_fee_bps = 0;
+    platform_state.volume_fee_tiers = [VolumeFeeTier::default(); 3];
+    platform_state.mint_extensions = mint_extensions;
+    platform_state.gift_fee = 0;
+    platform_state.gift_daily_cap = 0;
+    platform_state.gift_min_account_age = 0;
+    platform_state.purchase_retention_seconds = 0;
+    platform_state.dispute_window_seconds = 0;
+    platform_state.allow_non_transferable_gifts = false;
+
     msg!(
         "Platform initialized by admin: {}",
         ctx.accounts.admin.key()
     );
-    msg!("Token mint created: {}", ctx.accounts.token_mint.key());
+    msg!(
+        "Token mint created: {} (program {}, extensions {:#05b})",
+        ctx.accounts.token_mint.key(),
+        ctx.accounts.token_program.key(),
+        mint_extensions
+    );
     msg!("Protocol treasury: {}", ctx.accounts.protocol_treasury.key());
     msg!("Max supply: {}, Decimals: {}", max_supply, token_decimals);
     msg!("Base mint fee: {} lamports", base_mint_fee);
@@ -95,6 +139,8 @@ pub fn handler(
         base_mint_fee,
         fee_rate_per_thousand,
         sol_to_points_ratio,
+        token_program: ctx.accounts.token_program.key(),
+        mint_extensions,
     });
 
     Ok(())
@@ -110,4 +156,6 @@ pub struct PlatformInitialized {
     pub base_mint_fee: u64,
     pub fee_rate_per_thousand: u64,
     pub sol_to_points_ratio: u64,
+    pub token_program: Pubkey,
+    pub mint_extensions: u8,
 }
@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::errors::LoyaltyError;
+use crate::math::{apply_bps, format_points, points_to_lamports};
+use crate::pda::available_lamports;
+use crate::state::{MerchantRecord, PlatformState, Treasury};
+
+#[derive(Accounts)]
+pub struct MerchantCashOut<'info> {
+    /// The merchant cashing out points
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Protocol treasury PDA - pays out the lamports
+    #[account(
+        mut,
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// Platform state - buyback rate and spread
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_CASH_OUT) @ LoyaltyError::OperationPaused
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// The loyalty token mint (mut because we burn tokens)
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Merchant's associated token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = merchant,
+        associated_token::token_program = token_program
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<MerchantCashOut>, points_amount: u64) -> Result<()> {
+    require!(points_amount > 0, LoyaltyError::InvalidAmount);
+    require!(
+        ctx.accounts.merchant_token_account.amount >= points_amount,
+        LoyaltyError::InsufficientBalance
+    );
+
+    // Only points customers redeemed here can be cashed out, never self-minted ones
+    let merchant_record = &ctx.accounts.merchant_record;
+    let cash_out_allowance = merchant_record
+        .total_redeemed
+        .saturating_sub(merchant_record.points_cashed_out);
+    require!(
+        points_amount <= cash_out_allowance,
+        LoyaltyError::CashOutExceedsRedeemed
+    );
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    require!(platform_state.buyback_ratio > 0, LoyaltyError::CashOutDisabled);
+
+    // Value the points at the buyback rate and withhold the protocol spread
+    let gross_lamports = points_to_lamports(
+        points_amount,
+        platform_state.buyback_ratio,
+        platform_state.token_decimals,
+    )?;
+    let spread = apply_bps(gross_lamports, platform_state.cash_out_spread_bps)?;
+    let payout = gross_lamports - spread;
+    require!(payout > 0, LoyaltyError::InvalidAmount);
+
+    // Capped by what the treasury holds above rent-exemption
+    let treasury_info = ctx.accounts.protocol_treasury.to_account_info();
+    let available = available_lamports(&treasury_info)?;
+    require!(
+        payout <= available,
+        LoyaltyError::InsufficientTreasuryBalance
+    );
+
+    // STEP 1: Burn the merchant's points
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                from: ctx.accounts.merchant_token_account.to_account_info(),
+                authority: ctx.accounts.merchant.to_account_info(),
+            },
+        ),
+        points_amount,
+    )?;
+
+    // STEP 2: Pay the merchant from the program-owned treasury
+    treasury_info.sub_lamports(payout)?;
+    ctx.accounts.merchant.add_lamports(payout)?;
+
+    platform_state.current_supply = platform_state
+        .current_supply
+        .checked_sub(points_amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    platform_state.total_cashed_out = platform_state
+        .total_cashed_out
+        .checked_add(payout)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    merchant_record.total_cashed_out = merchant_record
+        .total_cashed_out
+        .checked_add(payout)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    merchant_record.points_cashed_out = merchant_record
+        .points_cashed_out
+        .checked_add(points_amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Merchant {} cashed out {} points for {} lamports (spread {} lamports)",
+        ctx.accounts.merchant.key(),
+        format_points(points_amount, platform_state.token_decimals),
+        payout,
+        spread
+    );
+
+    emit!(MerchantCashedOut {
+        merchant: ctx.accounts.merchant.key(),
+        points_burned: points_amount,
+        lamports_paid: payout,
+        spread_lamports: spread,
+        buyback_ratio: platform_state.buyback_ratio,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantCashedOut {
+    pub merchant: Pubkey,
+    pub points_burned: u64,
+    pub lamports_paid: u64,
+    /// Lamports withheld in the treasury as the protocol spread
+    pub spread_lamports: u64,
+    pub buyback_ratio: u64,
+    pub timestamp: i64,
+}
@@ -1,30 +1,46 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::associated_token::{
+    create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+};
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenInterface};
 
+use crate::campaigns::take_campaign_bonus;
 use crate::errors::LoyaltyError;
-use crate::state::{MerchantRecord, PlatformState};
+use crate::fees::mint_fee;
+use crate::lots::open_lot;
+use crate::math::format_points;
+use crate::pda::available_lamports;
+use crate::policy::{check_mint_limits, record_delegate_mint, record_mint};
+use crate::receipts::create_mint_receipt;
+use crate::state::{Campaign, ConsumerProfile, MerchantDelegate, MerchantRecord, PlatformState, PointsLot, Treasury};
+use crate::tiers::{boosted_reward, record_earned};
 
 #[derive(Accounts)]
 pub struct MintPoints<'info> {
-    /// The authorized merchant issuing points
+    /// The merchant, or one of its delegates, issuing points - pays account rent
+    /// (delegates get it back from the merchant-funded deposit)
     #[account(mut)]
-    pub merchant: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    /// Protocol treasury - receives minting fees
-    /// CHECK: This is validated against platform_state
+    /// The authorized merchant the points are issued for
+    /// CHECK: Verified through the merchant record PDA seeds
+    pub merchant: UncheckedAccount<'info>,
+
+    /// Protocol treasury PDA - receives minting fees
     #[account(
         mut,
-        constraint = protocol_treasury.key() == platform_state.protocol_treasury @ LoyaltyError::InvalidTreasury
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
     )]
-    pub protocol_treasury: AccountInfo<'info>,
+    pub protocol_treasury: Account<'info, Treasury>,
 
     /// Platform state - for mint authority
     #[account(
         mut,
         seeds = [PlatformState::SEED],
         bump = platform_state.bump,
-        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_MINT) @ LoyaltyError::OperationPaused
     )]
     pub platform_state: Account<'info, PlatformState>,
 
@@ -33,7 +49,8 @@ pub struct MintPoints<'info> {
         mut,
         seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
         bump = merchant_record.bump,
-        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
     )]
     pub merchant_record: Account<'info, MerchantRecord>,
 
@@ -43,22 +60,72 @@ pub struct MintPoints<'info> {
         seeds = [b"loyalty_mint"],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     /// The consumer receiving points
     /// CHECK: This is the consumer's wallet address
     pub consumer: UncheckedAccount<'info>,
 
-    /// Consumer's associated token account for loyalty tokens
+    /// Consumer's associated token account for loyalty tokens - created if missing
+    /// CHECK: address pinned to the consumer's ATA; created in the handler so the
+    /// rent a delegate fronts for it can be paid back
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &consumer.key(),
+            &token_mint.key(),
+            &token_program.key()
+        )
+    )]
+    pub consumer_token_account: UncheckedAccount<'info>,
+
+    /// Consumer profile - tracks the consumer's points lots
     #[account(
         init_if_needed,
-        payer = merchant,
-        associated_token::mint = token_mint,
-        associated_token::authority = consumer
+        payer = authority,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, consumer.key().as_ref()],
+        bump
     )]
-    pub consumer_token_account: Account<'info, TokenAccount>,
+    pub consumer_profile: Account<'info, ConsumerProfile>,
 
-    pub token_program: Program<'info, Token>,
+    /// New points lot recording this issuance and its expiry
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PointsLot::INIT_SPACE,
+        seeds = [
+            PointsLot::SEED,
+            consumer.key().as_ref(),
+            &consumer_profile.next_lot_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub points_lot: Account<'info, PointsLot>,
+
+    /// Optional merchant campaign adding bonus points
+    #[account(
+        mut,
+        seeds = [Campaign::SEED, merchant.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump,
+        has_one = merchant
+    )]
+    pub campaign: Option<Account<'info, Campaign>>,
+
+    /// Delegate PDA - required when `authority` is not the merchant
+    #[account(
+        mut,
+        seeds = [MerchantDelegate::SEED, merchant.key().as_ref(), authority.key().as_ref()],
+        bump = merchant_delegate.bump
+    )]
+    pub merchant_delegate: Option<Account<'info, MerchantDelegate>>,
+
+    /// Optional receipt PDA making the mint idempotent per purchase reference
+    /// CHECK: Address and emptiness are verified in `create_mint_receipt`
+    #[account(mut)]
+    pub mint_receipt: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -74,60 +141,130 @@ pub fn handler(
         LoyaltyError::ReferenceTooLong
     );
 
+    // Accounts created here, whose rent a delegate is paid back for below
+    let profile_created = ctx.accounts.consumer_profile.consumer == Pubkey::default();
+    let token_account_created = ctx.accounts.consumer_token_account.data_is_empty();
+    if token_account_created {
+        create(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.authority.to_account_info(),
+                associated_token: ctx.accounts.consumer_token_account.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+    }
+
     let platform_state = &mut ctx.accounts.platform_state;
     let merchant_record = &mut ctx.accounts.merchant_record;
 
-    // Calculate protocol fee (CRITICAL: Must be paid before minting)
-    let base_fee = platform_state.base_mint_fee;
-    let rate = platform_state.fee_rate_per_thousand;
-    let points_in_thousands = (amount + 999) / 1000; // Round up
-    let variable_fee = points_in_thousands.checked_mul(rate)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
-    let total_fee = base_fee.checked_add(variable_fee)
+    // Apply the consumer's tier multiplier to the merchant's base amount
+    let base_amount = amount;
+    let tier = ctx.accounts.consumer_profile.tier;
+    let tier_amount = boosted_reward(&ctx.accounts.consumer_profile, &platform_state.tier_config, base_amount)?;
+
+    // Add any active campaign bonus on top, debited from the campaign budget
+    let now = Clock::get()?.unix_timestamp;
+    let campaign_bonus = match ctx.accounts.campaign.as_deref_mut() {
+        Some(campaign) => take_campaign_bonus(campaign, base_amount, None, now)?,
+        None => 0,
+    };
+    let amount = tier_amount
+        .checked_add(campaign_bonus)
         .ok_or(LoyaltyError::ArithmeticOverflow)?;
 
-    msg!("Protocol fee calculation: base={}, rate={}, points={}, total_fee={}",
-        base_fee, rate, amount / 1_000_000, total_fee);
+    // Delegates mint within their own caps, counted on the requested base amount
+    // so tier and campaign boosts don't eat into them; the merchant is unrestricted
+    let delegate = match ctx.accounts.merchant_delegate.as_deref_mut() {
+        Some(merchant_delegate) => {
+            record_delegate_mint(merchant_delegate, base_amount, now)?;
+            Some(merchant_delegate.delegate)
+        }
+        None => {
+            require_keys_eq!(
+                ctx.accounts.authority.key(),
+                ctx.accounts.merchant.key(),
+                LoyaltyError::UnauthorizedDelegate
+            );
+            None
+        }
+    };
 
-    // ATOMIC STEP 1: Transfer protocol fee from merchant to treasury
-    anchor_lang::solana_program::program::invoke(
-        &anchor_lang::solana_program::system_instruction::transfer(
-            &ctx.accounts.merchant.key(),
-            &ctx.accounts.protocol_treasury.key(),
-            total_fee,
-        ),
-        &[
-            ctx.accounts.merchant.to_account_info(),
-            ctx.accounts.protocol_treasury.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-        ],
-    )?;
+    // A receipt for this reference means the purchase was already credited
+    if let Some(mint_receipt) = ctx.accounts.mint_receipt.as_ref() {
+        create_mint_receipt(
+            &mint_receipt.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.merchant.key(),
+            ctx.accounts.consumer.key(),
+            &purchase_reference,
+            amount,
+            now,
+        )?;
+    }
 
-    msg!("Protocol fee paid: {} lamports", total_fee);
+    // Enforce max supply and merchant allowance before charging the fee
+    check_mint_limits(platform_state, merchant_record, amount)?;
 
-    // Check mint allowance
-    if merchant_record.mint_allowance > 0 {
-        let remaining_allowance = merchant_record
-            .mint_allowance
-            .checked_sub(merchant_record.total_minted)
-            .ok_or(LoyaltyError::ArithmeticOverflow)?;
-
-        require!(
-            amount <= remaining_allowance,
-            LoyaltyError::ExceedsMintAllowance
-        );
-    }
+    // Calculate protocol fee (CRITICAL: Must be paid before minting)
+    let total_fee = mint_fee(platform_state, merchant_record, amount)?;
 
-    // Check max supply
-    let new_supply = platform_state
-        .current_supply
-        .checked_add(amount)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    msg!("Protocol fee calculation: points={}, total_fee={}",
+        format_points(amount, platform_state.token_decimals), total_fee);
 
-    require!(
-        new_supply <= platform_state.max_supply,
-        LoyaltyError::ExceedsMaxSupply
-    );
+    // ATOMIC STEP 1: Transfer protocol fee from merchant to treasury
+    let mut rent_reimbursed = 0;
+    match ctx.accounts.merchant_delegate.as_ref() {
+        // Delegates pay from the fee deposit the merchant funded their PDA with,
+        // which also pays back the rent the delegate fronted for new accounts
+        Some(merchant_delegate) => {
+            let rent = Rent::get()?;
+            let rent_of = |account: AccountInfo| rent.minimum_balance(account.data_len());
+            rent_reimbursed = rent_of(ctx.accounts.points_lot.to_account_info());
+            if profile_created {
+                rent_reimbursed += rent_of(ctx.accounts.consumer_profile.to_account_info());
+            }
+            if token_account_created {
+                rent_reimbursed += rent_of(ctx.accounts.consumer_token_account.to_account_info());
+            }
+            // A receipt is always new - an existing one fails the mint
+            if let Some(mint_receipt) = ctx.accounts.mint_receipt.as_ref() {
+                rent_reimbursed += rent_of(mint_receipt.to_account_info());
+            }
+
+            let delegate_info = merchant_delegate.to_account_info();
+            let total_charge = total_fee
+                .checked_add(rent_reimbursed)
+                .ok_or(LoyaltyError::ArithmeticOverflow)?;
+            require!(
+                available_lamports(&delegate_info)? >= total_charge,
+                LoyaltyError::InsufficientDelegateFeeBalance
+            );
+            delegate_info.sub_lamports(total_charge)?;
+            ctx.accounts.protocol_treasury.add_lamports(total_fee)?;
+            ctx.accounts.authority.add_lamports(rent_reimbursed)?;
+        }
+        None => {
+            anchor_lang::solana_program::program::invoke(
+                &anchor_lang::solana_program::system_instruction::transfer(
+                    &ctx.accounts.authority.key(),
+                    &ctx.accounts.protocol_treasury.key(),
+                    total_fee,
+                ),
+                &[
+                    ctx.accounts.authority.to_account_info(),
+                    ctx.accounts.protocol_treasury.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
+    msg!("Protocol fee paid: {} lamports", total_fee);
 
     // ATOMIC STEP 2: Mint tokens using PDA authority (only after fee paid)
     let seeds = &[
@@ -150,35 +287,52 @@ pub fn handler(
     )?;
 
     // Update state
-    platform_state.current_supply = new_supply;
+    record_mint(platform_state, merchant_record, amount)?;
     platform_state.total_fees_collected = platform_state.total_fees_collected
         .checked_add(total_fee)
         .ok_or(LoyaltyError::ArithmeticOverflow)?;
     
-    merchant_record.total_minted = merchant_record
-        .total_minted
-        .checked_add(amount)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
-    
     merchant_record.total_fees_paid = merchant_record
         .total_fees_paid
         .checked_add(total_fee)
         .ok_or(LoyaltyError::ArithmeticOverflow)?;
 
+    // Record the issuance as an expiring lot
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(ctx.accounts.consumer.key(), ctx.bumps.consumer_profile);
+    open_lot(
+        consumer_profile,
+        &mut ctx.accounts.points_lot,
+        ctx.bumps.points_lot,
+        amount,
+        platform_state.points_expiry_seconds,
+        now,
+    )?;
+    record_earned(consumer_profile, &platform_state.tier_config, amount, now)?;
+
     msg!(
-        "Minted {} points to consumer {} by merchant {}",
-        amount / 1_000_000,
+        "Minted {} points to consumer {} by merchant {} (signed by {})",
+        format_points(amount, platform_state.token_decimals),
         ctx.accounts.consumer.key(),
-        ctx.accounts.merchant.key()
+        ctx.accounts.merchant.key(),
+        ctx.accounts.authority.key()
     );
 
     emit!(PointsIssued {
         merchant: ctx.accounts.merchant.key(),
+        delegate,
         consumer: ctx.accounts.consumer.key(),
+        base_amount,
         amount,
+        tier,
+        campaign: ctx.accounts.campaign.as_ref().map(|campaign| campaign.key()),
+        campaign_bonus,
         fee_paid: total_fee,
+        rent_reimbursed,
         purchase_reference,
-        timestamp: Clock::get()?.unix_timestamp,
+        lot_index: ctx.accounts.points_lot.index,
+        expires_at: ctx.accounts.points_lot.expires_at,
+        timestamp: now,
     });
 
     Ok(())
@@ -187,9 +341,24 @@ pub fn handler(
 #[event]
 pub struct PointsIssued {
     pub merchant: Pubkey,
+    /// Delegate that signed the mint (None = the merchant itself)
+    pub delegate: Option<Pubkey>,
     pub consumer: Pubkey,
+    /// Amount requested by the merchant, before the tier multiplier
+    pub base_amount: u64,
+    /// Amount actually minted
     pub amount: u64,
+    /// Consumer tier that set the multiplier
+    pub tier: u8,
+    /// Campaign that added bonus points, if one was passed
+    pub campaign: Option<Pubkey>,
+    /// Bonus points from the campaign (included in `amount`)
+    pub campaign_bonus: u64,
     pub fee_paid: u64,
+    /// Rent paid back to a delegate signer out of the merchant's deposit
+    pub rent_reimbursed: u64,
     pub purchase_reference: String,
+    pub lot_index: u64,
+    pub expires_at: i64,
     pub timestamp: i64,
 }
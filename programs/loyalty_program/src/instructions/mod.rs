@@ -1,19 +1,105 @@
+#![allow(ambiguous_glob_reexports)]
+
+pub mod accept_admin;
+pub mod batch_mint_points;
+pub mod cancel_admin_transfer;
+pub mod close_campaign;
+pub mod close_mint_receipt;
+pub mod close_purchase_record;
+pub mod create_campaign;
+pub mod create_merchant_delegate;
+pub mod create_product;
 pub mod deposit_sol;
+pub mod deposit_token;
+pub mod dispute_purchase;
+pub mod expire_points;
+pub mod finalize_purchase;
+pub mod fulfill_purchase;
+pub mod fund_merchant_delegate;
+pub mod gift_points;
 pub mod initialize;
+pub mod merchant_cash_out;
 pub mod mint_points;
+pub mod pause_platform;
+pub mod propose_admin;
+pub mod purchase_mixed;
 pub mod purchase_product;
 pub mod purchase_with_points;
+pub mod purchase_with_token;
 pub mod redeem_points;
+pub mod refund_purchase;
 pub mod register_merchant;
+pub mod register_referrer;
+pub mod reinstate_merchant;
+pub mod resolve_dispute;
 pub mod revoke_merchant;
+pub mod revoke_merchant_delegate;
+pub mod set_buyback_config;
+pub mod set_expiry_delegate;
+pub mod set_fee_config;
+pub mod set_gift_config;
+pub mod set_merchant_allowance;
+pub mod set_merchant_fee_override;
+pub mod set_paused_operations;
+pub mod set_payment_mint;
+pub mod set_product_token_price;
+pub mod set_retention_config;
+pub mod set_tier_config;
 pub mod set_token_metadata;
+pub mod unpause_platform;
+pub mod update_platform_config;
+pub mod update_product;
+pub mod withdraw_treasury;
+pub mod withdraw_treasury_tokens;
 
+pub use accept_admin::*;
+pub use batch_mint_points::*;
+pub use cancel_admin_transfer::*;
+pub use close_campaign::*;
+pub use close_mint_receipt::*;
+pub use close_purchase_record::*;
+pub use create_campaign::*;
+pub use create_merchant_delegate::*;
+pub use create_product::*;
 pub use deposit_sol::*;
+pub use deposit_token::*;
+pub use dispute_purchase::*;
+pub use expire_points::*;
+pub use finalize_purchase::*;
+pub use fulfill_purchase::*;
+pub use fund_merchant_delegate::*;
+pub use gift_points::*;
 pub use initialize::*;
+pub use merchant_cash_out::*;
 pub use mint_points::*;
+pub use pause_platform::*;
+pub use propose_admin::*;
+pub use purchase_mixed::*;
 pub use purchase_product::*;
 pub use purchase_with_points::*;
+pub use purchase_with_token::*;
 pub use redeem_points::*;
+pub use refund_purchase::*;
 pub use register_merchant::*;
+pub use register_referrer::*;
+pub use reinstate_merchant::*;
+pub use resolve_dispute::*;
 pub use revoke_merchant::*;
+pub use revoke_merchant_delegate::*;
+pub use set_buyback_config::*;
+pub use set_expiry_delegate::*;
+pub use set_fee_config::*;
+pub use set_gift_config::*;
+pub use set_merchant_allowance::*;
+pub use set_merchant_fee_override::*;
+pub use set_paused_operations::*;
+pub use set_payment_mint::*;
+pub use set_product_token_price::*;
+pub use set_retention_config::*;
+pub use set_tier_config::*;
 pub use set_token_metadata::*;
+pub use unpause_platform::*;
+pub use update_platform_config::*;
+pub use update_product::*;
+pub use withdraw_treasury::*;
+pub use withdraw_treasury_tokens::*;
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct PausePlatform<'info> {
+    /// Platform admin - only they can pause the platform
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformAlreadyPaused
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<PausePlatform>, reason_code: u8) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    platform_state.is_active = false;
+    platform_state.pause_reason = reason_code;
+
+    msg!(
+        "Platform paused by admin {} with reason code {}",
+        ctx.accounts.admin.key(),
+        reason_code
+    );
+
+    emit!(PlatformPaused {
+        admin: ctx.accounts.admin.key(),
+        reason_code,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PlatformPaused {
+    pub admin: Pubkey,
+    pub reason_code: u8,
+    pub timestamp: i64,
+}
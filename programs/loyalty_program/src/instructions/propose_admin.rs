@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    /// Current platform admin
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    require!(
+        new_admin != Pubkey::default() && new_admin != ctx.accounts.admin.key(),
+        LoyaltyError::InvalidAdmin
+    );
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    platform_state.pending_admin = Some(new_admin);
+
+    msg!(
+        "Admin transfer proposed: {} -> {}",
+        ctx.accounts.admin.key(),
+        new_admin
+    );
+
+    emit!(AdminTransferProposed {
+        admin: ctx.accounts.admin.key(),
+        pending_admin: new_admin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::errors::LoyaltyError;
+use crate::lots::{burn_expired, consume_lots};
+use crate::math::{format_points, points_to_lamports};
+use crate::state::{ConsumerProfile, MerchantRecord, PlatformState, Product, PurchaseRecord};
+use crate::tiers::record_spent;
+
+#[derive(Accounts)]
+#[instruction(product_id_hash: [u8; 32])]
+pub struct PurchaseProductMixed<'info> {
+    /// The customer paying with points plus SOL
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// The merchant's wallet - receives the SOL remainder
+    /// CHECK: This is the merchant's wallet address
+    #[account(mut)]
+    pub merchant: AccountInfo<'info>,
+
+    /// Platform state
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_REDEEM | PlatformState::PAUSE_PURCHASE) @ LoyaltyError::OperationPaused
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// Product listing - source of the SOL price
+    #[account(
+        seeds = [Product::SEED, merchant.key().as_ref(), &product_id_hash],
+        bump = product.bump,
+        constraint = product.is_available @ LoyaltyError::ProductUnavailable
+    )]
+    pub product: Account<'info, Product>,
+
+    /// The loyalty token mint (mut because we burn tokens)
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Customer's token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = customer,
+        associated_token::token_program = token_program
+    )]
+    pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Consumer profile - open lots are consumed FIFO on spend
+    #[account(
+        init_if_needed,
+        payer = customer,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, customer.key().as_ref()],
+        bump
+    )]
+    pub consumer_profile: Account<'info, ConsumerProfile>,
+
+    /// Purchase record PDA - indexed by the customer's purchase counter
+    #[account(
+        init,
+        payer = customer,
+        space = 8 + PurchaseRecord::INIT_SPACE,
+        seeds = [
+            PurchaseRecord::SEED,
+            customer.key().as_ref(),
+            &consumer_profile.purchase_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PurchaseProductMixed<'info>>,
+    product_id_hash: [u8; 32],
+    points_amount: u64,
+    max_price: u64,
+) -> Result<()> {
+    let price_sol = ctx.accounts.product.price_sol;
+    require!(price_sol > 0, LoyaltyError::PaymentMethodNotAccepted);
+
+    // Value the points portion at the platform ratio; the rest is paid in SOL
+    let points_value = points_to_lamports(
+        points_amount,
+        ctx.accounts.platform_state.sol_to_points_ratio,
+        ctx.accounts.platform_state.token_decimals,
+    )?;
+    require!(
+        points_amount > 0 && points_value < price_sol,
+        LoyaltyError::InvalidPaymentSplit
+    );
+    let sol_amount = price_sol - points_value;
+    // `max_price` caps the SOL remainder, the part a price change can raise
+    require!(sol_amount <= max_price, LoyaltyError::PriceAboveMax);
+
+    require!(
+        ctx.accounts.customer_token_account.amount >= points_amount,
+        LoyaltyError::InsufficientPointsBalance
+    );
+
+    // Spend expiring lots oldest-first (open lots are passed as remaining accounts)
+    let now = Clock::get()?.unix_timestamp;
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(ctx.accounts.customer.key(), ctx.bumps.consumer_profile);
+    let lots = consume_lots(
+        consumer_profile,
+        ctx.remaining_accounts,
+        points_amount,
+        &ctx.accounts.customer.to_account_info(),
+        now,
+    )?;
+    record_spent(consumer_profile, points_amount)?;
+
+    // Expired lots reached by this spend are burned with the customer's signature
+    let expired_burned = burn_expired(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.customer_token_account,
+        &ctx.accounts.customer.to_account_info(),
+        &[],
+        &mut ctx.accounts.platform_state,
+        &mut ctx.accounts.consumer_profile,
+        lots.expired,
+        ctx.accounts.customer_token_account.amount,
+    )?;
+    require!(
+        ctx.accounts.customer_token_account.amount - expired_burned >= points_amount,
+        LoyaltyError::InsufficientPointsBalance
+    );
+
+    // STEP 1: Burn the points portion
+    burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                from: ctx.accounts.customer_token_account.to_account_info(),
+                authority: ctx.accounts.customer.to_account_info(),
+            },
+        ),
+        points_amount,
+    )?;
+
+    // STEP 2: Customer pays the SOL remainder to merchant
+    anchor_lang::solana_program::program::invoke(
+        &anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.customer.key(),
+            &ctx.accounts.merchant.key(),
+            sol_amount,
+        ),
+        &[
+            ctx.accounts.customer.to_account_info(),
+            ctx.accounts.merchant.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    // STEP 3: Record both portions of the payment (split-tender purchases earn no reward)
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    purchase_record.customer = ctx.accounts.customer.key();
+    purchase_record.merchant = ctx.accounts.merchant.key();
+    purchase_record.product_id_hash = product_id_hash;
+    purchase_record.payment_type = PurchaseRecord::PAYMENT_MIXED;
+    purchase_record.payment_mint = Pubkey::default();
+    purchase_record.amount_paid = sol_amount;
+    purchase_record.points_paid = points_amount;
+    purchase_record.points_earned = 0;
+    purchase_record.purchased_at = now;
+    purchase_record.refunded_at = 0;
+    purchase_record.finalized_at = 0;
+    purchase_record.status = PurchaseRecord::STATUS_CONFIRMED;
+    purchase_record.fulfilled_at = 0;
+    purchase_record.disputed_at = 0;
+    purchase_record.resolved_at = 0;
+    purchase_record.clawback_shortfall = 0;
+    purchase_record.campaign = None;
+    purchase_record.campaign_bonus = 0;
+    purchase_record.index = ctx.accounts.consumer_profile.take_purchase_index()?;
+    purchase_record.bump = ctx.bumps.purchase_record;
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    platform_state.current_supply = platform_state
+        .current_supply
+        .checked_sub(points_amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    merchant_record.total_redeemed = merchant_record
+        .total_redeemed
+        .checked_add(points_amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Product purchased with points + SOL: customer={}, merchant={}, product_hash={:?}, points={}, lamports={}",
+        ctx.accounts.customer.key(),
+        ctx.accounts.merchant.key(),
+        &product_id_hash[..8],
+        format_points(points_amount, platform_state.token_decimals),
+        sol_amount
+    );
+
+    emit!(ProductPurchasedMixed {
+        customer: ctx.accounts.customer.key(),
+        merchant: ctx.accounts.merchant.key(),
+        product_id_hash,
+        points_spent: points_amount,
+        lamports_paid: sol_amount,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProductPurchasedMixed {
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub product_id_hash: [u8; 32],
+    pub points_spent: u64,
+    pub lamports_paid: u64,
+    pub timestamp: i64,
+}
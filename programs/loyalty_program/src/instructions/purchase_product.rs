@@ -1,12 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
 
+use crate::campaigns::take_campaign_bonus;
 use crate::errors::LoyaltyError;
-use crate::state::{MerchantRecord, PlatformState, PurchaseRecord};
+use crate::fees::mint_fee;
+use crate::lots::{create_lot_account, load_or_create_profile, open_lot, store_account};
+use crate::math::format_points;
+use crate::policy::{check_allowance, check_supply, record_mint, record_supply};
+use crate::state::{
+    Campaign, ConsumerProfile, MerchantRecord, PlatformState, PointsLot, Product, PurchaseRecord,
+    ReferralLink, Treasury,
+};
+use crate::tiers::{boosted_reward, record_earned};
 
 #[derive(Accounts)]
-#[instruction(product_id_hash: [u8; 32], price_sol: u64, loyalty_points_reward: u64, nonce: u64)]
+#[instruction(product_id_hash: [u8; 32])]
 pub struct PurchaseProductWithSol<'info> {
     /// The customer making the purchase
     #[account(mut)]
@@ -17,20 +25,21 @@ pub struct PurchaseProductWithSol<'info> {
     #[account(mut)]
     pub merchant: AccountInfo<'info>,
 
-    /// Protocol treasury - receives minting fees
-    /// CHECK: This is validated against platform_state
+    /// Protocol treasury PDA - receives minting fees
     #[account(
         mut,
-        constraint = protocol_treasury.key() == platform_state.protocol_treasury @ LoyaltyError::InvalidTreasury
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
     )]
-    pub protocol_treasury: AccountInfo<'info>,
+    pub protocol_treasury: Account<'info, Treasury>,
 
     /// Platform state
     #[account(
         mut,
         seeds = [PlatformState::SEED],
         bump = platform_state.bump,
-        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_MINT | PlatformState::PAUSE_PURCHASE) @ LoyaltyError::OperationPaused
     )]
     pub platform_state: Account<'info, PlatformState>,
 
@@ -39,11 +48,47 @@ pub struct PurchaseProductWithSol<'info> {
         mut,
         seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
         bump = merchant_record.bump,
-        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
     )]
     pub merchant_record: Account<'info, MerchantRecord>,
 
-    /// Purchase record PDA - nonce allows same customer to buy same product multiple times
+    /// Product listing - source of the price and reward
+    #[account(
+        seeds = [Product::SEED, merchant.key().as_ref(), &product_id_hash],
+        bump = product.bump,
+        constraint = product.is_available @ LoyaltyError::ProductUnavailable
+    )]
+    pub product: Account<'info, Product>,
+
+    /// The loyalty token mint
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Customer's associated token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = customer,
+        associated_token::token_program = token_program
+    )]
+    pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Consumer profile - tracks the consumer's points lots
+    #[account(
+        init_if_needed,
+        payer = customer,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, customer.key().as_ref()],
+        bump
+    )]
+    pub consumer_profile: Account<'info, ConsumerProfile>,
+
+    /// Purchase record PDA - indexed by the customer's purchase counter
     #[account(
         init,
         payer = customer,
@@ -51,46 +96,118 @@ pub struct PurchaseProductWithSol<'info> {
         seeds = [
             PurchaseRecord::SEED,
             customer.key().as_ref(),
-            &product_id_hash,
-            &nonce.to_le_bytes()
+            &consumer_profile.purchase_count.to_le_bytes()
         ],
         bump
     )]
     pub purchase_record: Account<'info, PurchaseRecord>,
 
-    /// The loyalty token mint
+    /// New points lot recording this issuance and its expiry
     #[account(
-        mut,
-        seeds = [b"loyalty_mint"],
+        init,
+        payer = customer,
+        space = 8 + PointsLot::INIT_SPACE,
+        seeds = [
+            PointsLot::SEED,
+            customer.key().as_ref(),
+            &consumer_profile.next_lot_index.to_le_bytes()
+        ],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub points_lot: Account<'info, PointsLot>,
 
-    /// Customer's associated token account for loyalty tokens
+    /// Optional merchant campaign adding bonus points
     #[account(
         mut,
-        associated_token::mint = token_mint,
-        associated_token::authority = customer
+        seeds = [Campaign::SEED, merchant.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump,
+        has_one = merchant
     )]
-    pub customer_token_account: Account<'info, TokenAccount>,
+    pub campaign: Option<Account<'info, Campaign>>,
 
-    pub token_program: Program<'info, Token>,
+    /// Customer's referral link PDA - always passed so a registered referral can't be skipped.
+    /// Pays referral bonuses on the customer's first purchase if the link exists.
+    /// CHECK: address pinned by the seeds; only read if the program owns it
+    #[account(
+        mut,
+        seeds = [ReferralLink::SEED, customer.key().as_ref()],
+        bump
+    )]
+    pub referral_link: UncheckedAccount<'info>,
+
+    /// Referrer's loyalty token account (required when a referrer bonus is paid)
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Referrer's consumer profile (required when a referrer bonus is paid, created if needed)
+    /// CHECK: checked against the link's referrer in `load_or_create_profile`
+    #[account(mut)]
+    pub referrer_profile: Option<UncheckedAccount<'info>>,
+
+    /// New lot for the referrer bonus at the referrer profile's next index
+    /// CHECK: checked against the referrer profile in `create_lot_account`
+    #[account(mut)]
+    pub referrer_lot: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<PurchaseProductWithSol>,
     product_id_hash: [u8; 32],
-    price_sol: u64,
-    loyalty_points_reward: u64,
-    _nonce: u64,
+    max_price: u64,
 ) -> Result<()> {
-    require!(price_sol > 0, LoyaltyError::InvalidAmount);
-    require!(loyalty_points_reward > 0, LoyaltyError::InvalidAmount);
+    let price_sol = ctx.accounts.product.price_sol;
+    let base_reward = ctx.accounts.product.loyalty_points_reward;
+
+    require!(price_sol > 0, LoyaltyError::PaymentMethodNotAccepted);
+    // Guards against a price change landing before the purchase
+    require!(price_sol <= max_price, LoyaltyError::PriceAboveMax);
+    require!(base_reward > 0, LoyaltyError::InvalidAmount);
 
     let platform_state = &mut ctx.accounts.platform_state;
     let merchant_record = &mut ctx.accounts.merchant_record;
 
+    // Listed reward scaled by the customer's tier multiplier
+    let tier = ctx.accounts.consumer_profile.tier;
+    let tier_reward = boosted_reward(&ctx.accounts.consumer_profile, &platform_state.tier_config, base_reward)?;
+
+    // Add any active campaign bonus for this product, debited from the campaign budget
+    let now = Clock::get()?.unix_timestamp;
+    let campaign_bonus = match ctx.accounts.campaign.as_deref_mut() {
+        Some(campaign) => take_campaign_bonus(campaign, base_reward, Some(&product_id_hash), now)?,
+        None => 0,
+    };
+    let loyalty_points_reward = tier_reward
+        .checked_add(campaign_bonus)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    // Referral bonuses are platform-funded and paid once, on the first SOL purchase
+    let referral_info = ctx.accounts.referral_link.to_account_info();
+    let mut referral_link = if referral_info.owner == &crate::ID {
+        Some(ReferralLink::try_deserialize(&mut &referral_info.try_borrow_data()?[..])?)
+    } else {
+        None
+    };
+    let first_purchase = !ctx.accounts.consumer_profile.first_purchase_done;
+    let (referee_bonus, referrer_bonus) = match &referral_link {
+        Some(_) if first_purchase => (platform_state.referee_bonus, platform_state.referrer_bonus),
+        _ => (0, 0),
+    };
+    let referral_total = referee_bonus
+        .checked_add(referrer_bonus)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    // Enforce merchant allowance for the reward and max supply for everything minted
+    check_allowance(merchant_record, loyalty_points_reward)?;
+    check_supply(
+        platform_state,
+        loyalty_points_reward
+            .checked_add(referral_total)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?,
+    )?;
+
     // STEP 1: Customer pays SOL to merchant
     anchor_lang::solana_program::program::invoke(
         &anchor_lang::solana_program::system_instruction::transfer(
@@ -108,13 +225,7 @@ pub fn handler(
     msg!("Customer paid {} lamports to merchant", price_sol);
 
     // STEP 2: Calculate and pay protocol fee for minting loyalty points
-    let base_fee = platform_state.base_mint_fee;
-    let rate = platform_state.fee_rate_per_thousand;
-    let points_in_thousands = (loyalty_points_reward + 999) / 1000;
-    let variable_fee = points_in_thousands.checked_mul(rate)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
-    let total_fee = base_fee.checked_add(variable_fee)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    let total_fee = mint_fee(platform_state, merchant_record, loyalty_points_reward)?;
 
     // Fee is paid by the customer (who is the signer), not the merchant
     if total_fee > 0 {
@@ -134,7 +245,10 @@ pub fn handler(
 
     msg!("Customer paid protocol fee: {} lamports", total_fee);
 
-    // STEP 3: Mint loyalty points to customer
+    // STEP 3: Mint loyalty points (plus any referee bonus) to customer
+    let customer_points = loyalty_points_reward
+        .checked_add(referee_bonus)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
     let seeds = &[
         PlatformState::SEED,
         &[platform_state.bump],
@@ -151,32 +265,140 @@ pub fn handler(
             },
             signer_seeds,
         ),
-        loyalty_points_reward,
+        customer_points,
+    )?;
+
+    // STEP 4: Record the reward as an expiring lot
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(ctx.accounts.customer.key(), ctx.bumps.consumer_profile);
+    open_lot(
+        consumer_profile,
+        &mut ctx.accounts.points_lot,
+        ctx.bumps.points_lot,
+        customer_points,
+        platform_state.points_expiry_seconds,
+        now,
     )?;
+    record_earned(consumer_profile, &platform_state.tier_config, customer_points, now)?;
+    consumer_profile.first_purchase_done = true;
 
     // Update records
     let purchase_record = &mut ctx.accounts.purchase_record;
     purchase_record.customer = ctx.accounts.customer.key();
     purchase_record.merchant = ctx.accounts.merchant.key();
     purchase_record.product_id_hash = product_id_hash;
-    purchase_record.payment_type = 0; // 0 = SOL
+    purchase_record.payment_type = PurchaseRecord::PAYMENT_SOL;
+    purchase_record.payment_mint = Pubkey::default();
     purchase_record.amount_paid = price_sol;
+    purchase_record.points_paid = 0;
     purchase_record.points_earned = loyalty_points_reward;
-    purchase_record.purchased_at = Clock::get()?.unix_timestamp;
+    purchase_record.purchased_at = now;
+    purchase_record.refunded_at = 0;
+    purchase_record.finalized_at = 0;
+    purchase_record.status = PurchaseRecord::STATUS_CONFIRMED;
+    purchase_record.fulfilled_at = 0;
+    purchase_record.disputed_at = 0;
+    purchase_record.resolved_at = 0;
+    purchase_record.clawback_shortfall = 0;
+    purchase_record.campaign = ctx.accounts.campaign.as_ref().map(|campaign| campaign.key());
+    purchase_record.campaign_bonus = campaign_bonus;
+    purchase_record.index = ctx.accounts.consumer_profile.take_purchase_index()?;
     purchase_record.bump = ctx.bumps.purchase_record;
 
-    platform_state.current_supply = platform_state.current_supply
-        .checked_add(loyalty_points_reward)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
-    
+    record_mint(platform_state, merchant_record, loyalty_points_reward)?;
+
+    // STEP 5: Pay the referrer and close out the referral
+    if let Some(referral_link) = referral_link.as_mut() {
+        if referral_total > 0 {
+            if referrer_bonus > 0 {
+                let referrer_token_account = ctx
+                    .accounts
+                    .referrer_token_account
+                    .as_ref()
+                    .ok_or(LoyaltyError::InvalidReferrerAccount)?;
+                require!(
+                    referrer_token_account.owner == referral_link.referrer
+                        && referrer_token_account.mint == ctx.accounts.token_mint.key(),
+                    LoyaltyError::InvalidReferrerAccount
+                );
+
+                mint_to(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        MintTo {
+                            mint: ctx.accounts.token_mint.to_account_info(),
+                            to: referrer_token_account.to_account_info(),
+                            authority: platform_state.to_account_info(),
+                        },
+                        signer_seeds,
+                    ),
+                    referrer_bonus,
+                )?;
+
+                // The referrer's bonus is an expiring lot and counts toward their tier
+                let payer = ctx.accounts.customer.to_account_info();
+                let system_program = ctx.accounts.system_program.to_account_info();
+                let referrer_profile_info = ctx
+                    .accounts
+                    .referrer_profile
+                    .as_ref()
+                    .ok_or(LoyaltyError::InvalidReferrerAccount)?;
+                let referrer_lot_info = ctx
+                    .accounts
+                    .referrer_lot
+                    .as_ref()
+                    .ok_or(LoyaltyError::InvalidReferrerAccount)?;
+                let mut referrer_profile = load_or_create_profile(
+                    referrer_profile_info,
+                    referral_link.referrer,
+                    &payer,
+                    &system_program,
+                )?;
+                let (mut referrer_lot, referrer_lot_bump) = create_lot_account(
+                    referrer_lot_info,
+                    &referrer_profile,
+                    &payer,
+                    &system_program,
+                )?;
+                open_lot(
+                    &mut referrer_profile,
+                    &mut referrer_lot,
+                    referrer_lot_bump,
+                    referrer_bonus,
+                    platform_state.points_expiry_seconds,
+                    now,
+                )?;
+                record_earned(&mut referrer_profile, &platform_state.tier_config, referrer_bonus, now)?;
+                store_account(referrer_lot_info, &referrer_lot)?;
+                store_account(referrer_profile_info, &referrer_profile)?;
+            }
+
+            record_supply(platform_state, referral_total)?;
+            referral_link.bonus_paid_at = now;
+            store_account(&referral_info, referral_link)?;
+
+            msg!(
+                "Referral bonus paid: referee={} ({}), referrer={} ({})",
+                referral_link.referee,
+                format_points(referee_bonus, platform_state.token_decimals),
+                referral_link.referrer,
+                format_points(referrer_bonus, platform_state.token_decimals)
+            );
+
+            emit!(ReferralBonusPaid {
+                referee: referral_link.referee,
+                referrer: referral_link.referrer,
+                referee_bonus,
+                referrer_bonus,
+                timestamp: now,
+            });
+        }
+    }
+
     platform_state.total_fees_collected = platform_state.total_fees_collected
         .checked_add(total_fee)
         .ok_or(LoyaltyError::ArithmeticOverflow)?;
 
-    merchant_record.total_minted = merchant_record.total_minted
-        .checked_add(loyalty_points_reward)
-        .ok_or(LoyaltyError::ArithmeticOverflow)?;
-    
     merchant_record.total_fees_paid = merchant_record.total_fees_paid
         .checked_add(total_fee)
         .ok_or(LoyaltyError::ArithmeticOverflow)?;
@@ -187,18 +409,23 @@ pub fn handler(
         ctx.accounts.merchant.key(),
         &product_id_hash[..8],
         price_sol,
-        loyalty_points_reward / 1_000_000
+        format_points(loyalty_points_reward, platform_state.token_decimals)
     );
 
     emit!(ProductPurchased {
         customer: ctx.accounts.customer.key(),
         merchant: ctx.accounts.merchant.key(),
         product_id_hash,
-        payment_type: 0,
+        payment_type: PurchaseRecord::PAYMENT_SOL,
         amount_paid: price_sol,
         points_earned: loyalty_points_reward,
+        tier,
+        campaign: ctx.accounts.campaign.as_ref().map(|campaign| campaign.key()),
+        campaign_bonus,
         fee_paid: total_fee,
-        timestamp: Clock::get()?.unix_timestamp,
+        lot_index: ctx.accounts.points_lot.index,
+        expires_at: ctx.accounts.points_lot.expires_at,
+        timestamp: now,
     });
 
     Ok(())
@@ -212,6 +439,23 @@ pub struct ProductPurchased {
     pub payment_type: u8,
     pub amount_paid: u64,
     pub points_earned: u64,
+    /// Customer tier that set the reward multiplier
+    pub tier: u8,
+    /// Campaign that added bonus points, if one was passed
+    pub campaign: Option<Pubkey>,
+    /// Bonus points from the campaign (included in `points_earned`)
+    pub campaign_bonus: u64,
     pub fee_paid: u64,
+    pub lot_index: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralBonusPaid {
+    pub referee: Pubkey,
+    pub referrer: Pubkey,
+    pub referee_bonus: u64,
+    pub referrer_bonus: u64,
     pub timestamp: i64,
 }
@@ -1,11 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{burn, Burn, Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
 
 use crate::errors::LoyaltyError;
-use crate::state::{MerchantRecord, PlatformState, PurchaseRecord};
+use crate::lots::{burn_expired, consume_lots};
+use crate::math::format_points;
+use crate::state::{ConsumerProfile, MerchantRecord, PlatformState, Product, PurchaseRecord};
+use crate::tiers::record_spent;
 
 #[derive(Accounts)]
-#[instruction(product_id_hash: [u8; 32], points_amount: u64, nonce: u64)]
+#[instruction(product_id_hash: [u8; 32])]
 pub struct PurchaseProductWithPoints<'info> {
     /// The customer making the purchase with loyalty points
     #[account(mut)]
@@ -21,7 +24,8 @@ pub struct PurchaseProductWithPoints<'info> {
         mut,
         seeds = [PlatformState::SEED],
         bump = platform_state.bump,
-        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_REDEEM | PlatformState::PAUSE_PURCHASE) @ LoyaltyError::OperationPaused
     )]
     pub platform_state: Account<'info, PlatformState>,
 
@@ -30,24 +34,18 @@ pub struct PurchaseProductWithPoints<'info> {
         mut,
         seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
         bump = merchant_record.bump,
-        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
     )]
     pub merchant_record: Account<'info, MerchantRecord>,
 
-    /// Purchase record PDA - nonce allows same customer to redeem same product multiple times
+    /// Product listing - source of the points price
     #[account(
-        init,
-        payer = customer,
-        space = 8 + PurchaseRecord::INIT_SPACE,
-        seeds = [
-            PurchaseRecord::SEED,
-            customer.key().as_ref(),
-            &product_id_hash,
-            &nonce.to_le_bytes()
-        ],
-        bump
+        seeds = [Product::SEED, merchant.key().as_ref(), &product_id_hash],
+        bump = product.bump,
+        constraint = product.is_available @ LoyaltyError::ProductUnavailable
     )]
-    pub purchase_record: Account<'info, PurchaseRecord>,
+    pub product: Account<'info, Product>,
 
     /// The loyalty token mint (mut because we burn tokens)
     #[account(
@@ -55,27 +53,53 @@ pub struct PurchaseProductWithPoints<'info> {
         seeds = [b"loyalty_mint"],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     /// Customer's token account for loyalty tokens
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = customer
+        associated_token::authority = customer,
+        associated_token::token_program = token_program
     )]
-    pub customer_token_account: Account<'info, TokenAccount>,
+    pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Consumer profile - open lots are consumed FIFO on spend
+    #[account(
+        init_if_needed,
+        payer = customer,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, customer.key().as_ref()],
+        bump
+    )]
+    pub consumer_profile: Account<'info, ConsumerProfile>,
+
+    /// Purchase record PDA - indexed by the customer's purchase counter
+    #[account(
+        init,
+        payer = customer,
+        space = 8 + PurchaseRecord::INIT_SPACE,
+        seeds = [
+            PurchaseRecord::SEED,
+            customer.key().as_ref(),
+            &consumer_profile.purchase_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<PurchaseProductWithPoints>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, PurchaseProductWithPoints<'info>>,
     product_id_hash: [u8; 32],
-    points_amount: u64,
-    _nonce: u64,
+    max_price: u64,
 ) -> Result<()> {
-    require!(points_amount > 0, LoyaltyError::InvalidAmount);
+    let points_amount = ctx.accounts.product.price_points;
+    require!(points_amount > 0, LoyaltyError::PaymentMethodNotAccepted);
+    require!(points_amount <= max_price, LoyaltyError::PriceAboveMax);
 
     // Check customer has enough points
     let customer_balance = ctx.accounts.customer_token_account.amount;
@@ -84,6 +108,36 @@ pub fn handler(
         LoyaltyError::InsufficientPointsBalance
     );
 
+    // Spend expiring lots oldest-first (open lots are passed as remaining accounts)
+    let now = Clock::get()?.unix_timestamp;
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(ctx.accounts.customer.key(), ctx.bumps.consumer_profile);
+    let lots = consume_lots(
+        consumer_profile,
+        ctx.remaining_accounts,
+        points_amount,
+        &ctx.accounts.customer.to_account_info(),
+        now,
+    )?;
+    record_spent(consumer_profile, points_amount)?;
+
+    // Expired lots reached by this spend are burned with the customer's signature
+    let expired_burned = burn_expired(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.customer_token_account,
+        &ctx.accounts.customer.to_account_info(),
+        &[],
+        &mut ctx.accounts.platform_state,
+        &mut ctx.accounts.consumer_profile,
+        lots.expired,
+        ctx.accounts.customer_token_account.amount,
+    )?;
+    require!(
+        customer_balance - expired_burned >= points_amount,
+        LoyaltyError::InsufficientPointsBalance
+    );
+
     let platform_state = &mut ctx.accounts.platform_state;
     let merchant_record = &mut ctx.accounts.merchant_record;
 
@@ -100,17 +154,32 @@ pub fn handler(
         points_amount,
     )?;
 
-    msg!("Burned {} loyalty points from customer", points_amount / 1_000_000);
+    msg!(
+        "Burned {} loyalty points from customer",
+        format_points(points_amount, platform_state.token_decimals)
+    );
 
     // STEP 2: Record the purchase
     let purchase_record = &mut ctx.accounts.purchase_record;
     purchase_record.customer = ctx.accounts.customer.key();
     purchase_record.merchant = ctx.accounts.merchant.key();
     purchase_record.product_id_hash = product_id_hash;
-    purchase_record.payment_type = 1; // 1 = Loyalty Points
+    purchase_record.payment_type = PurchaseRecord::PAYMENT_POINTS;
+    purchase_record.payment_mint = Pubkey::default();
     purchase_record.amount_paid = points_amount;
+    purchase_record.points_paid = points_amount;
     purchase_record.points_earned = 0; // No points earned when paying with points
-    purchase_record.purchased_at = Clock::get()?.unix_timestamp;
+    purchase_record.purchased_at = now;
+    purchase_record.refunded_at = 0;
+    purchase_record.finalized_at = 0;
+    purchase_record.status = PurchaseRecord::STATUS_CONFIRMED;
+    purchase_record.fulfilled_at = 0;
+    purchase_record.disputed_at = 0;
+    purchase_record.resolved_at = 0;
+    purchase_record.clawback_shortfall = 0;
+    purchase_record.campaign = None;
+    purchase_record.campaign_bonus = 0;
+    purchase_record.index = ctx.accounts.consumer_profile.take_purchase_index()?;
     purchase_record.bump = ctx.bumps.purchase_record;
 
     // Update state - reduce total supply since tokens are burned
@@ -129,7 +198,7 @@ pub fn handler(
         ctx.accounts.customer.key(),
         ctx.accounts.merchant.key(),
         &product_id_hash[..8],
-        points_amount
+        format_points(points_amount, platform_state.token_decimals)
     );
 
     emit!(ProductPurchasedWithPoints {
@@ -137,7 +206,7 @@ pub fn handler(
         merchant: ctx.accounts.merchant.key(),
         product_id_hash,
         points_spent: points_amount,
-        timestamp: Clock::get()?.unix_timestamp,
+        timestamp: now,
     });
 
     Ok(())
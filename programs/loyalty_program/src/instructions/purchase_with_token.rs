@@ -0,0 +1,381 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::campaigns::take_campaign_bonus;
+use crate::errors::LoyaltyError;
+use crate::fees::mint_fee_in_tokens;
+use crate::lots::open_lot;
+use crate::math::{format_points, points_to_tokens};
+use crate::policy::{check_mint_limits, record_mint};
+use crate::state::{
+    Campaign, ConsumerProfile, MerchantRecord, PaymentMint, PlatformState, PointsLot, Product,
+    ProductTokenPrice, PurchaseRecord, Treasury,
+};
+use crate::tiers::{boosted_reward, record_earned};
+
+#[derive(Accounts)]
+#[instruction(product_id_hash: [u8; 32])]
+pub struct PurchaseProductWithToken<'info> {
+    /// The customer making the purchase
+    #[account(mut)]
+    pub customer: Signer<'info>,
+
+    /// The merchant's wallet
+    /// CHECK: This is the merchant's wallet address
+    pub merchant: AccountInfo<'info>,
+
+    /// Protocol treasury PDA - owns the account receiving fees
+    #[account(
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Box<Account<'info, Treasury>>,
+
+    /// Platform state
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_MINT | PlatformState::PAUSE_PURCHASE) @ LoyaltyError::OperationPaused
+    )]
+    pub platform_state: Box<Account<'info, PlatformState>>,
+
+    /// Merchant record
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant,
+        constraint = merchant_record.pending_refunds == 0 @ LoyaltyError::RefundOutstanding
+    )]
+    pub merchant_record: Box<Account<'info, MerchantRecord>>,
+
+    /// Product listing - its points price sets the token price unless the merchant priced this mint
+    #[account(
+        seeds = [Product::SEED, merchant.key().as_ref(), &product_id_hash],
+        bump = product.bump,
+        constraint = product.is_available @ LoyaltyError::ProductUnavailable
+    )]
+    pub product: Box<Account<'info, Product>>,
+
+    /// Allowlist entry for the payment mint - source of the ratio
+    #[account(
+        mut,
+        seeds = [PaymentMint::SEED, payment_token_mint.key().as_ref()],
+        bump = payment_mint.bump,
+        constraint = payment_mint.is_enabled @ LoyaltyError::PaymentMintDisabled
+    )]
+    pub payment_mint: Box<Account<'info, PaymentMint>>,
+
+    /// The SPL mint being paid with
+    pub payment_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Merchant's price PDA for this product in the payment mint - always passed so a
+    /// set price can't be skipped. Used whenever the merchant has created it.
+    /// CHECK: address pinned by the seeds; only read if the program owns it
+    #[account(
+        seeds = [ProductTokenPrice::SEED, product.key().as_ref(), payment_token_mint.key().as_ref()],
+        bump
+    )]
+    pub product_token_price: UncheckedAccount<'info>,
+
+    /// Customer's token account for the payment mint
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = customer,
+        associated_token::token_program = payment_token_program
+    )]
+    pub customer_payment_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Merchant's token account for the payment mint - receives the price
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = merchant,
+        associated_token::token_program = payment_token_program
+    )]
+    pub merchant_payment_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's token account for the payment mint - receives the fee
+    #[account(
+        mut,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = protocol_treasury,
+        associated_token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The loyalty token mint
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Customer's associated token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = customer,
+        associated_token::token_program = token_program
+    )]
+    pub customer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Consumer profile - tracks the consumer's points lots
+    #[account(
+        init_if_needed,
+        payer = customer,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, customer.key().as_ref()],
+        bump
+    )]
+    pub consumer_profile: Box<Account<'info, ConsumerProfile>>,
+
+    /// Purchase record PDA - indexed by the customer's purchase counter
+    #[account(
+        init,
+        payer = customer,
+        space = 8 + PurchaseRecord::INIT_SPACE,
+        seeds = [
+            PurchaseRecord::SEED,
+            customer.key().as_ref(),
+            &consumer_profile.purchase_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub purchase_record: Box<Account<'info, PurchaseRecord>>,
+
+    /// New points lot recording this issuance and its expiry
+    #[account(
+        init,
+        payer = customer,
+        space = 8 + PointsLot::INIT_SPACE,
+        seeds = [
+            PointsLot::SEED,
+            customer.key().as_ref(),
+            &consumer_profile.next_lot_index.to_le_bytes()
+        ],
+        bump
+    )]
+    pub points_lot: Box<Account<'info, PointsLot>>,
+
+    /// Optional merchant campaign adding bonus points
+    #[account(
+        mut,
+        seeds = [Campaign::SEED, merchant.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump,
+        has_one = merchant
+    )]
+    pub campaign: Option<Box<Account<'info, Campaign>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<PurchaseProductWithToken>,
+    product_id_hash: [u8; 32],
+    max_price: u64,
+) -> Result<()> {
+    let price_points = ctx.accounts.product.price_points;
+    let base_reward = ctx.accounts.product.loyalty_points_reward;
+
+    let price_info = ctx.accounts.product_token_price.to_account_info();
+    let set_price = if price_info.owner == &crate::ID {
+        ProductTokenPrice::try_deserialize(&mut &price_info.try_borrow_data()?[..])?.price_tokens
+    } else {
+        0
+    };
+
+    require!(
+        set_price > 0 || price_points > 0,
+        LoyaltyError::PaymentMethodNotAccepted
+    );
+    require!(base_reward > 0, LoyaltyError::InvalidAmount);
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    let payment_mint = &mut ctx.accounts.payment_mint;
+
+    // The merchant's price for this mint, else the listed points price at the mint's ratio
+    let price_tokens = if set_price > 0 {
+        set_price
+    } else {
+        points_to_tokens(
+            price_points,
+            payment_mint.points_per_token,
+            platform_state.token_decimals,
+            payment_mint.decimals,
+        )?
+    };
+    require!(price_tokens <= max_price, LoyaltyError::PriceAboveMax);
+
+    // Listed reward scaled by the customer's tier multiplier
+    let tier = ctx.accounts.consumer_profile.tier;
+    let tier_reward = boosted_reward(&ctx.accounts.consumer_profile, &platform_state.tier_config, base_reward)?;
+
+    // Add any active campaign bonus for this product, debited from the campaign budget
+    let now = Clock::get()?.unix_timestamp;
+    let campaign_bonus = match ctx.accounts.campaign.as_deref_mut() {
+        Some(campaign) => take_campaign_bonus(campaign, base_reward, Some(&product_id_hash), now)?,
+        None => 0,
+    };
+    let loyalty_points_reward = tier_reward
+        .checked_add(campaign_bonus)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    // Enforce max supply and merchant allowance
+    check_mint_limits(platform_state, merchant_record, loyalty_points_reward)?;
+
+    // STEP 1: Customer pays the token price to merchant
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.payment_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.customer_payment_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to: ctx.accounts.merchant_payment_account.to_account_info(),
+                authority: ctx.accounts.customer.to_account_info(),
+            },
+        ),
+        price_tokens,
+        payment_mint.decimals,
+    )?;
+
+    msg!("Customer paid {} units of {} to merchant", price_tokens, payment_mint.mint);
+
+    // STEP 2: Customer pays the protocol fee in the same mint
+    let total_fee = mint_fee_in_tokens(platform_state, merchant_record, payment_mint, loyalty_points_reward)?;
+
+    if total_fee > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.payment_token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.customer_payment_account.to_account_info(),
+                    mint: ctx.accounts.payment_token_mint.to_account_info(),
+                    to: ctx.accounts.treasury_payment_account.to_account_info(),
+                    authority: ctx.accounts.customer.to_account_info(),
+                },
+            ),
+            total_fee,
+            payment_mint.decimals,
+        )?;
+    }
+
+    msg!("Customer paid protocol fee: {} units of {}", total_fee, payment_mint.mint);
+
+    // STEP 3: Mint loyalty points to customer
+    let seeds = &[
+        PlatformState::SEED,
+        &[platform_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.customer_token_account.to_account_info(),
+                authority: platform_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        loyalty_points_reward,
+    )?;
+
+    // STEP 4: Record the reward as an expiring lot
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(ctx.accounts.customer.key(), ctx.bumps.consumer_profile);
+    open_lot(
+        consumer_profile,
+        &mut ctx.accounts.points_lot,
+        ctx.bumps.points_lot,
+        loyalty_points_reward,
+        platform_state.points_expiry_seconds,
+        now,
+    )?;
+    record_earned(consumer_profile, &platform_state.tier_config, loyalty_points_reward, now)?;
+
+    // Update records
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    purchase_record.customer = ctx.accounts.customer.key();
+    purchase_record.merchant = ctx.accounts.merchant.key();
+    purchase_record.product_id_hash = product_id_hash;
+    purchase_record.payment_type = PurchaseRecord::PAYMENT_TOKEN;
+    purchase_record.payment_mint = payment_mint.mint;
+    purchase_record.amount_paid = price_tokens;
+    purchase_record.points_paid = 0;
+    purchase_record.points_earned = loyalty_points_reward;
+    purchase_record.purchased_at = now;
+    purchase_record.refunded_at = 0;
+    purchase_record.finalized_at = 0;
+    purchase_record.status = PurchaseRecord::STATUS_CONFIRMED;
+    purchase_record.fulfilled_at = 0;
+    purchase_record.disputed_at = 0;
+    purchase_record.resolved_at = 0;
+    purchase_record.clawback_shortfall = 0;
+    purchase_record.campaign = ctx.accounts.campaign.as_ref().map(|campaign| campaign.key());
+    purchase_record.campaign_bonus = campaign_bonus;
+    purchase_record.index = ctx.accounts.consumer_profile.take_purchase_index()?;
+    purchase_record.bump = ctx.bumps.purchase_record;
+
+    record_mint(platform_state, merchant_record, loyalty_points_reward)?;
+
+    // Token fees are tracked per mint; the lamport fee totals stay SOL-only
+    payment_mint.total_fees_collected = payment_mint
+        .total_fees_collected
+        .checked_add(total_fee)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Product purchased: customer={}, merchant={}, product_hash={:?}, price={} tokens, earned={} points",
+        ctx.accounts.customer.key(),
+        ctx.accounts.merchant.key(),
+        &product_id_hash[..8],
+        price_tokens,
+        format_points(loyalty_points_reward, platform_state.token_decimals)
+    );
+
+    emit!(ProductPurchasedWithToken {
+        customer: ctx.accounts.customer.key(),
+        merchant: ctx.accounts.merchant.key(),
+        product_id_hash,
+        payment_mint: payment_mint.mint,
+        amount_paid: price_tokens,
+        points_earned: loyalty_points_reward,
+        tier,
+        campaign: ctx.accounts.campaign.as_ref().map(|campaign| campaign.key()),
+        campaign_bonus,
+        fee_paid: total_fee,
+        lot_index: ctx.accounts.points_lot.index,
+        expires_at: ctx.accounts.points_lot.expires_at,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProductPurchasedWithToken {
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub product_id_hash: [u8; 32],
+    pub payment_mint: Pubkey,
+    /// Price paid in payment mint units
+    pub amount_paid: u64,
+    pub points_earned: u64,
+    pub tier: u8,
+    pub campaign: Option<Pubkey>,
+    pub campaign_bonus: u64,
+    /// Protocol fee paid in payment mint units
+    pub fee_paid: u64,
+    pub lot_index: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
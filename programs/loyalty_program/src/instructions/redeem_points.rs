@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{transfer, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 use crate::errors::LoyaltyError;
-use crate::state::{MerchantRecord, PlatformState};
+use crate::lots::{burn_expired, consume_lots};
+use crate::math::format_points;
+use crate::state::{ConsumerProfile, MerchantRecord, PlatformState};
+use crate::tiers::record_spent;
 
 #[derive(Accounts)]
 pub struct RedeemPoints<'info> {
@@ -11,11 +14,13 @@ pub struct RedeemPoints<'info> {
     #[account(mut)]
     pub consumer: Signer<'info>,
 
-    /// Platform state
+    /// Platform state - supply drops when non-transferable points are burned
     #[account(
+        mut,
         seeds = [PlatformState::SEED],
         bump = platform_state.bump,
-        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive,
+        constraint = !platform_state.is_paused(PlatformState::PAUSE_REDEEM) @ LoyaltyError::OperationPaused
     )]
     pub platform_state: Account<'info, PlatformState>,
 
@@ -34,35 +39,48 @@ pub struct RedeemPoints<'info> {
 
     /// The loyalty token mint
     #[account(
+        mut,
         seeds = [b"loyalty_mint"],
         bump
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
     /// Consumer's token account
     #[account(
         mut,
         associated_token::mint = token_mint,
-        associated_token::authority = consumer
+        associated_token::authority = consumer,
+        associated_token::token_program = token_program
+    )]
+    pub consumer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Consumer profile - open lots are consumed FIFO on spend
+    #[account(
+        init_if_needed,
+        payer = consumer,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, consumer.key().as_ref()],
+        bump
     )]
-    pub consumer_token_account: Account<'info, TokenAccount>,
+    pub consumer_profile: Account<'info, ConsumerProfile>,
 
     /// Merchant's token account (receives the redeemed tokens)
     #[account(
         init_if_needed,
         payer = consumer,
         associated_token::mint = token_mint,
-        associated_token::authority = merchant
+        associated_token::authority = merchant,
+        associated_token::token_program = token_program
     )]
-    pub merchant_token_account: Account<'info, TokenAccount>,
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<RedeemPoints>,
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RedeemPoints<'info>>,
     amount: u64,
     reward_id: String,
 ) -> Result<()> {
@@ -75,18 +93,71 @@ pub fn handler(
         LoyaltyError::InsufficientBalance
     );
 
-    // Transfer tokens from consumer to merchant
-    transfer(
-        CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.consumer_token_account.to_account_info(),
-                to: ctx.accounts.merchant_token_account.to_account_info(),
-                authority: ctx.accounts.consumer.to_account_info(),
-            },
-        ),
+    // Spend expiring lots oldest-first (open lots are passed as remaining accounts)
+    let now = Clock::get()?.unix_timestamp;
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(ctx.accounts.consumer.key(), ctx.bumps.consumer_profile);
+    let lots = consume_lots(
+        consumer_profile,
+        ctx.remaining_accounts,
         amount,
+        &ctx.accounts.consumer.to_account_info(),
+        now,
+    )?;
+    record_spent(consumer_profile, amount)?;
+
+    // Expired lots reached by this spend are burned with the consumer's signature
+    let expired_burned = burn_expired(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.consumer_token_account,
+        &ctx.accounts.consumer.to_account_info(),
+        &[],
+        &mut ctx.accounts.platform_state,
+        &mut ctx.accounts.consumer_profile,
+        lots.expired,
+        ctx.accounts.consumer_token_account.amount,
     )?;
+    require!(
+        consumer_balance - expired_burned >= amount,
+        LoyaltyError::InsufficientBalance
+    );
+
+    if ctx.accounts.platform_state.has_mint_extension(PlatformState::EXT_NON_TRANSFERABLE) {
+        // Non-transferable points can't move to the merchant, so they are burned
+        burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.consumer_token_account.to_account_info(),
+                    authority: ctx.accounts.consumer.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let platform_state = &mut ctx.accounts.platform_state;
+        platform_state.current_supply = platform_state
+            .current_supply
+            .checked_sub(amount)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    } else {
+        // Transfer tokens from consumer to merchant
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.consumer_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.merchant_token_account.to_account_info(),
+                    authority: ctx.accounts.consumer.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
 
     // Update merchant stats
     let merchant_record = &mut ctx.accounts.merchant_record;
@@ -98,7 +169,7 @@ pub fn handler(
     msg!(
         "Consumer {} redeemed {} points at merchant {}",
         ctx.accounts.consumer.key(),
-        amount,
+        format_points(amount, ctx.accounts.platform_state.token_decimals),
         ctx.accounts.merchant.key()
     );
 
@@ -107,7 +178,7 @@ pub fn handler(
         merchant: ctx.accounts.merchant.key(),
         amount,
         reward_id,
-        timestamp: Clock::get()?.unix_timestamp,
+        timestamp: now,
     });
 
     Ok(())
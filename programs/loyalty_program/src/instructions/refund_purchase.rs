@@ -0,0 +1,334 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, mint_to, transfer_checked, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::campaigns::return_campaign_bonus;
+use crate::errors::LoyaltyError;
+use crate::lots::{burn_expired, consume_lots, platform_burnable};
+use crate::math::format_points;
+use crate::policy::{check_mint_limits, record_mint};
+use crate::state::{Campaign, ConsumerProfile, MerchantRecord, PlatformState, PurchaseRecord};
+use crate::tiers::{record_clawback, record_spend_refund};
+
+#[derive(Accounts)]
+pub struct RefundPurchase<'info> {
+    /// The merchant refunding the purchase - returns the SOL price
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Platform state - mint authority and burn delegate
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - totals are unwound
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// The customer being refunded
+    /// CHECK: This is the customer's wallet address, matched against the purchase record
+    #[account(mut)]
+    pub customer: UncheckedAccount<'info>,
+
+    /// Purchase being refunded
+    #[account(
+        mut,
+        has_one = merchant,
+        has_one = customer,
+        constraint = purchase_record.status != PurchaseRecord::STATUS_REFUNDED @ LoyaltyError::AlreadyRefunded
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    /// Customer profile - reward lots are consumed on clawback
+    #[account(
+        mut,
+        seeds = [ConsumerProfile::SEED, customer.key().as_ref()],
+        bump = consumer_profile.bump
+    )]
+    pub consumer_profile: Account<'info, ConsumerProfile>,
+
+    /// Campaign recorded on the purchase - gets back the clawed-back bonus
+    #[account(
+        mut,
+        seeds = [Campaign::SEED, merchant.key().as_ref(), &campaign.campaign_id.to_le_bytes()],
+        bump = campaign.bump,
+        constraint = purchase_record.campaign == Some(campaign.key()) @ LoyaltyError::InvalidCampaign
+    )]
+    pub campaign: Option<Account<'info, Campaign>>,
+
+    /// The loyalty token mint
+    #[account(
+        mut,
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Customer's associated token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = customer,
+        associated_token::token_program = token_program
+    )]
+    pub customer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Merchant's payment mint account (required for SPL token purchases)
+    #[account(mut)]
+    pub merchant_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Customer's payment mint account (required for SPL token purchases)
+    #[account(mut)]
+    pub customer_payment_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The purchase's payment mint (required for SPL token purchases)
+    #[account(address = purchase_record.payment_mint @ LoyaltyError::InvalidPaymentAccount)]
+    pub payment_token_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Token program of the payment mint (required for SPL token purchases)
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundPurchase<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let payment_type = ctx.accounts.purchase_record.payment_type;
+    let points_earned = ctx.accounts.purchase_record.points_earned;
+    let points_paid = ctx.accounts.purchase_record.points_paid;
+    let campaign_bonus = ctx.accounts.purchase_record.campaign_bonus;
+    // SOL and mixed purchases record the lamports portion in amount_paid,
+    // SPL token purchases the payment mint amount
+    let (lamports_paid, tokens_paid) = match payment_type {
+        PurchaseRecord::PAYMENT_POINTS => (0, 0),
+        PurchaseRecord::PAYMENT_TOKEN => (0, ctx.accounts.purchase_record.amount_paid),
+        _ => (ctx.accounts.purchase_record.amount_paid, 0),
+    };
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+
+    let seeds = &[
+        PlatformState::SEED,
+        &[platform_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    // STEP 1: Merchant returns the SOL paid
+    if lamports_paid > 0 {
+        anchor_lang::solana_program::program::invoke(
+            &anchor_lang::solana_program::system_instruction::transfer(
+                &ctx.accounts.merchant.key(),
+                &ctx.accounts.customer.key(),
+                lamports_paid,
+            ),
+            &[
+                ctx.accounts.merchant.to_account_info(),
+                ctx.accounts.customer.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    // STEP 1b: Merchant returns the payment tokens
+    if tokens_paid > 0 {
+        let (Some(from), Some(to), Some(mint), Some(payment_token_program)) = (
+            ctx.accounts.merchant_payment_account.as_ref(),
+            ctx.accounts.customer_payment_account.as_ref(),
+            ctx.accounts.payment_token_mint.as_ref(),
+            ctx.accounts.payment_token_program.as_ref(),
+        ) else {
+            return err!(LoyaltyError::InvalidPaymentAccount);
+        };
+        require!(
+            to.owner == ctx.accounts.customer.key(),
+            LoyaltyError::InvalidPaymentAccount
+        );
+
+        transfer_checked(
+            CpiContext::new(
+                payment_token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.merchant.to_account_info(),
+                },
+            ),
+            tokens_paid,
+            mint.decimals,
+        )?;
+    }
+
+    // STEP 2: Claw back the reward via the platform's delegate authority - only
+    // what is still burnable; the rest is recorded as a shortfall
+    let burnable = platform_burnable(platform_state, &ctx.accounts.customer_token_account);
+    let points_clawed_back = points_earned.min(burnable);
+    let clawback_shortfall = points_earned - points_clawed_back;
+
+    if points_clawed_back > 0 {
+        // Open lots are passed as remaining accounts, oldest first
+        let lots = consume_lots(
+            consumer_profile,
+            ctx.remaining_accounts,
+            points_clawed_back,
+            &ctx.accounts.customer.to_account_info(),
+            now,
+        )?;
+
+        burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.customer_token_account.to_account_info(),
+                    authority: platform_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            points_clawed_back,
+        )?;
+
+        platform_state.current_supply = platform_state
+            .current_supply
+            .checked_sub(points_clawed_back)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+        merchant_record.total_minted = merchant_record
+            .total_minted
+            .checked_sub(points_clawed_back)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+        record_clawback(consumer_profile, &platform_state.tier_config, points_clawed_back, now)?;
+
+        // Expired lots reached on the way are burned with whatever approval is left
+        let platform_info = platform_state.to_account_info();
+        burn_expired(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.customer_token_account,
+            &platform_info,
+            signer_seeds,
+            platform_state,
+            consumer_profile,
+            lots.expired,
+            burnable - points_clawed_back,
+        )?;
+    }
+
+    // The campaign gets back the bonus share of what was clawed back
+    let campaign_bonus_returned = match ctx.accounts.campaign.as_deref_mut() {
+        Some(campaign) => return_campaign_bonus(campaign, campaign_bonus, points_clawed_back, points_earned)?,
+        None => 0,
+    };
+
+    if clawback_shortfall > 0 {
+        msg!(
+            "Clawback short by {} points (spent or not approved for burning)",
+            format_points(clawback_shortfall, platform_state.token_decimals)
+        );
+    }
+
+    // STEP 3: Re-mint any points burned as payment (returned points are not lot-backed).
+    // The re-mint is issued on the merchant's account, within its allowance
+    if points_paid > 0 {
+        require!(
+            !platform_state.is_paused(PlatformState::PAUSE_MINT),
+            LoyaltyError::OperationPaused
+        );
+        check_mint_limits(platform_state, merchant_record, points_paid)?;
+        // Points the merchant already cashed out must stay covered by what remains redeemed
+        require!(
+            merchant_record.total_redeemed.saturating_sub(points_paid) >= merchant_record.points_cashed_out,
+            LoyaltyError::RefundedPointsCashedOut
+        );
+
+        mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.customer_token_account.to_account_info(),
+                    authority: platform_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            points_paid,
+        )?;
+
+        record_mint(platform_state, merchant_record, points_paid)?;
+        merchant_record.total_redeemed = merchant_record
+            .total_redeemed
+            .checked_sub(points_paid)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+        record_spend_refund(consumer_profile, points_paid);
+    }
+
+    // Settling an ordered refund lifts the block on the merchant's mints, sales and cash-outs
+    if ctx.accounts.purchase_record.status == PurchaseRecord::STATUS_REFUND_ORDERED {
+        merchant_record.pending_refunds = merchant_record
+            .pending_refunds
+            .checked_sub(1)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    }
+
+    let purchase_record = &mut ctx.accounts.purchase_record;
+    purchase_record.refunded_at = now;
+    purchase_record.clawback_shortfall = clawback_shortfall;
+    purchase_record.status = PurchaseRecord::STATUS_REFUNDED;
+
+    msg!(
+        "Purchase refunded: customer={}, merchant={}, product_hash={:?}, lamports={}, tokens={}, burned={}, reminted={}",
+        purchase_record.customer,
+        purchase_record.merchant,
+        &purchase_record.product_id_hash[..8],
+        lamports_paid,
+        tokens_paid,
+        format_points(points_clawed_back, platform_state.token_decimals),
+        format_points(points_paid, platform_state.token_decimals)
+    );
+
+    emit!(PurchaseRefunded {
+        customer: purchase_record.customer,
+        merchant: purchase_record.merchant,
+        purchase_record: purchase_record.key(),
+        product_id_hash: purchase_record.product_id_hash,
+        payment_type,
+        lamports_refunded: lamports_paid,
+        tokens_refunded: tokens_paid,
+        points_burned: points_clawed_back,
+        clawback_shortfall,
+        campaign_bonus_returned,
+        points_reminted: points_paid,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PurchaseRefunded {
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub purchase_record: Pubkey,
+    pub product_id_hash: [u8; 32],
+    pub payment_type: u8,
+    pub lamports_refunded: u64,
+    /// Payment mint units returned for SPL token purchases
+    pub tokens_refunded: u64,
+    pub points_burned: u64,
+    /// Reward points that could not be clawed back
+    pub clawback_shortfall: u64,
+    /// Bonus points credited back to the purchase's campaign budget
+    pub campaign_bonus_returned: u64,
+    pub points_reminted: u64,
+    pub timestamp: i64,
+}
@@ -48,6 +48,10 @@ pub fn handler(ctx: Context<RegisterMerchant>, mint_allowance: u64) -> Result<()
     merchant_record.mint_allowance = mint_allowance;
     merchant_record.total_minted = 0;
     merchant_record.total_redeemed = 0;
+    merchant_record.total_cashed_out = 0;
+    merchant_record.fee_override = None;
+    merchant_record.points_cashed_out = 0;
+    merchant_record.pending_refunds = 0;
     merchant_record.registered_at = clock.unix_timestamp;
     merchant_record.bump = ctx.bumps.merchant_record;
 
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{ConsumerProfile, PlatformState, ReferralLink};
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    /// The consumer being referred
+    #[account(mut)]
+    pub referee: Signer<'info>,
+
+    /// The consumer who referred them
+    /// CHECK: This is the referrer's wallet address
+    pub referrer: UncheckedAccount<'info>,
+
+    /// Platform state
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Referee's profile - a referrer can only be added before the first purchase
+    #[account(
+        init_if_needed,
+        payer = referee,
+        space = 8 + ConsumerProfile::INIT_SPACE,
+        seeds = [ConsumerProfile::SEED, referee.key().as_ref()],
+        bump
+    )]
+    pub consumer_profile: Account<'info, ConsumerProfile>,
+
+    /// Referral link PDA - one per referee, so it can only be set once
+    #[account(
+        init,
+        payer = referee,
+        space = 8 + ReferralLink::INIT_SPACE,
+        seeds = [ReferralLink::SEED, referee.key().as_ref()],
+        bump
+    )]
+    pub referral_link: Account<'info, ReferralLink>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referee = ctx.accounts.referee.key();
+    let referrer = ctx.accounts.referrer.key();
+
+    require!(referrer != referee, LoyaltyError::SelfReferral);
+
+    let consumer_profile = &mut ctx.accounts.consumer_profile;
+    consumer_profile.ensure_initialized(referee, ctx.bumps.consumer_profile);
+    require!(
+        !consumer_profile.first_purchase_done,
+        LoyaltyError::ReferralAfterFirstPurchase
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let referral_link = &mut ctx.accounts.referral_link;
+    referral_link.referee = referee;
+    referral_link.referrer = referrer;
+    referral_link.created_at = now;
+    referral_link.bonus_paid_at = 0;
+    referral_link.bump = ctx.bumps.referral_link;
+
+    msg!("Referral registered: referee={}, referrer={}", referee, referrer);
+
+    emit!(ReferralRegistered {
+        referee,
+        referrer,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ReferralRegistered {
+    pub referee: Pubkey,
+    pub referrer: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantRecord, PlatformState};
+
+#[derive(Accounts)]
+pub struct ReinstateMerchant<'info> {
+    /// Platform admin - only they can reinstate merchants
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// The merchant wallet to reinstate
+    /// CHECK: This is the merchant's wallet address we're reinstating
+    pub merchant_wallet: UncheckedAccount<'info>,
+
+    /// Merchant record PDA - existing record keeps its history
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant_wallet.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = !merchant_record.is_authorized @ LoyaltyError::MerchantAlreadyAuthorized
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+}
+
+pub fn handler(ctx: Context<ReinstateMerchant>) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    let merchant_wallet = ctx.accounts.merchant_wallet.key();
+
+    // total_minted / total_redeemed / total_fees_paid are intentionally preserved
+    merchant_record.is_authorized = true;
+
+    platform_state.merchant_count = platform_state
+        .merchant_count
+        .checked_add(1)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!("Merchant reinstated: {}", merchant_wallet);
+
+    emit!(MerchantReinstated {
+        merchant: merchant_wallet,
+        mint_allowance: merchant_record.mint_allowance,
+        total_minted: merchant_record.total_minted,
+        reinstated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantReinstated {
+    pub merchant: Pubkey,
+    pub mint_allowance: u64,
+    pub total_minted: u64,
+    pub reinstated_at: i64,
+}
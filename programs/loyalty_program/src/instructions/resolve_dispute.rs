@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantRecord, PlatformState, PurchaseRecord};
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    /// Platform admin - arbitrates disputes
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Disputed purchase
+    #[account(
+        mut,
+        constraint = purchase_record.status == PurchaseRecord::STATUS_DISPUTED @ LoyaltyError::InvalidPurchaseStatus
+    )]
+    pub purchase_record: Account<'info, PurchaseRecord>,
+
+    /// Merchant record - an ordered refund blocks the merchant's mints, sales and cash-outs until settled
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, purchase_record.merchant.as_ref()],
+        bump = merchant_record.bump
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+}
+
+/// Rule on a dispute. The price sits in the merchant's wallet, so a ruling for
+/// the customer orders the refund and the merchant settles it via
+/// `refund_purchase`; the record stays locked and the merchant can't mint, sell
+/// or cash out until then.
+pub fn handler(ctx: Context<ResolveDispute>, refund: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let purchase_record = &mut ctx.accounts.purchase_record;
+
+    purchase_record.status = if refund {
+        PurchaseRecord::STATUS_REFUND_ORDERED
+    } else if purchase_record.fulfilled_at != 0 {
+        PurchaseRecord::STATUS_FULFILLED
+    } else {
+        PurchaseRecord::STATUS_CONFIRMED
+    };
+    purchase_record.resolved_at = now;
+
+    if refund {
+        let merchant_record = &mut ctx.accounts.merchant_record;
+        merchant_record.pending_refunds = merchant_record
+            .pending_refunds
+            .checked_add(1)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    }
+
+    msg!(
+        "Admin resolved dispute on purchase {}: refund {}",
+        purchase_record.key(),
+        refund
+    );
+
+    emit!(DisputeResolved {
+        purchase_record: purchase_record.key(),
+        customer: purchase_record.customer,
+        merchant: purchase_record.merchant,
+        admin: ctx.accounts.admin.key(),
+        refund_ordered: refund,
+        status: purchase_record.status,
+        resolved_at: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub purchase_record: Pubkey,
+    pub customer: Pubkey,
+    pub merchant: Pubkey,
+    pub admin: Pubkey,
+    pub refund_ordered: bool,
+    /// Status after the ruling (see `PurchaseRecord::STATUS_*`)
+    pub status: u8,
+    pub resolved_at: i64,
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MerchantDelegate;
+
+#[derive(Accounts)]
+pub struct RevokeMerchantDelegate<'info> {
+    /// The merchant revoking the delegate - receives the rent and unused fee deposit
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// The delegate wallet being revoked
+    /// CHECK: This is the delegate's wallet address
+    pub delegate: UncheckedAccount<'info>,
+
+    /// Delegate PDA to close
+    #[account(
+        mut,
+        close = merchant,
+        seeds = [MerchantDelegate::SEED, merchant.key().as_ref(), delegate.key().as_ref()],
+        bump = merchant_delegate.bump,
+        has_one = merchant,
+        has_one = delegate
+    )]
+    pub merchant_delegate: Account<'info, MerchantDelegate>,
+}
+
+pub fn handler(ctx: Context<RevokeMerchantDelegate>) -> Result<()> {
+    let merchant_delegate = &ctx.accounts.merchant_delegate;
+
+    msg!(
+        "Merchant {} revoked delegate {} after {} points minted",
+        merchant_delegate.merchant,
+        merchant_delegate.delegate,
+        merchant_delegate.total_minted
+    );
+
+    emit!(MerchantDelegateRevoked {
+        merchant: merchant_delegate.merchant,
+        delegate: merchant_delegate.delegate,
+        total_minted: merchant_delegate.total_minted,
+        lamports_returned: merchant_delegate.to_account_info().lamports(),
+        revoked_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantDelegateRevoked {
+    pub merchant: Pubkey,
+    pub delegate: Pubkey,
+    pub total_minted: u64,
+    /// Rent plus unused fee deposit returned to the merchant
+    pub lamports_returned: u64,
+    pub revoked_at: i64,
+}
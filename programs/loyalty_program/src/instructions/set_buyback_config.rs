@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::fees::validate_buyback;
+use crate::math::BPS_DENOMINATOR;
+use crate::state::PlatformState;
+
+/// Cash-out settings to change - `None` leaves the current value in place
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct BuybackConfigArgs {
+    pub buyback_ratio: Option<u64>,
+    pub cash_out_spread_bps: Option<u16>,
+}
+
+#[derive(Accounts)]
+pub struct SetBuybackConfig<'info> {
+    /// Platform admin - only they can change the buyback rate
+    pub admin: Signer<'info>,
+
+    /// Platform state - holds the cash-out settings
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<SetBuybackConfig>, args: BuybackConfigArgs) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    let old_buyback_ratio = platform_state.buyback_ratio;
+    let old_cash_out_spread_bps = platform_state.cash_out_spread_bps;
+
+    if let Some(buyback_ratio) = args.buyback_ratio {
+        // 0 disables cash-out
+        platform_state.buyback_ratio = buyback_ratio;
+    }
+    if let Some(spread_bps) = args.cash_out_spread_bps {
+        require!(
+            spread_bps as u64 <= BPS_DENOMINATOR,
+            LoyaltyError::InvalidBasisPoints
+        );
+        platform_state.cash_out_spread_bps = spread_bps;
+    }
+
+    // Cash-out must stay at or below the mint price under the new settings
+    validate_buyback(platform_state)?;
+
+    msg!(
+        "Cash-out config updated: buyback ratio {} -> {}, spread {} -> {} bps",
+        old_buyback_ratio,
+        platform_state.buyback_ratio,
+        old_cash_out_spread_bps,
+        platform_state.cash_out_spread_bps
+    );
+
+    emit!(BuybackConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_buyback_ratio,
+        new_buyback_ratio: platform_state.buyback_ratio,
+        old_cash_out_spread_bps,
+        new_cash_out_spread_bps: platform_state.cash_out_spread_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct BuybackConfigUpdated {
+    pub admin: Pubkey,
+    pub old_buyback_ratio: u64,
+    pub new_buyback_ratio: u64,
+    pub old_cash_out_spread_bps: u16,
+    pub new_cash_out_spread_bps: u16,
+    pub timestamp: i64,
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{approve, revoke, Approve, Mint, Revoke, TokenAccount, TokenInterface};
+
+use crate::errors::LoyaltyError;
+use crate::math::format_points;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct SetExpiryDelegate<'info> {
+    /// The consumer opting in to (or out of) crank-driven expiry
+    pub consumer: Signer<'info>,
+
+    /// Platform state - the PDA that burns expired points
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// The loyalty token mint
+    #[account(
+        seeds = [b"loyalty_mint"],
+        bump
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Consumer's token account for loyalty tokens
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = consumer,
+        associated_token::token_program = token_program
+    )]
+    pub consumer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Without a permanent delegate, `expire_points` and refund clawbacks can only
+/// burn what the consumer has approved. `allowance` of 0 revokes the approval.
+pub fn handler(ctx: Context<SetExpiryDelegate>, allowance: u64) -> Result<()> {
+    require!(
+        !ctx.accounts
+            .platform_state
+            .has_mint_extension(PlatformState::EXT_PERMANENT_DELEGATE),
+        LoyaltyError::ExpiryDelegateNotNeeded
+    );
+
+    if allowance > 0 {
+        approve(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Approve {
+                    to: ctx.accounts.consumer_token_account.to_account_info(),
+                    delegate: ctx.accounts.platform_state.to_account_info(),
+                    authority: ctx.accounts.consumer.to_account_info(),
+                },
+            ),
+            allowance,
+        )?;
+    } else {
+        revoke(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Revoke {
+                source: ctx.accounts.consumer_token_account.to_account_info(),
+                authority: ctx.accounts.consumer.to_account_info(),
+            },
+        ))?;
+    }
+
+    msg!(
+        "Consumer {} set the expiry allowance to {} points",
+        ctx.accounts.consumer.key(),
+        format_points(allowance, ctx.accounts.platform_state.token_decimals)
+    );
+
+    emit!(ExpiryDelegateSet {
+        consumer: ctx.accounts.consumer.key(),
+        allowance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ExpiryDelegateSet {
+    pub consumer: Pubkey,
+    /// Points the platform may burn on expiry or clawback (0 = revoked)
+    pub allowance: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::fees::{validate_buyback, validate_volume_tiers};
+use crate::math::BPS_DENOMINATOR;
+use crate::state::{PlatformState, VolumeFeeTier};
+
+/// Bps and volume-tier fees to change - `None` leaves the current value in place.
+/// The flat and per-thousand fees are set through `update_platform_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct FeeConfigArgs {
+    pub mint_fee_bps: Option<u16>,
+    pub volume_fee_tiers: Option<[VolumeFeeTier; 3]>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeConfig<'info> {
+    /// Platform admin - only they can change fees
+    pub admin: Signer<'info>,
+
+    /// Platform state - holds the fee schedule
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<SetFeeConfig>, args: FeeConfigArgs) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    let old_mint_fee_bps = platform_state.mint_fee_bps;
+    let old_volume_fee_tiers = platform_state.volume_fee_tiers;
+
+    if let Some(mint_fee_bps) = args.mint_fee_bps {
+        require!(
+            mint_fee_bps as u64 <= BPS_DENOMINATOR,
+            LoyaltyError::InvalidBasisPoints
+        );
+        platform_state.mint_fee_bps = mint_fee_bps;
+    }
+    if let Some(volume_fee_tiers) = args.volume_fee_tiers {
+        validate_volume_tiers(&volume_fee_tiers)?;
+        platform_state.volume_fee_tiers = volume_fee_tiers;
+    }
+
+    // Cash-out must stay at or below the mint price under the new settings
+    validate_buyback(platform_state)?;
+
+    msg!(
+        "Mint fee bps: {} -> {}",
+        old_mint_fee_bps,
+        platform_state.mint_fee_bps
+    );
+
+    emit!(FeeConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_mint_fee_bps,
+        new_mint_fee_bps: platform_state.mint_fee_bps,
+        old_volume_fee_tiers,
+        new_volume_fee_tiers: platform_state.volume_fee_tiers,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub admin: Pubkey,
+    pub old_mint_fee_bps: u16,
+    pub new_mint_fee_bps: u16,
+    pub old_volume_fee_tiers: [VolumeFeeTier; 3],
+    pub new_volume_fee_tiers: [VolumeFeeTier; 3],
+    pub timestamp: i64,
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+/// Gift settings to change - `None` leaves the current value in place
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct GiftConfigArgs {
+    pub gift_fee: Option<u64>,
+    pub gift_daily_cap: Option<u64>,
+    pub gift_min_account_age: Option<i64>,
+    pub allow_non_transferable_gifts: Option<bool>,
+}
+
+#[derive(Accounts)]
+pub struct SetGiftConfig<'info> {
+    /// Platform admin - only they can change gifting rules
+    pub admin: Signer<'info>,
+
+    /// Platform state - holds the gift settings
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<SetGiftConfig>, args: GiftConfigArgs) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    let old_gift_fee = platform_state.gift_fee;
+    let old_gift_daily_cap = platform_state.gift_daily_cap;
+    let old_gift_min_account_age = platform_state.gift_min_account_age;
+    let old_allow_non_transferable_gifts = platform_state.allow_non_transferable_gifts;
+
+    if let Some(gift_fee) = args.gift_fee {
+        platform_state.gift_fee = gift_fee;
+    }
+    if let Some(gift_daily_cap) = args.gift_daily_cap {
+        // 0 removes the cap
+        platform_state.gift_daily_cap = gift_daily_cap;
+    }
+    if let Some(gift_min_account_age) = args.gift_min_account_age {
+        require!(gift_min_account_age >= 0, LoyaltyError::InvalidGiftConfig);
+        platform_state.gift_min_account_age = gift_min_account_age;
+    }
+    if let Some(allow) = args.allow_non_transferable_gifts {
+        // Only matters for a NonTransferable mint, where a gift burns and re-mints
+        platform_state.allow_non_transferable_gifts = allow;
+    }
+
+    msg!(
+        "Gift config updated: fee {} -> {} lamports, daily cap {} -> {}, min account age {} -> {} seconds, non-transferable gifts {} -> {}",
+        old_gift_fee,
+        platform_state.gift_fee,
+        old_gift_daily_cap,
+        platform_state.gift_daily_cap,
+        old_gift_min_account_age,
+        platform_state.gift_min_account_age,
+        old_allow_non_transferable_gifts,
+        platform_state.allow_non_transferable_gifts
+    );
+
+    emit!(GiftConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_gift_fee,
+        new_gift_fee: platform_state.gift_fee,
+        old_gift_daily_cap,
+        new_gift_daily_cap: platform_state.gift_daily_cap,
+        old_gift_min_account_age,
+        new_gift_min_account_age: platform_state.gift_min_account_age,
+        old_allow_non_transferable_gifts,
+        new_allow_non_transferable_gifts: platform_state.allow_non_transferable_gifts,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct GiftConfigUpdated {
+    pub admin: Pubkey,
+    pub old_gift_fee: u64,
+    pub new_gift_fee: u64,
+    pub old_gift_daily_cap: u64,
+    pub new_gift_daily_cap: u64,
+    pub old_gift_min_account_age: i64,
+    pub new_gift_min_account_age: i64,
+    pub old_allow_non_transferable_gifts: bool,
+    pub new_allow_non_transferable_gifts: bool,
+    pub timestamp: i64,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantRecord, PlatformState};
+
+#[derive(Accounts)]
+pub struct SetMerchantAllowance<'info> {
+    /// Platform admin - only they can change allowances
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// The merchant wallet whose allowance changes
+    /// CHECK: This is the merchant's wallet address
+    pub merchant_wallet: UncheckedAccount<'info>,
+
+    /// Merchant record PDA
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant_wallet.key().as_ref()],
+        bump = merchant_record.bump
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+}
+
+pub fn handler(ctx: Context<SetMerchantAllowance>, mint_allowance: u64) -> Result<()> {
+    let merchant_record = &mut ctx.accounts.merchant_record;
+
+    // 0 = unlimited; otherwise the cap must still cover what was already minted
+    require!(
+        mint_allowance == 0 || mint_allowance >= merchant_record.total_minted,
+        LoyaltyError::InvalidMintAllowance
+    );
+
+    let old_mint_allowance = merchant_record.mint_allowance;
+    merchant_record.mint_allowance = mint_allowance;
+
+    msg!(
+        "Merchant {} mint allowance: {} -> {}",
+        merchant_record.wallet,
+        old_mint_allowance,
+        mint_allowance
+    );
+
+    emit!(MerchantAllowanceUpdated {
+        merchant: merchant_record.wallet,
+        old_mint_allowance,
+        new_mint_allowance: mint_allowance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantAllowanceUpdated {
+    pub merchant: Pubkey,
+    pub old_mint_allowance: u64,
+    pub new_mint_allowance: u64,
+    pub timestamp: i64,
+}
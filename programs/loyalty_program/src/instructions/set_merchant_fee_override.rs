@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::fees::validate_schedule;
+use crate::state::{FeeSchedule, MerchantRecord, PlatformState};
+
+#[derive(Accounts)]
+pub struct SetMerchantFeeOverride<'info> {
+    /// Platform admin - only they can negotiate merchant fees
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// The merchant wallet whose fees change
+    /// CHECK: This is the merchant's wallet address
+    pub merchant_wallet: UncheckedAccount<'info>,
+
+    /// Merchant record PDA
+    #[account(
+        mut,
+        seeds = [MerchantRecord::SEED, merchant_wallet.key().as_ref()],
+        bump = merchant_record.bump
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+}
+
+pub fn handler(
+    ctx: Context<SetMerchantFeeOverride>,
+    fee_override: Option<FeeSchedule>,
+) -> Result<()> {
+    if let Some(schedule) = &fee_override {
+        validate_schedule(schedule)?;
+    }
+
+    let merchant_record = &mut ctx.accounts.merchant_record;
+    let old_fee_override = merchant_record.fee_override;
+    merchant_record.fee_override = fee_override;
+
+    msg!(
+        "Merchant {} fee override: {:?} -> {:?}",
+        merchant_record.wallet,
+        old_fee_override,
+        fee_override
+    );
+
+    emit!(MerchantFeeOverrideUpdated {
+        merchant: merchant_record.wallet,
+        old_fee_override,
+        new_fee_override: fee_override,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct MerchantFeeOverrideUpdated {
+    pub merchant: Pubkey,
+    pub old_fee_override: Option<FeeSchedule>,
+    pub new_fee_override: Option<FeeSchedule>,
+    pub timestamp: i64,
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct SetPausedOperations<'info> {
+    /// Platform admin - only they can pause operations
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(
+    ctx: Context<SetPausedOperations>,
+    paused_operations: u16,
+    reason_code: u8,
+) -> Result<()> {
+    require!(
+        paused_operations & !PlatformState::PAUSE_ALL == 0,
+        LoyaltyError::InvalidPausedOperations
+    );
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    let old_paused_operations = platform_state.paused_operations;
+
+    platform_state.paused_operations = paused_operations;
+    platform_state.pause_reason = reason_code;
+
+    msg!(
+        "Paused operations changed from {:#06b} to {:#06b} (reason code {})",
+        old_paused_operations,
+        paused_operations,
+        reason_code
+    );
+
+    emit!(PausedOperationsUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_paused_operations,
+        new_paused_operations: paused_operations,
+        reason_code,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PausedOperationsUpdated {
+    pub admin: Pubkey,
+    pub old_paused_operations: u16,
+    pub new_paused_operations: u16,
+    pub reason_code: u8,
+    pub timestamp: i64,
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::errors::LoyaltyError;
+use crate::state::{PaymentMint, PlatformState, Treasury};
+
+#[derive(Accounts)]
+pub struct SetPaymentMint<'info> {
+    /// Platform admin - only they can manage accepted payment mints
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Protocol treasury PDA - owns the fee and deposit token account
+    #[account(
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// The SPL mint being allowlisted
+    pub payment_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Payment mint allowlist entry
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + PaymentMint::INIT_SPACE,
+        seeds = [PaymentMint::SEED, payment_token_mint.key().as_ref()],
+        bump
+    )]
+    pub payment_mint: Account<'info, PaymentMint>,
+
+    /// Treasury's token account for this mint - receives deposits and fees
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = payment_token_mint,
+        associated_token::authority = protocol_treasury,
+        associated_token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SetPaymentMint>,
+    points_per_token: u64,
+    is_enabled: bool,
+) -> Result<()> {
+    require!(points_per_token > 0, LoyaltyError::InvalidRatio);
+
+    let payment_mint = &mut ctx.accounts.payment_mint;
+    let old_points_per_token = payment_mint.points_per_token;
+
+    payment_mint.mint = ctx.accounts.payment_token_mint.key();
+    payment_mint.decimals = ctx.accounts.payment_token_mint.decimals;
+    payment_mint.points_per_token = points_per_token;
+    payment_mint.is_enabled = is_enabled;
+    payment_mint.bump = ctx.bumps.payment_mint;
+
+    msg!(
+        "Payment mint {}: ratio {} -> {} points per token, enabled={}",
+        payment_mint.mint,
+        old_points_per_token,
+        points_per_token,
+        is_enabled
+    );
+
+    emit!(PaymentMintUpdated {
+        mint: payment_mint.mint,
+        old_points_per_token,
+        new_points_per_token: points_per_token,
+        is_enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PaymentMintUpdated {
+    pub mint: Pubkey,
+    pub old_points_per_token: u64,
+    pub new_points_per_token: u64,
+    pub is_enabled: bool,
+    pub timestamp: i64,
+}
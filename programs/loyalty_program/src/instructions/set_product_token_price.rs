@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantRecord, PaymentMint, PlatformState, Product, ProductTokenPrice};
+
+#[derive(Accounts)]
+pub struct SetProductTokenPrice<'info> {
+    /// The merchant who owns the product
+    #[account(mut)]
+    pub merchant: Signer<'info>,
+
+    /// Platform state
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// Product PDA - seeds bind it to the signing merchant
+    #[account(
+        seeds = [Product::SEED, merchant.key().as_ref(), &product.product_id_hash],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+
+    /// Allowlist entry - prices can only be set in an allowlisted mint
+    #[account(
+        seeds = [PaymentMint::SEED, payment_token_mint.key().as_ref()],
+        bump = payment_mint.bump
+    )]
+    pub payment_mint: Account<'info, PaymentMint>,
+
+    /// The SPL mint the price is denominated in
+    pub payment_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Price PDA for this product and payment mint (created on first use)
+    #[account(
+        init_if_needed,
+        payer = merchant,
+        space = 8 + ProductTokenPrice::INIT_SPACE,
+        seeds = [ProductTokenPrice::SEED, product.key().as_ref(), payment_token_mint.key().as_ref()],
+        bump
+    )]
+    pub product_token_price: Account<'info, ProductTokenPrice>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// `price_tokens` is in raw payment token units; 0 goes back to converting the
+/// product's points price at the mint's `points_per_token`.
+pub fn handler(ctx: Context<SetProductTokenPrice>, price_tokens: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let product_token_price = &mut ctx.accounts.product_token_price;
+    product_token_price.product = ctx.accounts.product.key();
+    product_token_price.payment_mint = ctx.accounts.payment_token_mint.key();
+    product_token_price.price_tokens = price_tokens;
+    product_token_price.updated_at = now;
+    product_token_price.bump = ctx.bumps.product_token_price;
+
+    msg!(
+        "Product token price set: product_hash={:?}, mint={}, price={}",
+        &ctx.accounts.product.product_id_hash[..8],
+        product_token_price.payment_mint,
+        price_tokens
+    );
+
+    emit!(ProductTokenPriceSet {
+        merchant: ctx.accounts.merchant.key(),
+        product: product_token_price.product,
+        payment_mint: product_token_price.payment_mint,
+        price_tokens,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProductTokenPriceSet {
+    pub merchant: Pubkey,
+    pub product: Pubkey,
+    pub payment_mint: Pubkey,
+    /// Raw payment token units (0 = points price conversion)
+    pub price_tokens: u64,
+    pub timestamp: i64,
+}
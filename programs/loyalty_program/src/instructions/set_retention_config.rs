@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+/// Purchase record lifecycle settings to change - `None` leaves the current value in place
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct RetentionConfigArgs {
+    pub purchase_retention_seconds: Option<i64>,
+    pub dispute_window_seconds: Option<i64>,
+}
+
+#[derive(Accounts)]
+pub struct SetRetentionConfig<'info> {
+    /// Platform admin - only they can change retention rules
+    pub admin: Signer<'info>,
+
+    /// Platform state - holds the retention and dispute windows
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<SetRetentionConfig>, args: RetentionConfigArgs) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    let old_purchase_retention_seconds = platform_state.purchase_retention_seconds;
+    let old_dispute_window_seconds = platform_state.dispute_window_seconds;
+
+    if let Some(retention_seconds) = args.purchase_retention_seconds {
+        require!(retention_seconds >= 0, LoyaltyError::InvalidRetentionPeriod);
+        platform_state.purchase_retention_seconds = retention_seconds;
+    }
+    if let Some(window_seconds) = args.dispute_window_seconds {
+        require!(window_seconds >= 0, LoyaltyError::InvalidDisputeWindow);
+        platform_state.dispute_window_seconds = window_seconds;
+    }
+
+    msg!(
+        "Retention config updated: retention {} -> {} seconds, dispute window {} -> {} seconds",
+        old_purchase_retention_seconds,
+        platform_state.purchase_retention_seconds,
+        old_dispute_window_seconds,
+        platform_state.dispute_window_seconds
+    );
+
+    emit!(RetentionConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_purchase_retention_seconds,
+        new_purchase_retention_seconds: platform_state.purchase_retention_seconds,
+        old_dispute_window_seconds,
+        new_dispute_window_seconds: platform_state.dispute_window_seconds,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct RetentionConfigUpdated {
+    pub admin: Pubkey,
+    pub old_purchase_retention_seconds: i64,
+    pub new_purchase_retention_seconds: i64,
+    pub old_dispute_window_seconds: i64,
+    pub new_dispute_window_seconds: i64,
+    pub timestamp: i64,
+}
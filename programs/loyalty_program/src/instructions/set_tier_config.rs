@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::math::BPS_DENOMINATOR;
+use crate::state::{PlatformState, TierConfig};
+
+#[derive(Accounts)]
+pub struct SetTierConfig<'info> {
+    /// Platform admin - only they can change tiers
+    pub admin: Signer<'info>,
+
+    /// Platform state - holds the tier config
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<SetTierConfig>, tier_config: TierConfig) -> Result<()> {
+    // Gold must sit above Silver when both tiers are enabled
+    require!(
+        tier_config.silver_threshold == 0
+            || tier_config.gold_threshold == 0
+            || tier_config.gold_threshold > tier_config.silver_threshold,
+        LoyaltyError::InvalidTierConfig
+    );
+
+    // Tiers only ever boost rewards, and higher tiers never earn less
+    let [bronze, silver, gold] = tier_config.multipliers_bps;
+    require!(
+        bronze as u64 >= BPS_DENOMINATOR && silver >= bronze && gold >= silver,
+        LoyaltyError::InvalidTierConfig
+    );
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    let old_tier_config = platform_state.tier_config;
+    platform_state.tier_config = tier_config;
+
+    msg!(
+        "Tier config updated: silver>={}, gold>={}, multipliers_bps={:?}",
+        tier_config.silver_threshold,
+        tier_config.gold_threshold,
+        tier_config.multipliers_bps
+    );
+
+    emit!(TierConfigUpdated {
+        old_tier_config,
+        new_tier_config: tier_config,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TierConfigUpdated {
+    pub old_tier_config: TierConfig,
+    pub new_tier_config: TierConfig,
+    pub timestamp: i64,
+}
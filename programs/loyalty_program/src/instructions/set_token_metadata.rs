@@ -4,7 +4,16 @@ use anchor_lang::solana_program::{
     program::invoke_signed,
     sysvar,
 };
-use anchor_spl::token::Mint;
+use anchor_spl::token_2022::spl_token_2022::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::spl_pod::optional_keys::OptionalNonZeroPubkey;
+use anchor_spl::token_interface::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_interface::{
+    token_metadata_initialize, token_metadata_update_field, Mint, TokenInterface,
+    TokenMetadataInitialize, TokenMetadataUpdateField,
+};
 use std::str::FromStr;
 
 use crate::errors::LoyaltyError;
@@ -31,18 +40,19 @@ pub struct SetTokenMetadata<'info> {
         seeds = [b"loyalty_mint"],
         bump,
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
-    /// Metaplex metadata account PDA
+    /// Metaplex metadata account PDA (not needed when metadata is embedded in the mint)
     /// Derived as: ["metadata", MPL_TOKEN_METADATA_ID, token_mint]
     /// CHECK: Created and validated by Metaplex program
     #[account(mut)]
-    pub metadata_account: UncheckedAccount<'info>,
+    pub metadata_account: Option<UncheckedAccount<'info>>,
 
-    /// Metaplex Token Metadata program
+    /// Metaplex Token Metadata program (not needed when metadata is embedded in the mint)
     /// CHECK: Key validated at runtime in handler
-    pub token_metadata_program: UncheckedAccount<'info>,
+    pub token_metadata_program: Option<UncheckedAccount<'info>>,
 
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 
     /// CHECK: Rent sysvar
@@ -64,13 +74,136 @@ pub fn handler(
     symbol: String,
     uri: String,
 ) -> Result<()> {
+    if ctx
+        .accounts
+        .platform_state
+        .has_mint_extension(PlatformState::EXT_METADATA_POINTER)
+    {
+        set_embedded_metadata(ctx, name, symbol, uri)
+    } else {
+        set_metaplex_metadata(ctx, name, symbol, uri)
+    }
+}
+
+/// Token-2022 mints with a metadata pointer store their metadata in the mint account.
+/// The platform PDA is its update authority, so it follows admin transfers; calling
+/// this again rewrites the name, symbol and URI.
+fn set_embedded_metadata(
+    ctx: Context<SetTokenMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let mint_info = ctx.accounts.token_mint.to_account_info();
+    let existing = {
+        let data = mint_info.try_borrow_data()?;
+        StateWithExtensions::<MintState>::unpack(&data)?
+            .get_variable_len_extension::<TokenMetadata>()
+            .ok()
+    };
+
+    // Token-2022 reallocs the mint for the metadata but doesn't fund the extra rent
+    let metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey(ctx.accounts.platform_state.key()),
+        mint: mint_info.key(),
+        name: name.clone(),
+        symbol: symbol.clone(),
+        uri: uri.clone(),
+        additional_metadata: existing
+            .as_ref()
+            .map(|existing| existing.additional_metadata.clone())
+            .unwrap_or_default(),
+    };
+    let current_size = match &existing {
+        Some(existing) => existing.tlv_size_of()?,
+        None => 0,
+    };
+    let new_len = (mint_info.data_len() - current_size)
+        .checked_add(metadata.tlv_size_of()?)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(mint_info.lamports());
+    if shortfall > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: mint_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    let platform_state = &ctx.accounts.platform_state;
+    let seeds = &[PlatformState::SEED, &[platform_state.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    if existing.is_none() {
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TokenMetadataInitialize {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    metadata: mint_info.clone(),
+                    update_authority: ctx.accounts.platform_state.to_account_info(),
+                    mint_authority: ctx.accounts.platform_state.to_account_info(),
+                    mint: mint_info,
+                },
+                signer_seeds,
+            ),
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+        )?;
+    } else {
+        for (field, value) in [
+            (Field::Name, name.clone()),
+            (Field::Symbol, symbol.clone()),
+            (Field::Uri, uri.clone()),
+        ] {
+            token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TokenMetadataUpdateField {
+                        token_program_id: ctx.accounts.token_program.to_account_info(),
+                        metadata: mint_info.clone(),
+                        update_authority: ctx.accounts.platform_state.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                field,
+                value,
+            )?;
+        }
+    }
+
+    msg!("Embedded token metadata set: name={}, symbol={}, uri={}", name, symbol, uri);
+    Ok(())
+}
+
+fn set_metaplex_metadata(
+    ctx: Context<SetTokenMetadata>,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    let (Some(metadata_account), Some(token_metadata_program)) = (
+        ctx.accounts.metadata_account.as_ref(),
+        ctx.accounts.token_metadata_program.as_ref(),
+    ) else {
+        return err!(LoyaltyError::InvalidMetadataAccount);
+    };
+
     // Validate Metaplex program ID at runtime
     let mpl_id = Pubkey::from_str("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s")
-        .map_err(|_| LoyaltyError::InvalidTreasury)?;
+        .map_err(|_| LoyaltyError::InvalidMetadataAccount)?;
     require_keys_eq!(
-        ctx.accounts.token_metadata_program.key(),
+        token_metadata_program.key(),
         mpl_id,
-        LoyaltyError::InvalidTreasury
+        LoyaltyError::InvalidMetadataAccount
     );
 
     // Build CreateMetadataAccountsV3 instruction data (Borsh)
@@ -94,7 +227,7 @@ pub fn handler(
     let ix = Instruction {
         program_id: mpl_id,
         accounts: vec![
-            AccountMeta::new(*ctx.accounts.metadata_account.key, false),    // metadata (mut)
+            AccountMeta::new(metadata_account.key(), false),    // metadata (mut)
             AccountMeta::new_readonly(ctx.accounts.token_mint.key(), false), // mint
             AccountMeta::new_readonly(ctx.accounts.platform_state.key(), true), // mint_authority (signer via PDA)
             AccountMeta::new(ctx.accounts.admin.key(), true),               // payer (mut, signer)
@@ -112,7 +245,7 @@ pub fn handler(
     invoke_signed(
         &ix,
         &[
-            ctx.accounts.metadata_account.to_account_info(),
+            metadata_account.to_account_info(),
             ctx.accounts.token_mint.to_account_info(),
             ctx.accounts.platform_state.to_account_info(), // mint_authority
             ctx.accounts.admin.to_account_info(),          // payer
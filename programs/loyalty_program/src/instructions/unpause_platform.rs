@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+#[derive(Accounts)]
+pub struct UnpausePlatform<'info> {
+    /// Platform admin - only they can unpause the platform
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin,
+        constraint = !platform_state.is_active @ LoyaltyError::PlatformNotPaused
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<UnpausePlatform>, reason_code: u8) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    platform_state.is_active = true;
+    platform_state.pause_reason = reason_code;
+
+    msg!(
+        "Platform unpaused by admin {} with reason code {}",
+        ctx.accounts.admin.key(),
+        reason_code
+    );
+
+    emit!(PlatformUnpaused {
+        admin: ctx.accounts.admin.key(),
+        reason_code,
+        paused_operations: platform_state.paused_operations,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PlatformUnpaused {
+    pub admin: Pubkey,
+    pub reason_code: u8,
+    /// Operations that remain individually paused after unpausing
+    pub paused_operations: u16,
+    pub timestamp: i64,
+}
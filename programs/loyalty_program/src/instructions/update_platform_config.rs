@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::fees::validate_buyback;
+use crate::state::PlatformState;
+
+/// Platform economics to change - `None` leaves the current value in place.
+/// Bps and volume-tier fees, cash-out, gifting and retention have their own
+/// `set_*_config` instructions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdatePlatformConfigArgs {
+    pub base_mint_fee: Option<u64>,
+    pub fee_rate_per_thousand: Option<u64>,
+    pub sol_to_points_ratio: Option<u64>,
+    pub max_supply: Option<u64>,
+    pub points_expiry_seconds: Option<i64>,
+    pub referrer_bonus: Option<u64>,
+    pub referee_bonus: Option<u64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlatformConfig<'info> {
+    /// Platform admin - only they can change platform economics
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+}
+
+pub fn handler(ctx: Context<UpdatePlatformConfig>, args: UpdatePlatformConfigArgs) -> Result<()> {
+    let platform_state = &mut ctx.accounts.platform_state;
+
+    let old_base_mint_fee = platform_state.base_mint_fee;
+    let old_fee_rate_per_thousand = platform_state.fee_rate_per_thousand;
+    let old_sol_to_points_ratio = platform_state.sol_to_points_ratio;
+    let old_max_supply = platform_state.max_supply;
+    let old_points_expiry_seconds = platform_state.points_expiry_seconds;
+    let old_referrer_bonus = platform_state.referrer_bonus;
+    let old_referee_bonus = platform_state.referee_bonus;
+
+    // Same invariants as initialize_platform
+    if let Some(ratio) = args.sol_to_points_ratio {
+        require!(ratio > 0, LoyaltyError::InvalidRatio);
+        platform_state.sol_to_points_ratio = ratio;
+    }
+    if let Some(max_supply) = args.max_supply {
+        require!(
+            max_supply >= platform_state.current_supply,
+            LoyaltyError::MaxSupplyBelowCurrentSupply
+        );
+        platform_state.max_supply = max_supply;
+    }
+    if let Some(expiry_seconds) = args.points_expiry_seconds {
+        // Applies to lots issued from now on; existing lots keep their expiry
+        require!(expiry_seconds >= 0, LoyaltyError::InvalidExpiryPeriod);
+        platform_state.points_expiry_seconds = expiry_seconds;
+    }
+    if let Some(base_mint_fee) = args.base_mint_fee {
+        platform_state.base_mint_fee = base_mint_fee;
+    }
+    if let Some(fee_rate_per_thousand) = args.fee_rate_per_thousand {
+        platform_state.fee_rate_per_thousand = fee_rate_per_thousand;
+    }
+    if let Some(referrer_bonus) = args.referrer_bonus {
+        platform_state.referrer_bonus = referrer_bonus;
+    }
+    if let Some(referee_bonus) = args.referee_bonus {
+        platform_state.referee_bonus = referee_bonus;
+    }
+
+    // Cash-out must stay at or below the mint price under the new settings
+    validate_buyback(platform_state)?;
+
+    msg!("Platform config updated by admin: {}", ctx.accounts.admin.key());
+    msg!(
+        "Base mint fee: {} -> {} lamports",
+        old_base_mint_fee,
+        platform_state.base_mint_fee
+    );
+    msg!(
+        "Fee rate: {} -> {} lamports per 1000 points",
+        old_fee_rate_per_thousand,
+        platform_state.fee_rate_per_thousand
+    );
+    msg!(
+        "SOL to points ratio: {} -> {}",
+        old_sol_to_points_ratio,
+        platform_state.sol_to_points_ratio
+    );
+    msg!("Max supply: {} -> {}", old_max_supply, platform_state.max_supply);
+    msg!(
+        "Points expiry: {} -> {} seconds",
+        old_points_expiry_seconds,
+        platform_state.points_expiry_seconds
+    );
+    msg!(
+        "Referral bonuses: referrer {} -> {}, referee {} -> {}",
+        old_referrer_bonus,
+        platform_state.referrer_bonus,
+        old_referee_bonus,
+        platform_state.referee_bonus
+    );
+
+    emit!(PlatformConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_base_mint_fee,
+        new_base_mint_fee: platform_state.base_mint_fee,
+        old_fee_rate_per_thousand,
+        new_fee_rate_per_thousand: platform_state.fee_rate_per_thousand,
+        old_sol_to_points_ratio,
+        new_sol_to_points_ratio: platform_state.sol_to_points_ratio,
+        old_max_supply,
+        new_max_supply: platform_state.max_supply,
+        old_points_expiry_seconds,
+        new_points_expiry_seconds: platform_state.points_expiry_seconds,
+        old_referrer_bonus,
+        new_referrer_bonus: platform_state.referrer_bonus,
+        old_referee_bonus,
+        new_referee_bonus: platform_state.referee_bonus,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct PlatformConfigUpdated {
+    pub admin: Pubkey,
+    pub old_base_mint_fee: u64,
+    pub new_base_mint_fee: u64,
+    pub old_fee_rate_per_thousand: u64,
+    pub new_fee_rate_per_thousand: u64,
+    pub old_sol_to_points_ratio: u64,
+    pub new_sol_to_points_ratio: u64,
+    pub old_max_supply: u64,
+    pub new_max_supply: u64,
+    pub old_points_expiry_seconds: i64,
+    pub new_points_expiry_seconds: i64,
+    pub old_referrer_bonus: u64,
+    pub new_referrer_bonus: u64,
+    pub old_referee_bonus: u64,
+    pub new_referee_bonus: u64,
+    pub timestamp: i64,
+}
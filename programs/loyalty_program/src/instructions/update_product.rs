@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::state::{MerchantRecord, PlatformState, Product};
+
+#[derive(Accounts)]
+pub struct UpdateProduct<'info> {
+    /// The merchant who owns the product
+    pub merchant: Signer<'info>,
+
+    /// Platform state
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.is_active @ LoyaltyError::PlatformInactive
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Merchant record - verify authorization
+    #[account(
+        seeds = [MerchantRecord::SEED, merchant.key().as_ref()],
+        bump = merchant_record.bump,
+        constraint = merchant_record.is_authorized @ LoyaltyError::UnauthorizedMerchant
+    )]
+    pub merchant_record: Account<'info, MerchantRecord>,
+
+    /// Product PDA - seeds bind it to the signing merchant
+    #[account(
+        mut,
+        seeds = [Product::SEED, merchant.key().as_ref(), &product.product_id_hash],
+        bump = product.bump
+    )]
+    pub product: Account<'info, Product>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateProduct>,
+    price_sol: u64,
+    price_points: u64,
+    loyalty_points_reward: u64,
+    is_available: bool,
+) -> Result<()> {
+    require!(
+        price_sol > 0 || price_points > 0,
+        LoyaltyError::InvalidProductPrice
+    );
+
+    let product = &mut ctx.accounts.product;
+    let clock = Clock::get()?;
+
+    product.price_sol = price_sol;
+    product.price_points = price_points;
+    product.loyalty_points_reward = loyalty_points_reward;
+    product.is_available = is_available;
+    product.updated_at = clock.unix_timestamp;
+
+    msg!(
+        "Product updated: product_hash={:?}, price_sol={}, price_points={}, reward={}, available={}",
+        &product.product_id_hash[..8],
+        price_sol,
+        price_points,
+        loyalty_points_reward,
+        is_available
+    );
+
+    emit!(ProductUpdated {
+        merchant: ctx.accounts.merchant.key(),
+        product_id_hash: product.product_id_hash,
+        price_sol,
+        price_points,
+        loyalty_points_reward,
+        is_available,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct ProductUpdated {
+    pub merchant: Pubkey,
+    pub product_id_hash: [u8; 32],
+    pub price_sol: u64,
+    pub price_points: u64,
+    pub loyalty_points_reward: u64,
+    pub is_available: bool,
+    pub timestamp: i64,
+}
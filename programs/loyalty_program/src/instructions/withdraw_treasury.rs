@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::pda::available_lamports;
+use crate::state::{PlatformState, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    /// Platform admin - only they can withdraw from the treasury
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        mut,
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Protocol treasury PDA - source of the withdrawal
+    #[account(
+        mut,
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// Destination wallet for the withdrawn lamports
+    /// CHECK: Any account chosen by the admin can receive lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, LoyaltyError::InvalidAmount);
+
+    let treasury_info = ctx.accounts.protocol_treasury.to_account_info();
+    let available = available_lamports(&treasury_info)?;
+    require!(
+        amount <= available,
+        LoyaltyError::InsufficientTreasuryBalance
+    );
+
+    // The treasury is owned by this program, so lamports can be moved directly
+    treasury_info.sub_lamports(amount)?;
+    ctx.accounts.destination.add_lamports(amount)?;
+
+    let platform_state = &mut ctx.accounts.platform_state;
+    platform_state.total_withdrawn = platform_state
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    msg!(
+        "Withdrew {} lamports from treasury to {}",
+        amount,
+        ctx.accounts.destination.key()
+    );
+
+    emit!(TreasuryWithdrawn {
+        admin: ctx.accounts.admin.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        total_withdrawn: platform_state.total_withdrawn,
+        remaining_balance: available - amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+    /// Lamports left above the rent-exempt minimum
+    pub remaining_balance: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::errors::LoyaltyError;
+use crate::state::{PlatformState, Treasury};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryTokens<'info> {
+    /// Platform admin - only they can withdraw from the treasury
+    pub admin: Signer<'info>,
+
+    /// Platform state - verify admin authority
+    #[account(
+        seeds = [PlatformState::SEED],
+        bump = platform_state.bump,
+        constraint = platform_state.admin == admin.key() @ LoyaltyError::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Protocol treasury PDA - authority over its token accounts
+    #[account(
+        seeds = [Treasury::SEED],
+        bump = protocol_treasury.bump
+    )]
+    pub protocol_treasury: Account<'info, Treasury>,
+
+    /// The payment mint being withdrawn
+    pub payment_token_mint: InterfaceAccount<'info, Mint>,
+
+    /// Treasury's token account for the payment mint - source of the withdrawal
+    #[account(
+        mut,
+        token::mint = payment_token_mint,
+        token::authority = protocol_treasury,
+        token::token_program = payment_token_program
+    )]
+    pub treasury_payment_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Destination token account of the same mint
+    #[account(
+        mut,
+        token::mint = payment_token_mint,
+        token::token_program = payment_token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<WithdrawTreasuryTokens>, amount: u64) -> Result<()> {
+    require!(amount > 0, LoyaltyError::InvalidAmount);
+    require!(
+        amount <= ctx.accounts.treasury_payment_account.amount,
+        LoyaltyError::InsufficientTreasuryBalance
+    );
+
+    let seeds = &[
+        Treasury::SEED,
+        &[ctx.accounts.protocol_treasury.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.payment_token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.treasury_payment_account.to_account_info(),
+                mint: ctx.accounts.payment_token_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.protocol_treasury.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        ctx.accounts.payment_token_mint.decimals,
+    )?;
+
+    msg!(
+        "Withdrew {} units of {} from treasury to {}",
+        amount,
+        ctx.accounts.treasury_payment_account.mint,
+        ctx.accounts.destination.key()
+    );
+
+    emit!(TreasuryTokensWithdrawn {
+        admin: ctx.accounts.admin.key(),
+        mint: ctx.accounts.treasury_payment_account.mint,
+        destination: ctx.accounts.destination.key(),
+        amount,
+        remaining_balance: ctx.accounts.treasury_payment_account.amount - amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct TreasuryTokensWithdrawn {
+    pub admin: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining_balance: u64,
+    pub timestamp: i64,
+}
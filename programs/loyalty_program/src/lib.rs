@@ -1,10 +1,20 @@
 use anchor_lang::prelude::*;
 
+pub mod campaigns;
 pub mod errors;
+pub mod fees;
 pub mod instructions;
+pub mod lots;
+pub mod loyalty_mint;
+pub mod math;
+pub mod pda;
+pub mod policy;
+pub mod receipts;
 pub mod state;
+pub mod tiers;
 
 use instructions::*;
+use state::{FeeSchedule, TierConfig};
 
 declare_id!("9RkPYyU3tB5X9g2TkBPiZHUrVNRZjwjZ3Eu8LZhK4LXj");
 
@@ -12,7 +22,8 @@ declare_id!("9RkPYyU3tB5X9g2TkBPiZHUrVNRZjwjZ3Eu8LZhK4LXj");
 pub mod loyalty_program {
     use super::*;
 
-    /// Initialize the loyalty platform with admin authority and create SPL token mint
+    /// Initialize the loyalty platform with admin authority and create the token mint
+    /// (SPL Token, or Token-2022 with optional extensions)
     pub fn initialize_platform(
         ctx: Context<InitializePlatform>,
         token_decimals: u8,
@@ -20,8 +31,98 @@ pub mod loyalty_program {
         base_mint_fee: u64,
         fee_rate_per_thousand: u64,
         sol_to_points_ratio: u64,
+        mint_extensions: u8,
     ) -> Result<()> {
-        instructions::initialize::handler(ctx, token_decimals, max_supply, base_mint_fee, fee_rate_per_thousand, sol_to_points_ratio)
+        instructions::initialize::handler(ctx, token_decimals, max_supply, base_mint_fee, fee_rate_per_thousand, sol_to_points_ratio, mint_extensions)
+    }
+
+    /// Update mint fees, conversion ratio, max supply, points expiry or referral bonuses (admin only)
+    pub fn update_platform_config(
+        ctx: Context<UpdatePlatformConfig>,
+        args: UpdatePlatformConfigArgs,
+    ) -> Result<()> {
+        instructions::update_platform_config::handler(ctx, args)
+    }
+
+    /// Update the bps mint fee and volume discounts (admin only)
+    pub fn set_fee_config(ctx: Context<SetFeeConfig>, args: FeeConfigArgs) -> Result<()> {
+        instructions::set_fee_config::handler(ctx, args)
+    }
+
+    /// Update the merchant cash-out buyback rate and spread (admin only)
+    pub fn set_buyback_config(ctx: Context<SetBuybackConfig>, args: BuybackConfigArgs) -> Result<()> {
+        instructions::set_buyback_config::handler(ctx, args)
+    }
+
+    /// Update the gift fee, daily cap, minimum account age and non-transferable gifting (admin only)
+    pub fn set_gift_config(ctx: Context<SetGiftConfig>, args: GiftConfigArgs) -> Result<()> {
+        instructions::set_gift_config::handler(ctx, args)
+    }
+
+    /// Update purchase record retention and the dispute window (admin only)
+    pub fn set_retention_config(
+        ctx: Context<SetRetentionConfig>,
+        args: RetentionConfigArgs,
+    ) -> Result<()> {
+        instructions::set_retention_config::handler(ctx, args)
+    }
+
+    /// Set consumer tier thresholds and earn multipliers (admin only)
+    pub fn set_tier_config(ctx: Context<SetTierConfig>, tier_config: TierConfig) -> Result<()> {
+        instructions::set_tier_config::handler(ctx, tier_config)
+    }
+
+    /// Pause the whole platform during an incident (admin only)
+    pub fn pause_platform(ctx: Context<PausePlatform>, reason_code: u8) -> Result<()> {
+        instructions::pause_platform::handler(ctx, reason_code)
+    }
+
+    /// Resume the platform after a pause (admin only)
+    pub fn unpause_platform(ctx: Context<UnpausePlatform>, reason_code: u8) -> Result<()> {
+        instructions::unpause_platform::handler(ctx, reason_code)
+    }
+
+    /// Pause or resume individual operations via a bitmask (admin only)
+    pub fn set_paused_operations(
+        ctx: Context<SetPausedOperations>,
+        paused_operations: u16,
+        reason_code: u8,
+    ) -> Result<()> {
+        instructions::set_paused_operations::handler(ctx, paused_operations, reason_code)
+    }
+
+    /// Propose a new platform admin (admin only)
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    /// Accept a pending admin transfer (pending admin only)
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    /// Cancel a pending admin transfer (admin only)
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        instructions::cancel_admin_transfer::handler(ctx)
+    }
+
+    /// Withdraw lamports from the protocol treasury PDA (admin only)
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury::handler(ctx, amount)
+    }
+
+    /// Withdraw payment tokens from a treasury token account (admin only)
+    pub fn withdraw_treasury_tokens(ctx: Context<WithdrawTreasuryTokens>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury_tokens::handler(ctx, amount)
+    }
+
+    /// Allowlist an SPL payment mint, or change its points ratio or status (admin only)
+    pub fn set_payment_mint(
+        ctx: Context<SetPaymentMint>,
+        points_per_token: u64,
+        is_enabled: bool,
+    ) -> Result<()> {
+        instructions::set_payment_mint::handler(ctx, points_per_token, is_enabled)
     }
 
     /// Register a new merchant (admin only)
@@ -37,7 +138,49 @@ pub mod loyalty_program {
         instructions::revoke_merchant::handler(ctx)
     }
 
-    /// Mint loyalty points to a consumer (merchant only)
+    /// Re-authorize a previously revoked merchant (admin only)
+    pub fn reinstate_merchant(ctx: Context<ReinstateMerchant>) -> Result<()> {
+        instructions::reinstate_merchant::handler(ctx)
+    }
+
+    /// Change a merchant's mint allowance (admin only)
+    pub fn set_merchant_allowance(
+        ctx: Context<SetMerchantAllowance>,
+        mint_allowance: u64,
+    ) -> Result<()> {
+        instructions::set_merchant_allowance::handler(ctx, mint_allowance)
+    }
+
+    /// Set or clear a merchant's negotiated fee schedule (admin only)
+    pub fn set_merchant_fee_override(
+        ctx: Context<SetMerchantFeeOverride>,
+        fee_override: Option<FeeSchedule>,
+    ) -> Result<()> {
+        instructions::set_merchant_fee_override::handler(ctx, fee_override)
+    }
+
+    /// Authorize a staff wallet or POS device to mint with caps and an optional expiry (merchant only)
+    pub fn create_merchant_delegate(
+        ctx: Context<CreateMerchantDelegate>,
+        per_tx_cap: u64,
+        daily_cap: u64,
+        expires_at: i64,
+        fee_deposit: u64,
+    ) -> Result<()> {
+        instructions::create_merchant_delegate::handler(ctx, per_tx_cap, daily_cap, expires_at, fee_deposit)
+    }
+
+    /// Top up a delegate's fee deposit (merchant only)
+    pub fn fund_merchant_delegate(ctx: Context<FundMerchantDelegate>, amount: u64) -> Result<()> {
+        instructions::fund_merchant_delegate::handler(ctx, amount)
+    }
+
+    /// Revoke a delegate and reclaim its rent and unused fee deposit (merchant only)
+    pub fn revoke_merchant_delegate(ctx: Context<RevokeMerchantDelegate>) -> Result<()> {
+        instructions::revoke_merchant_delegate::handler(ctx)
+    }
+
+    /// Mint loyalty points to a consumer (merchant or merchant delegate)
     pub fn mint_points(
         ctx: Context<MintPoints>,
         amount: u64,
@@ -46,26 +189,119 @@ pub mod loyalty_program {
         instructions::mint_points::handler(ctx, amount, purchase_reference)
     }
 
-    /// Purchase product with SOL and earn loyalty points
-    pub fn purchase_product_with_sol(
-        ctx: Context<PurchaseProductWithSol>,
+    /// Mint loyalty points to up to 18 consumers for one aggregated fee, without campaigns
+    /// or receipts (merchant only)
+    pub fn batch_mint_points<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchMintPoints<'info>>,
+        entries: Vec<BatchMintEntry>,
+    ) -> Result<()> {
+        instructions::batch_mint_points::handler(ctx, entries)
+    }
+
+    /// Close a mint receipt and reclaim its rent (merchant only)
+    pub fn close_mint_receipt(ctx: Context<CloseMintReceipt>) -> Result<()> {
+        instructions::close_mint_receipt::handler(ctx)
+    }
+
+    /// List a product with on-chain pricing (merchant only)
+    pub fn create_product(
+        ctx: Context<CreateProduct>,
         product_id_hash: [u8; 32],
         price_sol: u64,
+        price_points: u64,
         loyalty_points_reward: u64,
-        nonce: u64,
     ) -> Result<()> {
-        instructions::purchase_product::handler(ctx, product_id_hash, price_sol, loyalty_points_reward, nonce)
+        instructions::create_product::handler(ctx, product_id_hash, price_sol, price_points, loyalty_points_reward)
+    }
+
+    /// Update a product's pricing, reward and availability (merchant only)
+    pub fn update_product(
+        ctx: Context<UpdateProduct>,
+        price_sol: u64,
+        price_points: u64,
+        loyalty_points_reward: u64,
+        is_available: bool,
+    ) -> Result<()> {
+        instructions::update_product::handler(ctx, price_sol, price_points, loyalty_points_reward, is_available)
+    }
+
+    /// Set a product's price in an allowlisted payment mint, overriding the points price conversion (merchant only)
+    pub fn set_product_token_price(ctx: Context<SetProductTokenPrice>, price_tokens: u64) -> Result<()> {
+        instructions::set_product_token_price::handler(ctx, price_tokens)
+    }
+
+    /// Start a time-boxed bonus points campaign (merchant only)
+    pub fn create_campaign(
+        ctx: Context<CreateCampaign>,
+        campaign_id: u64,
+        start_at: i64,
+        end_at: i64,
+        product_id_hash: Option<[u8; 32]>,
+        bonus_bps: u16,
+        budget: u64,
+    ) -> Result<()> {
+        instructions::create_campaign::handler(ctx, campaign_id, start_at, end_at, product_id_hash, bonus_bps, budget)
+    }
+
+    /// Close a campaign and reclaim its rent (merchant only)
+    pub fn close_campaign(ctx: Context<CloseCampaign>) -> Result<()> {
+        instructions::close_campaign::handler(ctx)
+    }
+
+    /// Purchase product with SOL and earn loyalty points.
+    /// Fails if the listed price is above `max_price` lamports.
+    pub fn purchase_product_with_sol(
+        ctx: Context<PurchaseProductWithSol>,
+        product_id_hash: [u8; 32],
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::purchase_product::handler(ctx, product_id_hash, max_price)
+    }
+
+    /// Purchase product with an allowlisted SPL token and earn loyalty points.
+    /// Fails if the price is above `max_price` payment token units.
+    pub fn purchase_product_with_token(
+        ctx: Context<PurchaseProductWithToken>,
+        product_id_hash: [u8; 32],
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::purchase_with_token::handler(ctx, product_id_hash, max_price)
+    }
+
+    /// Purchase product paying partly in points (burned) and the remainder in SOL.
+    /// Fails if the SOL remainder is above `max_price` lamports.
+    pub fn purchase_product_mixed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseProductMixed<'info>>,
+        product_id_hash: [u8; 32],
+        points_amount: u64,
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::purchase_mixed::handler(ctx, product_id_hash, points_amount, max_price)
+    }
+
+    /// Register the consumer who referred the signer (once, before the first purchase)
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::register_referrer::handler(ctx)
     }
 
     /// Redeem loyalty points at a merchant (consumer)
-    pub fn redeem_points(
-        ctx: Context<RedeemPoints>,
+    pub fn redeem_points<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemPoints<'info>>,
         amount: u64,
         reward_id: String,
     ) -> Result<()> {
         instructions::redeem_points::handler(ctx, amount, reward_id)
     }
 
+    /// Gift loyalty points to another consumer, subject to the daily cap and account age.
+    /// The recipient gets a lot expiring with the earliest of the lots the points came from.
+    pub fn gift_points<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GiftPoints<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::gift_points::handler(ctx, amount)
+    }
+
     /// Merchant deposits SOL to receive loyalty points
     pub fn deposit_sol(
         ctx: Context<DepositSol>,
@@ -74,17 +310,72 @@ pub mod loyalty_program {
         instructions::deposit_sol::handler(ctx, sol_amount)
     }
 
-    /// Purchase product with loyalty points (burns points)
-    pub fn purchase_product_with_points(
-        ctx: Context<PurchaseProductWithPoints>,
+    /// Merchant deposits an allowlisted SPL token to receive loyalty points
+    pub fn deposit_token(ctx: Context<DepositToken>, token_amount: u64) -> Result<()> {
+        instructions::deposit_token::handler(ctx, token_amount)
+    }
+
+    /// Burn merchant-held points for lamports from the treasury at the buyback rate
+    pub fn merchant_cash_out(ctx: Context<MerchantCashOut>, points_amount: u64) -> Result<()> {
+        instructions::merchant_cash_out::handler(ctx, points_amount)
+    }
+
+    /// Purchase product with loyalty points (burns points).
+    /// Fails if the price is above `max_price` points.
+    pub fn purchase_product_with_points<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseProductWithPoints<'info>>,
         product_id_hash: [u8; 32],
-        points_amount: u64,
-        nonce: u64,
+        max_price: u64,
+    ) -> Result<()> {
+        instructions::purchase_with_points::handler(ctx, product_id_hash, max_price)
+    }
+
+    /// Refund a purchase: return SOL and claw back the reward, or re-mint spent points (merchant only)
+    pub fn refund_purchase<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundPurchase<'info>>,
     ) -> Result<()> {
-        instructions::purchase_with_points::handler(ctx, product_id_hash, points_amount, nonce)
+        instructions::refund_purchase::handler(ctx)
+    }
+
+    /// Mark a purchase as delivered (merchant only)
+    pub fn fulfill_purchase(ctx: Context<FulfillPurchase>) -> Result<()> {
+        instructions::fulfill_purchase::handler(ctx)
+    }
+
+    /// Dispute a purchase within the platform's dispute window (customer)
+    pub fn dispute_purchase(ctx: Context<DisputePurchase>, reason_code: u8) -> Result<()> {
+        instructions::dispute_purchase::handler(ctx, reason_code)
+    }
+
+    /// Rule on a disputed purchase, optionally ordering a refund (admin only)
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, refund: bool) -> Result<()> {
+        instructions::resolve_dispute::handler(ctx, refund)
+    }
+
+    /// Mark a fulfilled purchase final once its dispute window has closed, so the customer can close its record (merchant only)
+    pub fn finalize_purchase(ctx: Context<FinalizePurchase>) -> Result<()> {
+        instructions::finalize_purchase::handler(ctx)
+    }
+
+    /// Close a final or retention-expired purchase record and reclaim its rent (customer)
+    pub fn close_purchase_record(ctx: Context<ClosePurchaseRecord>) -> Result<()> {
+        instructions::close_purchase_record::handler(ctx)
+    }
+
+    /// Opt in to crank-driven expiry by approving the platform to burn up to `allowance` points
+    /// (consumer only, SPL mints without a permanent delegate)
+    pub fn set_expiry_delegate(ctx: Context<SetExpiryDelegate>, allowance: u64) -> Result<()> {
+        instructions::set_expiry_delegate::handler(ctx, allowance)
+    }
+
+    /// Close a consumer's oldest points lot once it has expired and burn what the platform
+    /// may; the rest is owed on the profile until the next burn (permissionless)
+    pub fn expire_points(ctx: Context<ExpirePoints>) -> Result<()> {
+        instructions::expire_points::handler(ctx)
     }
 
-    /// Set Metaplex token metadata (name, symbol, uri) for the loyalty token
+    /// Set the loyalty token's name, symbol and uri: in Metaplex metadata for SPL mints, or
+    /// on the Token-2022 mint itself with the platform PDA as update authority
     pub fn set_token_metadata(
         ctx: Context<SetTokenMetadata>,
         name: String,
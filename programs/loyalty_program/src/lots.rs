@@ -0,0 +1,284 @@
+//! FIFO bookkeeping for expiring points lots.
+//!
+//! Every issuance to a consumer opens a [`PointsLot`]. Lots in
+//! `[head_lot_index, next_lot_index)` are open; spends consume them from the
+//! head, and `expire_points` burns the head lot once it is past `expires_at`.
+//! A spend that reaches an expired lot before the crank does closes it and
+//! burns its points, then keeps drawing from the next lot. Expired points the
+//! platform can't burn are still closed out of the lots and supply, and stay
+//! owed on the profile until a later burn catches up with them.
+//!
+//! Instructions that reach consumers through remaining accounts (batches,
+//! referrers) can't use `init`, so they create profiles and lots with
+//! [`load_or_create_profile`] and [`create_lot_account`] instead.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::{burn, Burn, TokenAccount};
+
+use crate::errors::LoyaltyError;
+use crate::math::format_points;
+use crate::pda::create_pda_account;
+use crate::state::{ConsumerProfile, PlatformState, PointsLot};
+
+/// Open a new lot for `amount` points at the profile's next index
+pub fn open_lot(
+    profile: &mut ConsumerProfile,
+    lot: &mut PointsLot,
+    lot_bump: u8,
+    amount: u64,
+    expiry_seconds: i64,
+    now: i64,
+) -> Result<()> {
+    let expires_at = if expiry_seconds > 0 {
+        now.checked_add(expiry_seconds)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?
+    } else {
+        0
+    };
+    open_lot_until(profile, lot, lot_bump, amount, expires_at, now)
+}
+
+/// Open a new lot that expires at `expires_at` (0 = never), e.g. gifted points
+/// keeping the expiry of the lots they came from
+pub fn open_lot_until(
+    profile: &mut ConsumerProfile,
+    lot: &mut PointsLot,
+    lot_bump: u8,
+    amount: u64,
+    expires_at: i64,
+    now: i64,
+) -> Result<()> {
+    lot.consumer = profile.consumer;
+    lot.index = profile.next_lot_index;
+    lot.amount = amount;
+    lot.remaining = amount;
+    lot.issued_at = now;
+    lot.expires_at = expires_at;
+    lot.bump = lot_bump;
+
+    profile.next_lot_index = profile
+        .next_lot_index
+        .checked_add(1)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    profile.lot_balance = profile
+        .lot_balance
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    if profile.first_earned_at == 0 {
+        profile.first_earned_at = now;
+    }
+
+    Ok(())
+}
+
+/// Load `consumer`'s profile from `profile_info`, creating it if it doesn't exist yet.
+/// Changes are persisted with [`store_account`].
+pub fn load_or_create_profile<'info>(
+    profile_info: &AccountInfo<'info>,
+    consumer: Pubkey,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<ConsumerProfile> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[ConsumerProfile::SEED, consumer.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(profile_info.key(), expected, LoyaltyError::InvalidConsumerProfile);
+
+    if profile_info.owner == &crate::ID {
+        return ConsumerProfile::try_deserialize(&mut &profile_info.try_borrow_data()?[..]);
+    }
+
+    create_pda_account(
+        profile_info,
+        payer,
+        system_program,
+        8 + ConsumerProfile::INIT_SPACE,
+        &[ConsumerProfile::SEED, consumer.as_ref(), &[bump]],
+    )?;
+    Ok(ConsumerProfile {
+        consumer,
+        bump,
+        ..Default::default()
+    })
+}
+
+/// Create the lot account at `profile`'s next index, returning an empty lot and its
+/// bump for [`open_lot`]. The opened lot is persisted with [`store_account`].
+pub fn create_lot_account<'info>(
+    lot_info: &AccountInfo<'info>,
+    profile: &ConsumerProfile,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<(PointsLot, u8)> {
+    let index_bytes = profile.next_lot_index.to_le_bytes();
+    let (expected, bump) = Pubkey::find_program_address(
+        &[PointsLot::SEED, profile.consumer.as_ref(), &index_bytes],
+        &crate::ID,
+    );
+    require_keys_eq!(lot_info.key(), expected, LoyaltyError::InvalidPointsLot);
+    require!(lot_info.owner != &crate::ID, LoyaltyError::InvalidPointsLot);
+
+    create_pda_account(
+        lot_info,
+        payer,
+        system_program,
+        8 + PointsLot::INIT_SPACE,
+        &[PointsLot::SEED, profile.consumer.as_ref(), &index_bytes, &[bump]],
+    )?;
+
+    Ok((PointsLot::default(), bump))
+}
+
+/// Write `account` (with its discriminator) into a program-owned account's data
+pub fn store_account<T: AccountSerialize>(info: &AccountInfo, account: &T) -> Result<()> {
+    account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+}
+
+/// Points taken from a consumer's lots by [`consume_lots`]
+pub struct LotsConsumed {
+    /// Points drawn from unexpired lots
+    pub consumed: u64,
+    /// Points left in expired lots that were closed on the way - the caller burns them
+    pub expired: u64,
+    /// Earliest `expires_at` among the lots drawn from (0 = none of them expire)
+    pub earliest_expiry: i64,
+}
+
+/// Consume up to `amount` points from open lots, oldest first.
+///
+/// `lot_accounts` must be the consumer's open lots in index order starting at
+/// the head. Points beyond the tracked lot balance (e.g. received by transfer)
+/// are not lot-backed and need no accounts. Expired lots are closed without
+/// counting towards `amount`, and emptied lots are closed too; their rent goes
+/// to `rent_destination`.
+pub fn consume_lots<'info>(
+    profile: &mut ConsumerProfile,
+    lot_accounts: &'info [AccountInfo<'info>],
+    amount: u64,
+    rent_destination: &AccountInfo<'info>,
+    now: i64,
+) -> Result<LotsConsumed> {
+    let mut consumed = 0u64;
+    let mut expired = 0u64;
+    let mut earliest_expiry = 0i64;
+    let mut lot_infos = lot_accounts.iter();
+
+    while consumed < amount && profile.lot_balance > 0 {
+        let lot_info = lot_infos.next().ok_or(LoyaltyError::MissingPointsLots)?;
+        let mut lot = Account::<PointsLot>::try_from(lot_info)?;
+
+        require!(
+            lot.consumer == profile.consumer && lot.index == profile.head_lot_index,
+            LoyaltyError::InvalidPointsLot
+        );
+
+        if lot.is_expired(now) {
+            // Expired points can't pay for anything - drop the lot and move on
+            expired = expired
+                .checked_add(lot.remaining)
+                .ok_or(LoyaltyError::ArithmeticOverflow)?;
+            profile.lot_balance = profile
+                .lot_balance
+                .checked_sub(lot.remaining)
+                .ok_or(LoyaltyError::ArithmeticOverflow)?;
+            lot.remaining = 0;
+        } else {
+            let taken = lot.remaining.min(amount - consumed);
+            if lot.expires_at > 0 && (earliest_expiry == 0 || lot.expires_at < earliest_expiry) {
+                earliest_expiry = lot.expires_at;
+            }
+            lot.remaining -= taken;
+            consumed += taken;
+            profile.lot_balance = profile
+                .lot_balance
+                .checked_sub(taken)
+                .ok_or(LoyaltyError::ArithmeticOverflow)?;
+        }
+
+        if lot.remaining == 0 {
+            profile.head_lot_index = profile
+                .head_lot_index
+                .checked_add(1)
+                .ok_or(LoyaltyError::ArithmeticOverflow)?;
+            lot.close(rent_destination.clone())?;
+        } else {
+            lot.exit(&crate::ID)?;
+        }
+    }
+
+    Ok(LotsConsumed {
+        consumed,
+        expired,
+        earliest_expiry,
+    })
+}
+
+/// Burn the points of expired lots closed by [`consume_lots`] or `expire_points`.
+///
+/// `authority` is the token account owner when the consumer signs, or the
+/// platform PDA (with `signer_seeds`) on merchant-signed paths and the crank;
+/// `burnable` is how much that authority may burn. Expired points leave
+/// `current_supply` straight away, burned or not. Whatever can't be burned
+/// (moved out of the account or not approved) is owed on the profile and burned
+/// first the next time expired points are burned. Returns the amount burned.
+#[allow(clippy::too_many_arguments)]
+pub fn burn_expired<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    platform_state: &mut PlatformState,
+    profile: &mut ConsumerProfile,
+    expired: u64,
+    burnable: u64,
+) -> Result<u64> {
+    // Supply already excludes points owed from earlier expiries; it can only
+    // fall short if they were burned again after moving to another account
+    platform_state.current_supply = platform_state.current_supply.saturating_sub(expired);
+
+    let owed = profile
+        .expired_unburned
+        .checked_add(expired)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    let burn_amount = owed.min(burnable).min(token_account.amount);
+    profile.expired_unburned = owed - burn_amount;
+    if burn_amount == 0 {
+        return Ok(0);
+    }
+
+    burn(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Burn {
+                mint: mint.clone(),
+                from: token_account.to_account_info(),
+                authority: authority.clone(),
+            },
+            signer_seeds,
+        ),
+        burn_amount,
+    )?;
+
+    msg!(
+        "Burned {} expired points from {}",
+        format_points(burn_amount, platform_state.token_decimals),
+        token_account.owner
+    );
+
+    Ok(burn_amount)
+}
+
+/// Points the platform PDA may burn from `token_account`, either as the
+/// mint's permanent delegate or through the owner's approval
+pub fn platform_burnable(platform_state: &Account<PlatformState>, token_account: &TokenAccount) -> u64 {
+    if platform_state.has_mint_extension(PlatformState::EXT_PERMANENT_DELEGATE) {
+        token_account.amount
+    } else if token_account.delegate == COption::Some(platform_state.key()) {
+        token_account.delegated_amount.min(token_account.amount)
+    } else {
+        0
+    }
+}
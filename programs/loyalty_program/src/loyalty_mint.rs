@@ -0,0 +1,160 @@
+//! Creation of the loyalty points mint.
+//!
+//! The mint lives at the `loyalty_mint` PDA and is owned by either the classic
+//! SPL Token program or Token-2022. Token-2022 mints can opt into the
+//! extensions in `PlatformState::EXT_*`; extensions are initialized before the
+//! mint itself, as Token-2022 requires. Every other instruction goes through
+//! `anchor_spl::token_interface`, so it works with either program.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+use anchor_spl::token_2022::spl_token_2022::{extension::ExtensionType, state::Mint};
+use anchor_spl::token_interface::{
+    initialize_mint2, metadata_pointer_initialize, non_transferable_mint_initialize,
+    permanent_delegate_initialize, InitializeMint2, MetadataPointerInitialize,
+    NonTransferableMintInitialize, PermanentDelegateInitialize,
+};
+
+use crate::errors::LoyaltyError;
+use crate::state::PlatformState;
+
+/// Token-2022 extension types for an `EXT_*` bitmask
+pub fn extension_types(extensions: u8) -> Vec<ExtensionType> {
+    let mut types = Vec::new();
+    if extensions & PlatformState::EXT_NON_TRANSFERABLE != 0 {
+        types.push(ExtensionType::NonTransferable);
+    }
+    if extensions & PlatformState::EXT_PERMANENT_DELEGATE != 0 {
+        types.push(ExtensionType::PermanentDelegate);
+    }
+    if extensions & PlatformState::EXT_METADATA_POINTER != 0 {
+        types.push(ExtensionType::MetadataPointer);
+    }
+    types
+}
+
+/// Mint account size for an `EXT_*` bitmask (the classic size when empty)
+pub fn mint_space(extensions: u8) -> Result<usize> {
+    ExtensionType::try_calculate_account_len::<Mint>(&extension_types(extensions))
+        .map_err(|_| LoyaltyError::InvalidMintExtensions.into())
+}
+
+/// Validate an `EXT_*` bitmask against the token program creating the mint
+pub fn validate_extensions(extensions: u8, token_program: &Pubkey) -> Result<()> {
+    require!(
+        extensions & !PlatformState::EXT_ALL == 0
+            && (extensions == 0 || *token_program == anchor_spl::token_2022::ID),
+        LoyaltyError::InvalidMintExtensions
+    );
+    Ok(())
+}
+
+/// Create and initialize the loyalty mint with `platform_state` as mint,
+/// freeze and (optionally) permanent delegate authority
+#[allow(clippy::too_many_arguments)]
+pub fn create_loyalty_mint<'info>(
+    payer: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    mint_bump: u8,
+    platform_state: &Pubkey,
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    decimals: u8,
+    extensions: u8,
+) -> Result<()> {
+    validate_extensions(extensions, token_program.key)?;
+
+    let space = mint_space(extensions)?;
+    let mint_seeds = &[&b"loyalty_mint"[..], &[mint_bump]];
+    create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: mint.clone(),
+            },
+            &[&mint_seeds[..]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        token_program.key,
+    )?;
+
+    if extensions & PlatformState::EXT_NON_TRANSFERABLE != 0 {
+        non_transferable_mint_initialize(CpiContext::new(
+            token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ))?;
+    }
+    if extensions & PlatformState::EXT_PERMANENT_DELEGATE != 0 {
+        permanent_delegate_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                PermanentDelegateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            platform_state,
+        )?;
+    }
+    if extensions & PlatformState::EXT_METADATA_POINTER != 0 {
+        // Metadata is embedded in the mint account itself
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(*platform_state),
+            Some(mint.key()),
+        )?;
+    }
+
+    initialize_mint2(
+        CpiContext::new(
+            token_program.clone(),
+            InitializeMint2 { mint: mint.clone() },
+        ),
+        decimals,
+        platform_state,
+        Some(platform_state),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::program_pack::Pack;
+
+    #[test]
+    fn classic_mint_has_base_size() {
+        assert!(extension_types(0).is_empty());
+        assert_eq!(mint_space(0).unwrap(), Mint::LEN);
+    }
+
+    #[test]
+    fn extensions_grow_the_mint() {
+        assert_eq!(extension_types(PlatformState::EXT_ALL).len(), 3);
+        assert!(mint_space(PlatformState::EXT_NON_TRANSFERABLE).unwrap() > Mint::LEN);
+        assert!(
+            mint_space(PlatformState::EXT_ALL).unwrap()
+                > mint_space(PlatformState::EXT_NON_TRANSFERABLE).unwrap()
+        );
+    }
+
+    #[test]
+    fn extensions_require_token_2022() {
+        let classic = anchor_spl::token::ID;
+        let token_2022 = anchor_spl::token_2022::ID;
+        assert!(validate_extensions(0, &classic).is_ok());
+        assert!(validate_extensions(PlatformState::EXT_NON_TRANSFERABLE, &classic).is_err());
+        assert!(validate_extensions(PlatformState::EXT_ALL, &token_2022).is_ok());
+        assert!(validate_extensions(1 << 7, &token_2022).is_err());
+    }
+}
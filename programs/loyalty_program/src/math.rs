@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+
+use crate::errors::LoyaltyError;
+
+/// Largest supported token decimals (10^9 still fits comfortably in u64)
+pub const MAX_DECIMALS: u8 = 9;
+
+/// Length of the UTC day used by daily caps
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Raw token units per whole loyalty point, i.e. 10^decimals
+pub fn decimals_multiplier(decimals: u8) -> Result<u64> {
+    require!(decimals <= MAX_DECIMALS, LoyaltyError::InvalidDecimals);
+    Ok(10u64.pow(decimals as u32))
+}
+
+/// Convert lamports to raw loyalty token units at `ratio` whole points per SOL
+///
+/// points = lamports * ratio * 10^decimals / LAMPORTS_PER_SOL, computed in u128
+/// so 9-decimal mints don't overflow on the intermediate product.
+pub fn lamports_to_points(lamports: u64, ratio: u64, decimals: u8) -> Result<u64> {
+    let multiplier = decimals_multiplier(decimals)? as u128;
+    let points = (lamports as u128)
+        .checked_mul(ratio as u128)
+        .and_then(|v| v.checked_mul(multiplier))
+        .ok_or(LoyaltyError::ArithmeticOverflow)?
+        / LAMPORTS_PER_SOL as u128;
+
+    u64::try_from(points).map_err(|_| LoyaltyError::ArithmeticOverflow.into())
+}
+
+/// Convert raw loyalty token units to lamports at `ratio` whole points per SOL
+///
+/// Inverse of [`lamports_to_points`], rounding down so points never cover
+/// more than their value.
+pub fn points_to_lamports(points: u64, ratio: u64, decimals: u8) -> Result<u64> {
+    require!(ratio > 0, LoyaltyError::InvalidRatio);
+    let multiplier = decimals_multiplier(decimals)? as u128;
+    let lamports = (points as u128)
+        .checked_mul(LAMPORTS_PER_SOL as u128)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?
+        / (ratio as u128 * multiplier);
+
+    u64::try_from(lamports).map_err(|_| LoyaltyError::ArithmeticOverflow.into())
+}
+
+/// Convert raw loyalty token units to raw payment token units at
+/// `points_per_token` whole points per whole payment token, rounding up so a
+/// price or fee is never undercharged.
+pub fn points_to_tokens(
+    points: u64,
+    points_per_token: u64,
+    points_decimals: u8,
+    token_decimals: u8,
+) -> Result<u64> {
+    require!(points_per_token > 0, LoyaltyError::InvalidRatio);
+    let divisor = points_per_token as u128 * decimals_multiplier(points_decimals)? as u128;
+    let tokens = (points as u128)
+        .checked_mul(decimals_multiplier(token_decimals)? as u128)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?
+        .div_ceil(divisor);
+
+    u64::try_from(tokens).map_err(|_| LoyaltyError::ArithmeticOverflow.into())
+}
+
+/// Convert raw payment token units to raw loyalty token units at
+/// `points_per_token` whole points per whole payment token, rounding down
+pub fn tokens_to_points(
+    tokens: u64,
+    points_per_token: u64,
+    points_decimals: u8,
+    token_decimals: u8,
+) -> Result<u64> {
+    let multiplier = decimals_multiplier(points_decimals)? as u128;
+    let points = (tokens as u128)
+        .checked_mul(points_per_token as u128)
+        .and_then(|v| v.checked_mul(multiplier))
+        .ok_or(LoyaltyError::ArithmeticOverflow)?
+        / decimals_multiplier(token_decimals)? as u128;
+
+    u64::try_from(points).map_err(|_| LoyaltyError::ArithmeticOverflow.into())
+}
+
+/// Basis points denominator (10_000 bps = 100%)
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Scale `amount` by `bps` basis points, rounding down
+pub fn apply_bps(amount: u64, bps: u16) -> Result<u64> {
+    let scaled = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?
+        / BPS_DENOMINATOR as u128;
+
+    u64::try_from(scaled).map_err(|_| LoyaltyError::ArithmeticOverflow.into())
+}
+
+/// Render a raw token amount as whole points with its fractional part, for logs
+pub fn format_points(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let multiplier = 10u64.pow(decimals as u32);
+    format!(
+        "{}.{:0width$}",
+        amount / multiplier,
+        amount % multiplier,
+        width = decimals as usize
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_SOL: u64 = LAMPORTS_PER_SOL;
+
+    #[test]
+    fn multiplier_matches_decimals() {
+        assert_eq!(decimals_multiplier(0).unwrap(), 1);
+        assert_eq!(decimals_multiplier(6).unwrap(), 1_000_000);
+        assert_eq!(decimals_multiplier(9).unwrap(), 1_000_000_000);
+        assert!(decimals_multiplier(10).is_err());
+    }
+
+    #[test]
+    fn converts_lamports_with_zero_decimals() {
+        assert_eq!(lamports_to_points(ONE_SOL, 100, 0).unwrap(), 100);
+        assert_eq!(lamports_to_points(ONE_SOL / 2, 100, 0).unwrap(), 50);
+        // Sub-point remainders round down
+        assert_eq!(lamports_to_points(ONE_SOL / 1_000, 100, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn converts_lamports_with_six_decimals() {
+        assert_eq!(lamports_to_points(ONE_SOL, 100, 6).unwrap(), 100_000_000);
+        assert_eq!(lamports_to_points(ONE_SOL / 2, 100, 6).unwrap(), 50_000_000);
+    }
+
+    #[test]
+    fn converts_lamports_with_nine_decimals() {
+        assert_eq!(lamports_to_points(ONE_SOL, 100, 9).unwrap(), 100_000_000_000);
+        // 1_000 SOL * 100 * 10^9 overflows u64 in the intermediate product
+        assert_eq!(
+            lamports_to_points(1_000 * ONE_SOL, 100, 9).unwrap(),
+            100_000_000_000_000
+        );
+    }
+
+    #[test]
+    fn converts_points_back_to_lamports() {
+        assert_eq!(points_to_lamports(100_000_000, 100, 6).unwrap(), ONE_SOL);
+        assert_eq!(points_to_lamports(50, 100, 0).unwrap(), ONE_SOL / 2);
+        // Round-trips with lamports_to_points
+        let points = lamports_to_points(ONE_SOL / 3, 100, 9).unwrap();
+        assert!(points_to_lamports(points, 100, 9).unwrap() <= ONE_SOL / 3);
+        assert!(points_to_lamports(1, 0, 6).is_err());
+    }
+
+    #[test]
+    fn converts_between_points_and_payment_tokens() {
+        // 100 points per whole 6-decimal token, points with 9 decimals
+        assert_eq!(tokens_to_points(1_000_000, 100, 9, 6).unwrap(), 100_000_000_000);
+        assert_eq!(points_to_tokens(100_000_000_000, 100, 9, 6).unwrap(), 1_000_000);
+        // Prices and fees in payment tokens round up
+        assert_eq!(points_to_tokens(1, 100, 9, 6).unwrap(), 1);
+        assert!(points_to_tokens(1, 0, 6, 6).is_err());
+    }
+
+    #[test]
+    fn applies_basis_points() {
+        assert_eq!(apply_bps(1_000_000, 10_000).unwrap(), 1_000_000);
+        assert_eq!(apply_bps(1_000_000, 15_000).unwrap(), 1_500_000);
+        // Fractions of a raw unit round down
+        assert_eq!(apply_bps(3, 12_500).unwrap(), 3);
+        assert!(apply_bps(u64::MAX, 20_000).is_err());
+    }
+
+    #[test]
+    fn formats_points_for_each_decimals() {
+        assert_eq!(format_points(42, 0), "42");
+        assert_eq!(format_points(1_500_000, 6), "1.500000");
+        assert_eq!(format_points(1_000_000_001, 9), "1.000000001");
+    }
+}
@@ -0,0 +1,81 @@
+//! Helpers for program-owned PDAs.
+//!
+//! The treasury and merchant delegates both hold spendable SOL on top of
+//! their rent. Anything paid out of them must leave the rent-exempt minimum
+//! in place, so every balance check goes through [`available_lamports`].
+//!
+//! Accounts created by hand rather than with `init` go through
+//! [`create_pda_account`], which mirrors Anchor's own init so a PDA that
+//! someone pre-funded with lamports can still be created.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer};
+
+/// Lamports held above the rent-exempt minimum
+pub fn available_lamports(account: &AccountInfo) -> Result<u64> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(account.data_len());
+    Ok(account.lamports().saturating_sub(rent_exempt_minimum))
+}
+
+/// Create a program-owned PDA with `space` bytes, paying only the rent it is missing
+pub fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+
+    if current_lamports == 0 {
+        return create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                CreateAccount {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent_exempt_minimum,
+            space as u64,
+            &crate::ID,
+        );
+    }
+
+    // Someone already sent lamports to the address - top up, then allocate and assign
+    let missing_lamports = rent_exempt_minimum.max(1).saturating_sub(current_lamports);
+    if missing_lamports > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.clone(),
+                Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            missing_lamports,
+        )?;
+    }
+    allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            Assign {
+                account_to_assign: account.clone(),
+            },
+            &[signer_seeds],
+        ),
+        &crate::ID,
+    )
+}
@@ -0,0 +1,123 @@
+//! Minting policy shared by every instruction that issues loyalty points.
+//!
+//! Any path that mints must call [`check_mint_limits`] before the `mint_to`
+//! CPI and [`record_mint`] after it, so `max_supply` and merchant
+//! `mint_allowance` are enforced identically everywhere. Platform-funded
+//! points (e.g. referral bonuses) use [`check_supply`] and [`record_supply`].
+//! Mints signed by a merchant delegate also go through [`record_delegate_mint`].
+
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::math::SECONDS_PER_DAY;
+use crate::state::{MerchantDelegate, MerchantRecord, PlatformState};
+
+/// Ensure minting `amount` keeps the platform within `max_supply`
+pub fn check_supply(platform_state: &PlatformState, amount: u64) -> Result<()> {
+    let new_supply = platform_state
+        .current_supply
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    require!(
+        new_supply <= platform_state.max_supply,
+        LoyaltyError::ExceedsMaxSupply
+    );
+
+    Ok(())
+}
+
+/// Ensure minting `amount` keeps the merchant within its `mint_allowance`
+/// (an allowance of 0 means unlimited within the platform max supply)
+pub fn check_allowance(merchant_record: &MerchantRecord, amount: u64) -> Result<()> {
+    if merchant_record.mint_allowance == 0 {
+        return Ok(());
+    }
+
+    let new_total = merchant_record
+        .total_minted
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    require!(
+        new_total <= merchant_record.mint_allowance,
+        LoyaltyError::ExceedsMintAllowance
+    );
+
+    Ok(())
+}
+
+/// Supply and allowance checks for points minted on a merchant's account
+pub fn check_mint_limits(
+    platform_state: &PlatformState,
+    merchant_record: &MerchantRecord,
+    amount: u64,
+) -> Result<()> {
+    check_allowance(merchant_record, amount)?;
+    check_supply(platform_state, amount)
+}
+
+/// Record a completed platform-funded mint against supply only
+pub fn record_supply(platform_state: &mut PlatformState, amount: u64) -> Result<()> {
+    platform_state.current_supply = platform_state
+        .current_supply
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Record a completed mint against platform supply and merchant totals
+pub fn record_mint(
+    platform_state: &mut PlatformState,
+    merchant_record: &mut MerchantRecord,
+    amount: u64,
+) -> Result<()> {
+    record_supply(platform_state, amount)?;
+
+    merchant_record.total_minted = merchant_record
+        .total_minted
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Enforce a delegate's expiry and caps for a mint of `amount`, then count it
+/// toward the delegate's daily tally (reset on the first mint of a new UTC day)
+pub fn record_delegate_mint(
+    merchant_delegate: &mut MerchantDelegate,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    require!(
+        !merchant_delegate.is_expired(now),
+        LoyaltyError::DelegateExpired
+    );
+    require!(
+        merchant_delegate.per_tx_cap == 0 || amount <= merchant_delegate.per_tx_cap,
+        LoyaltyError::DelegateTxCapExceeded
+    );
+
+    let today = now / SECONDS_PER_DAY;
+    if merchant_delegate.day != today {
+        merchant_delegate.day = today;
+        merchant_delegate.minted_today = 0;
+    }
+    let minted_today = merchant_delegate
+        .minted_today
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    require!(
+        merchant_delegate.daily_cap == 0 || minted_today <= merchant_delegate.daily_cap,
+        LoyaltyError::DelegateDailyCapExceeded
+    );
+
+    merchant_delegate.minted_today = minted_today;
+    merchant_delegate.total_minted = merchant_delegate
+        .total_minted
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::LoyaltyError;
+
 /// Platform state account - stores global configuration
 #[account]
 #[derive(InitSpace)]
@@ -10,7 +12,7 @@ pub struct PlatformState {
     /// The SPL token mint for loyalty points
     pub token_mint: Pubkey,
     
-    /// Protocol treasury for collecting fees
+    /// Protocol treasury PDA (program-owned) holding fees and SOL deposits
     pub protocol_treasury: Pubkey,
     
     /// Maximum total supply of loyalty tokens
@@ -37,16 +39,204 @@ pub struct PlatformState {
     /// Total fees collected
     pub total_fees_collected: u64,
     
+    /// Total SOL deposited by merchants through deposit_sol
+    pub total_sol_deposited: u64,
+    
+    /// Total lamports withdrawn from the treasury by the admin
+    pub total_withdrawn: u64,
+    
     /// SOL to loyalty points conversion ratio
     /// e.g. 100 means 1 SOL = 100 loyalty points (before decimal adjustment)
     pub sol_to_points_ratio: u64,
     
     /// Bump seed for PDA
     pub bump: u8,
+    
+    /// Bitmask of individually paused operations (see `PAUSE_*`)
+    pub paused_operations: u16,
+    
+    /// Reason code supplied with the most recent pause
+    pub pause_reason: u8,
+    
+    /// Proposed new admin awaiting acceptance (two-step transfer)
+    pub pending_admin: Option<Pubkey>,
+    
+    /// Seconds after issuance that a points lot expires (0 = never)
+    pub points_expiry_seconds: i64,
+    
+    /// Consumer tier thresholds and earn multipliers
+    pub tier_config: TierConfig,
+    
+    /// Points minted to the referrer when a referee completes their first SOL purchase
+    pub referrer_bonus: u64,
+    
+    /// Points minted to the referee on their first SOL purchase
+    pub referee_bonus: u64,
+    
+    /// Points per SOL at which merchants cash out (0 = cash-out disabled)
+    pub buyback_ratio: u64,
+    
+    /// Protocol spread withheld from merchant cash-outs, in basis points
+    pub cash_out_spread_bps: u16,
+    
+    /// Total lamports paid out of the treasury to merchants cashing out
+    pub total_cashed_out: u64,
+    
+    /// Percentage mint fee in basis points of the points' SOL value
+    pub mint_fee_bps: u16,
+    
+    /// Fee discounts by merchant lifetime volume (see `fees::mint_fee`)
+    pub volume_fee_tiers: [VolumeFeeTier; 3],
+    
+    /// Token-2022 extensions the loyalty mint was created with (EXT_* bitmask)
+    pub mint_extensions: u8,
+    
+    /// Lamports paid to the treasury by the sender of each gift
+    pub gift_fee: u64,
+    
+    /// Points a consumer may gift per UTC day (0 = unlimited)
+    pub gift_daily_cap: u64,
+    
+    /// Seconds since a consumer's first earn before they may gift
+    pub gift_min_account_age: i64,
+    
+    /// Seconds after `purchased_at` before a customer may close a purchase record (0 = only once final)
+    pub purchase_retention_seconds: i64,
+    
+    /// Seconds after `purchased_at` during which the customer may dispute (0 = disputes disabled)
+    pub dispute_window_seconds: i64,
+    
+    /// Whether gifts are allowed when the loyalty mint is NonTransferable (burned and re-minted)
+    pub allow_non_transferable_gifts: bool,
 }
 
 impl PlatformState {
     pub const SEED: &'static [u8] = b"platform_state";
+
+    /// Platform-wide mint fee schedule
+    pub fn fee_schedule(&self) -> FeeSchedule {
+        FeeSchedule {
+            base_fee: self.base_mint_fee,
+            rate_per_thousand: self.fee_rate_per_thousand,
+            rate_bps: self.mint_fee_bps,
+        }
+    }
+
+    /// Issuing points: mint_points, deposit_sol, purchase_product_with_sol
+    pub const PAUSE_MINT: u16 = 1 << 0;
+    /// Spending points: redeem_points, purchase_product_with_points, purchase_product_mixed
+    pub const PAUSE_REDEEM: u16 = 1 << 1;
+    /// Product purchases with any payment method
+    pub const PAUSE_PURCHASE: u16 = 1 << 2;
+    /// Merchant SOL deposits
+    pub const PAUSE_DEPOSIT: u16 = 1 << 3;
+    /// Merchant cash-outs from the treasury
+    pub const PAUSE_CASH_OUT: u16 = 1 << 4;
+    /// Consumer-to-consumer gifts
+    pub const PAUSE_GIFT: u16 = 1 << 5;
+    pub const PAUSE_ALL: u16 = Self::PAUSE_MINT
+        | Self::PAUSE_REDEEM
+        | Self::PAUSE_PURCHASE
+        | Self::PAUSE_DEPOSIT
+        | Self::PAUSE_CASH_OUT
+        | Self::PAUSE_GIFT;
+
+    /// True if any of the operations in `operations` is paused
+    pub fn is_paused(&self, operations: u16) -> bool {
+        self.paused_operations & operations != 0
+    }
+
+    /// Points cannot be transferred between wallets, only minted and burned
+    pub const EXT_NON_TRANSFERABLE: u8 = 1 << 0;
+    /// Platform PDA can burn from any loyalty token account (clawbacks, expiry)
+    pub const EXT_PERMANENT_DELEGATE: u8 = 1 << 1;
+    /// Token metadata is stored on the mint itself instead of Metaplex
+    pub const EXT_METADATA_POINTER: u8 = 1 << 2;
+    pub const EXT_ALL: u8 = Self::EXT_NON_TRANSFERABLE
+        | Self::EXT_PERMANENT_DELEGATE
+        | Self::EXT_METADATA_POINTER;
+
+    /// True if the loyalty mint was created with `extension`
+    pub fn has_mint_extension(&self, extension: u8) -> bool {
+        self.mint_extensions & extension != 0
+    }
+}
+
+/// Mint fee schedule - every component is in lamports and they are summed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct FeeSchedule {
+    /// Flat fee per mint
+    pub base_fee: u64,
+    
+    /// Fee per 1000 raw token units minted (rounded up)
+    pub rate_per_thousand: u64,
+    
+    /// Fee in basis points of the minted points' value at `sol_to_points_ratio`
+    pub rate_bps: u16,
+}
+
+/// Volume discount applied once a merchant's `total_minted` reaches `min_total_minted`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct VolumeFeeTier {
+    /// Lifetime points minted to qualify (0 = tier disabled)
+    pub min_total_minted: u64,
+    
+    /// Discount off the mint fee in basis points
+    pub discount_bps: u16,
+}
+
+/// Consumer tier thresholds (lifetime points earned) and earn multipliers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct TierConfig {
+    /// Lifetime points earned to reach Silver (0 = tier disabled)
+    pub silver_threshold: u64,
+    
+    /// Lifetime points earned to reach Gold (0 = tier disabled)
+    pub gold_threshold: u64,
+    
+    /// Earn multiplier per tier in basis points (10_000 = 1x), indexed by tier
+    pub multipliers_bps: [u16; 3],
+}
+
+impl TierConfig {
+    pub const BRONZE: u8 = 0;
+    pub const SILVER: u8 = 1;
+    pub const GOLD: u8 = 2;
+
+    /// Every tier at 1x with no thresholds - tiers have no effect
+    pub const DISABLED: TierConfig = TierConfig {
+        silver_threshold: 0,
+        gold_threshold: 0,
+        multipliers_bps: [10_000; 3],
+    };
+
+    /// Tier reached with `lifetime_earned` points
+    pub fn tier_for(&self, lifetime_earned: u64) -> u8 {
+        if self.gold_threshold > 0 && lifetime_earned >= self.gold_threshold {
+            Self::GOLD
+        } else if self.silver_threshold > 0 && lifetime_earned >= self.silver_threshold {
+            Self::SILVER
+        } else {
+            Self::BRONZE
+        }
+    }
+
+    /// Earn multiplier for `tier` in basis points
+    pub fn multiplier_bps(&self, tier: u8) -> u16 {
+        self.multipliers_bps[tier.min(Self::GOLD) as usize]
+    }
+}
+
+/// Protocol treasury - program-owned vault for fees and merchant SOL deposits
+#[account]
+#[derive(InitSpace)]
+pub struct Treasury {
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const SEED: &'static [u8] = b"treasury";
 }
 
 /// Merchant record - stores authorization status for each merchant
@@ -71,9 +261,21 @@ pub struct MerchantRecord {
     /// Total fees paid by this merchant
     pub total_fees_paid: u64,
     
+    /// Total lamports received by cashing points out of the treasury
+    pub total_cashed_out: u64,
+    
+    /// Negotiated fee schedule replacing the platform schedule (None = platform default)
+    pub fee_override: Option<FeeSchedule>,
+    
     /// Timestamp when merchant was registered
     pub registered_at: i64,
     
+    /// Points this merchant has burned through cash-out - capped by `total_redeemed`
+    pub points_cashed_out: u64,
+    
+    /// Refunds ordered by a dispute ruling and not yet settled - minting, sales and cash-out are blocked meanwhile
+    pub pending_refunds: u32,
+    
     /// Bump seed for PDA
     pub bump: u8,
 }
@@ -89,28 +291,424 @@ pub struct PurchaseRecord {
     /// Customer wallet
     pub customer: Pubkey,
     
+    /// Position in the customer's purchase history (part of the PDA seeds)
+    pub index: u64,
+    
     /// Merchant wallet
     pub merchant: Pubkey,
     
     /// Product ID hash (32 bytes)
     pub product_id_hash: [u8; 32],
     
-    /// Payment type: 0 = SOL, 1 = Loyalty Points
+    /// Payment type: 0 = SOL, 1 = Loyalty Points, 2 = Mixed (points + SOL), 3 = SPL token
     pub payment_type: u8,
     
-    /// Amount paid (in lamports for SOL, or token amount for points)
+    /// SPL mint paid with (default pubkey unless paid with an SPL token)
+    pub payment_mint: Pubkey,
+    
+    /// Amount paid (in lamports for SOL and mixed, payment mint units for SPL, or token amount for points)
     pub amount_paid: u64,
     
+    /// Loyalty points burned as payment (points and mixed purchases)
+    pub points_paid: u64,
+    
     /// Loyalty points earned (if paid with SOL)
     pub points_earned: u64,
     
     /// Purchase timestamp
     pub purchased_at: i64,
     
+    /// Refund timestamp (0 = not refunded)
+    pub refunded_at: i64,
+    
+    /// Timestamp the merchant marked the order final (0 = not final)
+    pub finalized_at: i64,
+    
+    /// Order status (see `PurchaseRecord::STATUS_*`)
+    pub status: u8,
+    
+    /// Fulfillment timestamp (0 = not fulfilled)
+    pub fulfilled_at: i64,
+    
+    /// Timestamp of the customer's dispute (0 = never disputed)
+    pub disputed_at: i64,
+    
+    /// Timestamp the admin resolved the dispute (0 = unresolved)
+    pub resolved_at: i64,
+    
+    /// Reward points a refund could not claw back (already spent or not burnable)
+    pub clawback_shortfall: u64,
+    
+    /// Campaign that added bonus points to the reward (None = no campaign)
+    pub campaign: Option<Pubkey>,
+    
+    /// Bonus points from the campaign (included in `points_earned`)
+    pub campaign_bonus: u64,
+    
     /// Bump seed for PDA
     pub bump: u8,
 }
 
 impl PurchaseRecord {
     pub const SEED: &'static [u8] = b"purchase";
+
+    pub const PAYMENT_SOL: u8 = 0;
+    pub const PAYMENT_POINTS: u8 = 1;
+    pub const PAYMENT_MIXED: u8 = 2;
+    pub const PAYMENT_TOKEN: u8 = 3;
+
+    /// Paid and recorded on-chain
+    pub const STATUS_CONFIRMED: u8 = 0;
+    /// Merchant has delivered the order
+    pub const STATUS_FULFILLED: u8 = 1;
+    /// Customer has opened a dispute awaiting the admin
+    pub const STATUS_DISPUTED: u8 = 2;
+    /// Admin ruled for the customer; the merchant owes a refund
+    pub const STATUS_REFUND_ORDERED: u8 = 3;
+    /// Merchant has refunded the purchase
+    pub const STATUS_REFUNDED: u8 = 4;
+
+    /// True while a dispute or an ordered refund is outstanding
+    pub fn has_open_dispute(&self) -> bool {
+        self.status == Self::STATUS_DISPUTED || self.status == Self::STATUS_REFUND_ORDERED
+    }
+}
+
+/// Product listing - merchant-owned catalog entry with authoritative pricing
+#[account]
+#[derive(InitSpace)]
+pub struct Product {
+    /// Merchant wallet that owns this listing
+    pub merchant: Pubkey,
+    
+    /// Product ID hash (32 bytes)
+    pub product_id_hash: [u8; 32],
+    
+    /// Price in lamports (0 = not purchasable with SOL)
+    pub price_sol: u64,
+    
+    /// Price in loyalty token units (0 = not purchasable with points)
+    pub price_points: u64,
+    
+    /// Loyalty points minted to the customer on a SOL purchase
+    pub loyalty_points_reward: u64,
+    
+    /// Whether the product can currently be purchased
+    pub is_available: bool,
+    
+    /// Timestamp when the product was listed
+    pub created_at: i64,
+    
+    /// Timestamp of the last pricing or availability change
+    pub updated_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Product {
+    pub const SEED: &'static [u8] = b"product";
+}
+
+/// Consumer profile - per-consumer ledger of expiring points lots and tier
+#[account]
+#[derive(InitSpace, Default)]
+pub struct ConsumerProfile {
+    /// Consumer wallet
+    pub consumer: Pubkey,
+    
+    /// Index that the next issued lot will use
+    pub next_lot_index: u64,
+    
+    /// Oldest open lot - lots are consumed FIFO from here
+    pub head_lot_index: u64,
+    
+    /// Points still held in open lots
+    pub lot_balance: u64,
+    
+    /// Timestamp of the consumer's first points issuance
+    pub first_earned_at: i64,
+    
+    /// Total points ever earned (after tier multipliers)
+    pub lifetime_earned: u64,
+    
+    /// Total points ever spent on purchases and redemptions
+    pub lifetime_spent: u64,
+    
+    /// Current tier (see `TierConfig::BRONZE`/`SILVER`/`GOLD`)
+    pub tier: u8,
+    
+    /// Whether the consumer has completed a SOL purchase (referral bonuses pay once)
+    pub first_purchase_done: bool,
+    
+    /// Day (unix time / 86400) that `gifted_today` counts toward
+    pub gift_day: i64,
+    
+    /// Points gifted during `gift_day`
+    pub gifted_today: u64,
+    
+    /// Purchases made so far - the index the next `PurchaseRecord` will use
+    pub purchase_count: u64,
+    
+    /// Expired points that could not be burned yet - already out of
+    /// `current_supply`, burned ahead of the next expired lot
+    pub expired_unburned: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ConsumerProfile {
+    pub const SEED: &'static [u8] = b"consumer";
+
+    /// Fill in identity fields on a freshly created (`init_if_needed`) profile
+    pub fn ensure_initialized(&mut self, consumer: Pubkey, bump: u8) {
+        if self.consumer == Pubkey::default() {
+            self.consumer = consumer;
+            self.bump = bump;
+        }
+    }
+
+    /// Claim the index for a new purchase record and advance the counter
+    pub fn take_purchase_index(&mut self) -> Result<u64> {
+        let index = self.purchase_count;
+        self.purchase_count = index
+            .checked_add(1)
+            .ok_or(LoyaltyError::ArithmeticOverflow)?;
+        Ok(index)
+    }
+}
+
+/// Points lot - a single issuance of points with its expiry
+#[account]
+#[derive(InitSpace, Default)]
+pub struct PointsLot {
+    /// Consumer wallet that owns the points
+    pub consumer: Pubkey,
+    
+    /// Position in the consumer's FIFO lot sequence
+    pub index: u64,
+    
+    /// Points originally issued in this lot
+    pub amount: u64,
+    
+    /// Points not yet spent or expired
+    pub remaining: u64,
+    
+    /// Issuance timestamp
+    pub issued_at: i64,
+    
+    /// Expiry timestamp (0 = never expires)
+    pub expires_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PointsLot {
+    pub const SEED: &'static [u8] = b"lot";
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+}
+
+/// Promotional campaign - merchant-funded bonus points within a time window
+#[account]
+#[derive(InitSpace)]
+pub struct Campaign {
+    /// Merchant wallet running the campaign
+    pub merchant: Pubkey,
+    
+    /// Merchant-chosen identifier (part of the PDA seeds)
+    pub campaign_id: u64,
+    
+    /// Campaign start timestamp (inclusive)
+    pub start_at: i64,
+    
+    /// Campaign end timestamp (exclusive)
+    pub end_at: i64,
+    
+    /// Only purchases of this product qualify (None = every issuance)
+    pub product_id_hash: Option<[u8; 32]>,
+    
+    /// Bonus on the base reward in basis points (10_000 = double points)
+    pub bonus_bps: u16,
+    
+    /// Total bonus points the campaign may issue
+    pub budget: u64,
+    
+    /// Bonus points issued so far
+    pub bonus_issued: u64,
+    
+    /// Timestamp when the campaign was created
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl Campaign {
+    pub const SEED: &'static [u8] = b"campaign";
+
+    pub fn is_active(&self, now: i64) -> bool {
+        now >= self.start_at && now < self.end_at
+    }
+
+    /// True if an issuance for `product_id_hash` (None = not a product purchase) qualifies
+    pub fn applies_to(&self, product_id_hash: Option<&[u8; 32]>) -> bool {
+        match &self.product_id_hash {
+            None => true,
+            Some(filter) => product_id_hash == Some(filter),
+        }
+    }
+}
+
+/// Referral link - records who referred a consumer (set once)
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralLink {
+    /// Consumer who was referred
+    pub referee: Pubkey,
+    
+    /// Consumer who referred them
+    pub referrer: Pubkey,
+    
+    /// Timestamp when the link was registered
+    pub created_at: i64,
+    
+    /// Timestamp when the referral bonuses were paid (0 = not yet)
+    pub bonus_paid_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ReferralLink {
+    pub const SEED: &'static [u8] = b"referral";
+}
+
+/// Payment mint - an admin-allowlisted SPL token accepted for purchases and deposits
+#[account]
+#[derive(InitSpace)]
+pub struct PaymentMint {
+    /// SPL token mint accepted as payment
+    pub mint: Pubkey,
+    
+    /// Decimals of the payment mint
+    pub decimals: u8,
+    
+    /// Whole loyalty points per whole payment token
+    pub points_per_token: u64,
+    
+    /// Whether purchases and deposits currently accept this mint
+    pub is_enabled: bool,
+    
+    /// Tokens paid into the treasury by merchant deposits
+    pub total_deposited: u64,
+    
+    /// Tokens collected as protocol fees
+    pub total_fees_collected: u64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl PaymentMint {
+    pub const SEED: &'static [u8] = b"payment_mint";
+}
+
+/// Merchant-set product price in one payment mint, used instead of converting
+/// the points price at the mint's `points_per_token`
+#[account]
+#[derive(InitSpace)]
+pub struct ProductTokenPrice {
+    /// Product this price applies to
+    pub product: Pubkey,
+    
+    /// SPL token mint the price is denominated in
+    pub payment_mint: Pubkey,
+    
+    /// Price in raw payment token units (0 = fall back to the points price conversion)
+    pub price_tokens: u64,
+    
+    /// Timestamp of the last price change
+    pub updated_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl ProductTokenPrice {
+    pub const SEED: &'static [u8] = b"product_token_price";
+}
+
+/// Merchant delegate - a staff wallet or POS device allowed to mint for a merchant
+#[account]
+#[derive(InitSpace)]
+pub struct MerchantDelegate {
+    /// Merchant wallet the delegate mints for
+    pub merchant: Pubkey,
+    
+    /// Delegate wallet allowed to sign `mint_points`
+    pub delegate: Pubkey,
+    
+    /// Maximum points requested per mint, before tier and campaign boosts (0 = unlimited)
+    pub per_tx_cap: u64,
+    
+    /// Maximum points requested per UTC day, before boosts (0 = unlimited)
+    pub daily_cap: u64,
+    
+    /// Timestamp after which the delegate can no longer mint (0 = never)
+    pub expires_at: i64,
+    
+    /// Day (unix time / 86400) that `minted_today` counts toward
+    pub day: i64,
+    
+    /// Points requested during `day`
+    pub minted_today: u64,
+    
+    /// Total points requested by this delegate, before boosts
+    pub total_minted: u64,
+    
+    /// Timestamp when the delegate was created
+    pub created_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl MerchantDelegate {
+    pub const SEED: &'static [u8] = b"merchant_delegate";
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+}
+
+/// Mint receipt - marks a purchase reference as already credited by a merchant
+#[account]
+#[derive(InitSpace)]
+pub struct MintReceipt {
+    /// Merchant that issued the points
+    pub merchant: Pubkey,
+    
+    /// SHA-256 of the purchase reference (part of the PDA seeds)
+    pub reference_hash: [u8; 32],
+    
+    /// Consumer credited for the reference
+    pub consumer: Pubkey,
+    
+    /// Points minted for the reference
+    pub amount: u64,
+    
+    /// Timestamp when the points were minted
+    pub minted_at: i64,
+    
+    /// Bump seed for PDA
+    pub bump: u8,
+}
+
+impl MintReceipt {
+    pub const SEED: &'static [u8] = b"mint_receipt";
 }
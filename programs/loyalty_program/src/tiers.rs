@@ -0,0 +1,86 @@
+//! Consumer tier tracking.
+//!
+//! Tiers are derived from a consumer's lifetime points earned against the
+//! admin-configured [`TierConfig`]. The tier held *before* an issuance sets
+//! its multiplier; the profile is re-tiered afterwards.
+
+use anchor_lang::prelude::*;
+
+use crate::errors::LoyaltyError;
+use crate::math::apply_bps;
+use crate::state::{ConsumerProfile, TierConfig};
+
+/// Reward after the consumer's current tier multiplier
+pub fn boosted_reward(profile: &ConsumerProfile, config: &TierConfig, amount: u64) -> Result<u64> {
+    apply_bps(amount, config.multiplier_bps(profile.tier))
+}
+
+/// Add earned points to the profile and re-tier, emitting `TierChanged` on a move
+pub fn record_earned(
+    profile: &mut ConsumerProfile,
+    config: &TierConfig,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    profile.lifetime_earned = profile
+        .lifetime_earned
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+
+    retier(profile, config, now);
+    Ok(())
+}
+
+/// Remove clawed-back points (e.g. a refunded reward) from lifetime earned and re-tier
+pub fn record_clawback(
+    profile: &mut ConsumerProfile,
+    config: &TierConfig,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    profile.lifetime_earned = profile.lifetime_earned.saturating_sub(amount);
+
+    retier(profile, config, now);
+    Ok(())
+}
+
+fn retier(profile: &mut ConsumerProfile, config: &TierConfig, now: i64) {
+    let new_tier = config.tier_for(profile.lifetime_earned);
+    if new_tier != profile.tier {
+        let old_tier = profile.tier;
+        profile.tier = new_tier;
+
+        msg!("Consumer {} tier: {} -> {}", profile.consumer, old_tier, new_tier);
+
+        emit!(TierChanged {
+            consumer: profile.consumer,
+            old_tier,
+            new_tier,
+            lifetime_earned: profile.lifetime_earned,
+            timestamp: now,
+        });
+    }
+}
+
+/// Add spent points to the profile's lifetime total
+pub fn record_spent(profile: &mut ConsumerProfile, amount: u64) -> Result<()> {
+    profile.lifetime_spent = profile
+        .lifetime_spent
+        .checked_add(amount)
+        .ok_or(LoyaltyError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Remove refunded points from the profile's lifetime spent total
+pub fn record_spend_refund(profile: &mut ConsumerProfile, amount: u64) {
+    profile.lifetime_spent = profile.lifetime_spent.saturating_sub(amount);
+}
+
+#[event]
+pub struct TierChanged {
+    pub consumer: Pubkey,
+    pub old_tier: u8,
+    pub new_tier: u8,
+    pub lifetime_earned: u64,
+    pub timestamp: i64,
+}
@@ -13,6 +13,7 @@
  */
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram, Connection } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import * as crypto from "crypto";
 import * as fs from "fs";
 import * as os from "os";
@@ -126,6 +127,7 @@ async function main() {
       { pubkey: tokenMintPDA,                       isSigner: false, isWritable: true  }, // token_mint
       { pubkey: metadataPDA,                        isSigner: false, isWritable: true  }, // metadata_account
       { pubkey: MPL_TOKEN_METADATA_ID,              isSigner: false, isWritable: false }, // token_metadata_program
+      { pubkey: TOKEN_PROGRAM_ID,                   isSigner: false, isWritable: false }, // token_program
       { pubkey: SystemProgram.programId,            isSigner: false, isWritable: false }, // system_program
       { pubkey: anchor.web3.SYSVAR_RENT_PUBKEY,    isSigner: false, isWritable: false }, // rent
     ],
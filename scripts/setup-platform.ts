@@ -4,7 +4,7 @@
  */
 import * as anchor from "@coral-xyz/anchor";
 import { PublicKey, Keypair, SystemProgram, Connection } from "@solana/web3.js";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import * as fs from "fs";
 import * as os from "os";
 import * as path from "path";
@@ -14,7 +14,6 @@ dotenv.config();
 
 const PROGRAM_ID = new PublicKey(process.env.PROGRAM_ID || "9RkPYyU3tB5X9g2TkBPiZHUrVNRZjwjZ3Eu8LZhK4LXj");
 const RPC_URL = process.env.SOLANA_RPC_URL || "http://localhost:8899";
-const PROTOCOL_TREASURY = new PublicKey(process.env.VITE_PLATFORM_AUTHORITY || "HQafx3NKvQgY4qus6wnfhZuDyU7UYCAyRGb4tnDtkDdU");
 
 // Platform config
 const TOKEN_DECIMALS = 6;
@@ -22,6 +21,10 @@ const MAX_SUPPLY = new anchor.BN(1_000_000_000 * 10 ** TOKEN_DECIMALS); // 1B LP
 const BASE_MINT_FEE = new anchor.BN(5000); // 5000 lamports
 const FEE_RATE_PER_THOUSAND = new anchor.BN(1000);
 const SOL_TO_POINTS_RATIO = new anchor.BN(100); // 1 SOL = 100 LP
+// Token-2022 extensions bitmask: 1 = NonTransferable, 2 = PermanentDelegate, 4 = MetadataPointer
+// Any extension creates the mint under Token-2022; 0 uses the classic SPL Token program
+const MINT_EXTENSIONS = Number(process.env.MINT_EXTENSIONS || 0);
+const MINT_TOKEN_PROGRAM_ID = MINT_EXTENSIONS > 0 ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
 
 async function main() {
   // Load admin keypair
@@ -50,9 +53,14 @@ async function main() {
     [Buffer.from("loyalty_mint")],
     PROGRAM_ID
   );
+  const [treasuryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    PROGRAM_ID
+  );
 
   console.log("\nPDAs:");
   console.log("  Platform State:", platformStatePDA.toBase58());
+  console.log("  Protocol Treasury:", treasuryPDA.toBase58());
   console.log("  Token Mint:", tokenMintPDA.toBase58());
 
   // Check if platform is already initialized
@@ -72,14 +80,15 @@ async function main() {
       .digest()
       .slice(0, 8);
 
-    // Serialize args: token_decimals(u8) + max_supply(u64) + base_mint_fee(u64) + fee_rate_per_thousand(u64) + sol_to_points_ratio(u64)
-    const argsBuffer = Buffer.alloc(1 + 8 + 8 + 8 + 8);
+    // Serialize args: token_decimals(u8) + max_supply(u64) + base_mint_fee(u64) + fee_rate_per_thousand(u64) + sol_to_points_ratio(u64) + mint_extensions(u8)
+    const argsBuffer = Buffer.alloc(1 + 8 + 8 + 8 + 8 + 1);
     let offset = 0;
     argsBuffer.writeUInt8(TOKEN_DECIMALS, offset); offset += 1;
     argsBuffer.writeBigUInt64LE(BigInt(MAX_SUPPLY.toString()), offset); offset += 8;
     argsBuffer.writeBigUInt64LE(BigInt(BASE_MINT_FEE.toString()), offset); offset += 8;
     argsBuffer.writeBigUInt64LE(BigInt(FEE_RATE_PER_THOUSAND.toString()), offset); offset += 8;
     argsBuffer.writeBigUInt64LE(BigInt(SOL_TO_POINTS_RATIO.toString()), offset); offset += 8;
+    argsBuffer.writeUInt8(MINT_EXTENSIONS, offset); offset += 1;
 
     const data = Buffer.concat([initDiscriminator, argsBuffer]);
 
@@ -87,10 +96,10 @@ async function main() {
       programId: PROGRAM_ID,
       keys: [
         { pubkey: adminKeypair.publicKey, isSigner: true, isWritable: true },
-        { pubkey: PROTOCOL_TREASURY, isSigner: false, isWritable: true }, // protocol_treasury = new admin wallet
         { pubkey: platformStatePDA, isSigner: false, isWritable: true },
+        { pubkey: treasuryPDA, isSigner: false, isWritable: true }, // protocol_treasury PDA
         { pubkey: tokenMintPDA, isSigner: false, isWritable: true },
-        { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        { pubkey: MINT_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
         { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
         { pubkey: anchor.web3.SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
       ],
@@ -109,7 +118,7 @@ async function main() {
   }
 
   console.log("\n🎉 Setup complete! The platform is ready.");
-  console.log("\nProtocol Treasury:", PROTOCOL_TREASURY.toBase58());
+  console.log("\nProtocol Treasury:", treasuryPDA.toBase58());
 }
 
 main().catch(console.error);
@@ -0,0 +1,215 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMint,
+} from "@solana/spl-token";
+import { assert, expect } from "chai";
+import { LoyaltyProgram } from "../../target/types/loyalty_program";
+
+// Runs on its own validator (see Test.toml): the platform is a singleton, so a
+// 0-decimal loyalty mint needs a fresh deployment. Raw units are whole points
+// here, so conversions have to round to whole points and logs show no fraction.
+describe("loyalty_program (0-decimal mint)", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.LoyaltyProgram as Program<LoyaltyProgram>;
+
+  const admin = provider.wallet;
+  const merchant = Keypair.generate();
+  const consumer = Keypair.generate();
+
+  const TOKEN_DECIMALS = 0;
+  const BASE_FEE = 5000;
+  // Per 1000 whole points, each started thousand charged in full
+  const RATE_PER_THOUSAND = 100_000;
+  const SOL_TO_POINTS_RATIO = 100;
+  const MAX_SUPPLY = new anchor.BN(1_000_000_000);
+  const MINT_AMOUNT = new anchor.BN(1500);
+
+  const [platformStatePDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("platform_state")],
+    program.programId
+  );
+  const [tokenMintPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("loyalty_mint")],
+    program.programId
+  );
+  const [treasuryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+  const [merchantRecordPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("merchant"), merchant.publicKey.toBuffer()],
+    program.programId
+  );
+
+  const consumerProfilePDA = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("consumer"), wallet.toBuffer()],
+      program.programId
+    )[0];
+  const lotPDA = (wallet: PublicKey, index: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("lot"), wallet.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  const nextLotPDA = async (wallet: PublicKey) => {
+    const profile = await program.account.consumerProfile.fetchNullable(
+      consumerProfilePDA(wallet)
+    );
+    return lotPDA(wallet, profile ? profile.nextLotIndex : new anchor.BN(0));
+  };
+  const ata = (wallet: PublicKey) =>
+    getAssociatedTokenAddressSync(tokenMintPDA, wallet);
+  const balance = async (wallet: PublicKey) =>
+    Number((await getAccount(provider.connection, ata(wallet))).amount);
+  const logsOf = async (signature: string) =>
+    (
+      await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      })
+    ).meta.logMessages;
+  const deposit = (lamports: number) =>
+    program.methods
+      .depositSol(new anchor.BN(lamports))
+      .accounts({
+        merchant: merchant.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        merchantRecord: merchantRecordPDA,
+        tokenMint: tokenMintPDA,
+        merchantTokenAccount: ata(merchant.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([merchant])
+      .rpc({ commitment: "confirmed" });
+
+  before(async () => {
+    for (const wallet of [merchant, consumer]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        5 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+
+    await program.methods
+      .initializePlatform(
+        TOKEN_DECIMALS,
+        MAX_SUPPLY,
+        new anchor.BN(BASE_FEE),
+        new anchor.BN(RATE_PER_THOUSAND),
+        new anchor.BN(SOL_TO_POINTS_RATIO),
+        0
+      )
+      .accounts({
+        admin: admin.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        tokenMint: tokenMintPDA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMerchant(new anchor.BN(100_000))
+      .accounts({
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+        merchantWallet: merchant.publicKey,
+        merchantRecord: merchantRecordPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("creates the mint with 0 decimals", async () => {
+    const mint = await getMint(provider.connection, tokenMintPDA);
+    expect(mint.decimals).to.equal(TOKEN_DECIMALS);
+
+    const platformState = await program.account.platformState.fetch(
+      platformStatePDA
+    );
+    expect(platformState.tokenDecimals).to.equal(TOKEN_DECIMALS);
+    expect(platformState.maxSupply.toString()).to.equal(MAX_SUPPLY.toString());
+  });
+
+  it("rounds a deposit down to whole points", async () => {
+    // 0.015 SOL * 100 points per SOL = 1.5 points
+    const tx = await deposit(0.015 * anchor.web3.LAMPORTS_PER_SOL);
+
+    expect(await balance(merchant.publicKey)).to.equal(1);
+    const platformState = await program.account.platformState.fetch(
+      platformStatePDA
+    );
+    expect(platformState.currentSupply.toNumber()).to.equal(1);
+    expect(
+      (await logsOf(tx)).some((log) => log.includes("received 1 loyalty points"))
+    ).to.be.true;
+  });
+
+  it("fails to deposit less than one point's worth", async () => {
+    try {
+      // 0.005 SOL is half a point
+      await deposit(0.005 * anchor.web3.LAMPORTS_PER_SOL);
+      assert.fail("Should have thrown an error");
+    } catch (error: any) {
+      expect(error.error.errorCode.code).to.equal("InvalidAmount");
+    }
+  });
+
+  it("mints whole points and rounds the fee up to the next thousand", async () => {
+    const treasuryBefore = await provider.connection.getBalance(treasuryPDA);
+
+    const tx = await program.methods
+      .mintPoints(MINT_AMOUNT, "DECIMALS-0-001")
+      .accounts({
+        authority: merchant.publicKey,
+        merchant: merchant.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        merchantRecord: merchantRecordPDA,
+        tokenMint: tokenMintPDA,
+        consumer: consumer.publicKey,
+        consumerTokenAccount: ata(consumer.publicKey),
+        consumerProfile: consumerProfilePDA(consumer.publicKey),
+        pointsLot: await nextLotPDA(consumer.publicKey),
+        campaign: null,
+        merchantDelegate: null,
+        mintReceipt: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([merchant])
+      .rpc({ commitment: "confirmed" });
+
+    expect(await balance(consumer.publicKey)).to.equal(MINT_AMOUNT.toNumber());
+
+    // 1500 points start two thousands: base fee + 2 * rate
+    const expectedFee = BASE_FEE + 2 * RATE_PER_THOUSAND;
+    const treasuryAfter = await provider.connection.getBalance(treasuryPDA);
+    expect(treasuryAfter - treasuryBefore).to.equal(expectedFee);
+
+    // Whole points are logged without a fractional part
+    expect(
+      (await logsOf(tx)).some((log) => log.includes("Minted 1500 points"))
+    ).to.be.true;
+
+    const lot = await program.account.pointsLot.fetch(
+      lotPDA(consumer.publicKey, new anchor.BN(0))
+    );
+    expect(lot.remaining.toNumber()).to.equal(MINT_AMOUNT.toNumber());
+  });
+});
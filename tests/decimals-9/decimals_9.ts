@@ -0,0 +1,213 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMint,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { LoyaltyProgram } from "../../target/types/loyalty_program";
+
+// Runs on its own validator (see Test.toml): the platform is a singleton, so a
+// 9-decimal loyalty mint needs a fresh deployment. Raw amounts here exceed
+// u32 and the fee math has to stay exact at the highest supported precision.
+describe("loyalty_program (9-decimal mint)", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.LoyaltyProgram as Program<LoyaltyProgram>;
+
+  const admin = provider.wallet;
+  const merchant = Keypair.generate();
+  const consumer = Keypair.generate();
+
+  const TOKEN_DECIMALS = 9;
+  const ONE_POINT = new anchor.BN(10).pow(new anchor.BN(TOKEN_DECIMALS));
+  const BASE_FEE = 5000;
+  // Per 1000 raw units: 100 points at 9 decimals cost 0.1 SOL
+  const RATE_PER_THOUSAND = 1;
+  const SOL_TO_POINTS_RATIO = 100;
+  const MAX_SUPPLY = ONE_POINT.mul(new anchor.BN(1_000_000_000));
+  const MINT_AMOUNT = ONE_POINT.mul(new anchor.BN(100));
+
+  const [platformStatePDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("platform_state")],
+    program.programId
+  );
+  const [tokenMintPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("loyalty_mint")],
+    program.programId
+  );
+  const [treasuryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+  const [merchantRecordPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("merchant"), merchant.publicKey.toBuffer()],
+    program.programId
+  );
+
+  const consumerProfilePDA = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("consumer"), wallet.toBuffer()],
+      program.programId
+    )[0];
+  const lotPDA = (wallet: PublicKey, index: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("lot"), wallet.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  const nextLotPDA = async (wallet: PublicKey) => {
+    const profile = await program.account.consumerProfile.fetchNullable(
+      consumerProfilePDA(wallet)
+    );
+    return lotPDA(wallet, profile ? profile.nextLotIndex : new anchor.BN(0));
+  };
+  const ata = (wallet: PublicKey) =>
+    getAssociatedTokenAddressSync(tokenMintPDA, wallet);
+  const balance = async (wallet: PublicKey) =>
+    new anchor.BN(
+      (await getAccount(provider.connection, ata(wallet))).amount.toString()
+    );
+
+  before(async () => {
+    for (const wallet of [merchant, consumer]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        5 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+
+    await program.methods
+      .initializePlatform(
+        TOKEN_DECIMALS,
+        MAX_SUPPLY,
+        new anchor.BN(BASE_FEE),
+        new anchor.BN(RATE_PER_THOUSAND),
+        new anchor.BN(SOL_TO_POINTS_RATIO),
+        0
+      )
+      .accounts({
+        admin: admin.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        tokenMint: tokenMintPDA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMerchant(ONE_POINT.mul(new anchor.BN(100_000)))
+      .accounts({
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+        merchantWallet: merchant.publicKey,
+        merchantRecord: merchantRecordPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("creates the mint with 9 decimals", async () => {
+    const mint = await getMint(provider.connection, tokenMintPDA);
+    expect(mint.decimals).to.equal(TOKEN_DECIMALS);
+
+    const platformState = await program.account.platformState.fetch(
+      platformStatePDA
+    );
+    expect(platformState.tokenDecimals).to.equal(TOKEN_DECIMALS);
+    expect(platformState.maxSupply.toString()).to.equal(MAX_SUPPLY.toString());
+  });
+
+  it("converts deposited SOL into 9-decimal points", async () => {
+    const depositAmount = new anchor.BN(0.5 * anchor.web3.LAMPORTS_PER_SOL);
+
+    await program.methods
+      .depositSol(depositAmount)
+      .accounts({
+        merchant: merchant.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        merchantRecord: merchantRecordPDA,
+        tokenMint: tokenMintPDA,
+        merchantTokenAccount: ata(merchant.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([merchant])
+      .rpc();
+
+    // 0.5 SOL * 100 points per SOL = 50 points = 50 * 10^9 raw units
+    const expectedPoints = ONE_POINT.mul(new anchor.BN(50));
+    expect((await balance(merchant.publicKey)).toString()).to.equal(
+      expectedPoints.toString()
+    );
+
+    const platformState = await program.account.platformState.fetch(
+      platformStatePDA
+    );
+    expect(platformState.currentSupply.toString()).to.equal(
+      expectedPoints.toString()
+    );
+  });
+
+  it("mints points and charges the fee on raw units", async () => {
+    const treasuryBefore = await provider.connection.getBalance(treasuryPDA);
+
+    await program.methods
+      .mintPoints(MINT_AMOUNT, "DECIMALS-9-001")
+      .accounts({
+        authority: merchant.publicKey,
+        merchant: merchant.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        merchantRecord: merchantRecordPDA,
+        tokenMint: tokenMintPDA,
+        consumer: consumer.publicKey,
+        consumerTokenAccount: ata(consumer.publicKey),
+        consumerProfile: consumerProfilePDA(consumer.publicKey),
+        pointsLot: await nextLotPDA(consumer.publicKey),
+        campaign: null,
+        merchantDelegate: null,
+        mintReceipt: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([merchant])
+      .rpc();
+
+    expect((await balance(consumer.publicKey)).toString()).to.equal(
+      MINT_AMOUNT.toString()
+    );
+
+    // base fee + ceil(100 * 10^9 / 1000) * rate
+    const expectedFee = MINT_AMOUNT.divn(1000)
+      .muln(RATE_PER_THOUSAND)
+      .addn(BASE_FEE);
+    const treasuryAfter = await provider.connection.getBalance(treasuryPDA);
+    expect(treasuryAfter - treasuryBefore).to.equal(expectedFee.toNumber());
+
+    const merchantRecord = await program.account.merchantRecord.fetch(
+      merchantRecordPDA
+    );
+    expect(merchantRecord.totalMinted.toString()).to.equal(
+      MINT_AMOUNT.toString()
+    );
+    expect(merchantRecord.totalFeesPaid.toNumber()).to.equal(
+      expectedFee.toNumber()
+    );
+
+    const lot = await program.account.pointsLot.fetch(
+      lotPDA(consumer.publicKey, new anchor.BN(0))
+    );
+    expect(lot.remaining.toString()).to.equal(MINT_AMOUNT.toString());
+  });
+});
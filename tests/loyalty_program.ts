@@ -5,6 +5,10 @@ import {
   TOKEN_PROGRAM_ID,
   getAssociatedTokenAddress,
   getAccount,
+  getMint,
+  createAssociatedTokenAccount,
+  createMint,
+  mintTo as mintSplTokens,
 } from "@solana/spl-token";
 import { assert, expect } from "chai";
 import { LoyaltyProgram } from "../target/types/loyalty_program";
@@ -21,13 +25,13 @@ describe("loyalty_program", () => {
   const admin = provider.wallet;
   const merchant = Keypair.generate();
   const consumer = Keypair.generate();
-  const protocolTreasury = Keypair.generate();
 
   // PDAs
   let platformStatePDA: PublicKey;
   let platformStateBump: number;
   let tokenMintPDA: PublicKey;
   let merchantRecordPDA: PublicKey;
+  let treasuryPDA: PublicKey;
 
   // Test constants
   const TOKEN_DECIMALS = 6;
@@ -37,6 +41,10 @@ describe("loyalty_program", () => {
   const BASE_MINT_FEE = new anchor.BN(5000); // 5000 lamports
   const FEE_RATE_PER_THOUSAND = new anchor.BN(1000); // 1000 lamports per 1000 points
   const SOL_TO_POINTS_RATIO = new anchor.BN(100); // 1 SOL = 100 loyalty points
+  const PRODUCT_ID_HASH = createHash("sha256").update("PRODUCT-001").digest();
+  const PRODUCT_PRICE_SOL = new anchor.BN(0.1 * anchor.web3.LAMPORTS_PER_SOL);
+  const PRODUCT_PRICE_POINTS = new anchor.BN(200 * 10 ** TOKEN_DECIMALS);
+  const PRODUCT_REWARD = new anchor.BN(10 * 10 ** TOKEN_DECIMALS);
 
   before(async () => {
     // Derive PDAs
@@ -50,6 +58,11 @@ describe("loyalty_program", () => {
       program.programId
     );
 
+    [treasuryPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury")],
+      program.programId
+    );
+
     [merchantRecordPDA] = PublicKey.findProgramAddressSync(
       [Buffer.from("merchant"), merchant.publicKey.toBuffer()],
       program.programId
@@ -69,6 +82,102 @@ describe("loyalty_program", () => {
     await provider.connection.confirmTransaction(airdropConsumer);
   });
 
+  // Consumer profile PDA tracking a wallet's points lots
+  const consumerProfilePDA = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("consumer"), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  const lotPDA = (wallet: PublicKey, index: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("lot"), wallet.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // Referral link PDA for a referee - passed on every SOL purchase
+  const referralLinkPDA = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("referral"), wallet.toBuffer()],
+      program.programId
+    )[0];
+
+  // Purchase record PDA at a wallet's purchase index
+  const purchaseRecordAt = (wallet: PublicKey, index: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("purchase"), wallet.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+
+  // Purchase record created by a wallet's next purchase
+  const nextPurchaseRecordPDA = async (wallet: PublicKey) => {
+    const profile = await program.account.consumerProfile.fetchNullable(
+      consumerProfilePDA(wallet)
+    );
+    return purchaseRecordAt(
+      wallet,
+      profile ? profile.purchaseCount : new anchor.BN(0)
+    );
+  };
+
+  // Lot opened by the next issuance to a wallet
+  const nextLotPDA = async (wallet: PublicKey) => {
+    const profile = await program.account.consumerProfile.fetchNullable(
+      consumerProfilePDA(wallet)
+    );
+    return lotPDA(wallet, profile ? profile.nextLotIndex : new anchor.BN(0));
+  };
+
+  // update_platform_config args leaving every field unchanged except the overrides
+  const platformConfig = (overrides: Record<string, anchor.BN> = {}) => ({
+    baseMintFee: null,
+    feeRatePerThousand: null,
+    solToPointsRatio: null,
+    maxSupply: null,
+    pointsExpirySeconds: null,
+    referrerBonus: null,
+    refereeBonus: null,
+    ...overrides,
+  });
+
+  // Open lots, oldest first, passed as remaining accounts when spending
+  const openLotAccounts = async (wallet: PublicKey) => {
+    const profile = await program.account.consumerProfile.fetchNullable(
+      consumerProfilePDA(wallet)
+    );
+    if (!profile) return [];
+    const accounts = [];
+    for (
+      let i = profile.headLotIndex.toNumber();
+      i < profile.nextLotIndex.toNumber();
+      i++
+    ) {
+      accounts.push({
+        pubkey: lotPDA(wallet, new anchor.BN(i)),
+        isSigner: false,
+        isWritable: true,
+      });
+    }
+    return accounts;
+  };
+
+  // Opt a wallet in to platform burns (expiry crank and refund clawbacks)
+  const approveExpiryBurns = async (wallet: Keypair, allowance: anchor.BN) =>
+    program.methods
+      .setExpiryDelegate(allowance)
+      .accounts({
+        consumer: wallet.publicKey,
+        platformState: platformStatePDA,
+        tokenMint: tokenMintPDA,
+        consumerTokenAccount: await getAssociatedTokenAddress(
+          tokenMintPDA,
+          wallet.publicKey
+        ),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([wallet])
+      .rpc();
+
   describe("initialize_platform", () => {
     it("initializes the platform successfully", async () => {
       const tx = await program.methods
@@ -77,11 +186,12 @@ describe("loyalty_program", () => {
           MAX_SUPPLY,
           BASE_MINT_FEE,
           FEE_RATE_PER_THOUSAND,
-          SOL_TO_POINTS_RATIO
+          SOL_TO_POINTS_RATIO,
+          0 // classic SPL Token mint, no extensions
         )
         .accounts({
           admin: admin.publicKey,
-          protocolTreasury: protocolTreasury.publicKey,
+          protocolTreasury: treasuryPDA,
           platformState: platformStatePDA,
           tokenMint: tokenMintPDA,
           tokenProgram: TOKEN_PROGRAM_ID,
@@ -99,11 +209,15 @@ describe("loyalty_program", () => {
       expect(platformState.admin.toBase58()).to.equal(admin.publicKey.toBase58());
       expect(platformState.tokenMint.toBase58()).to.equal(tokenMintPDA.toBase58());
       expect(platformState.protocolTreasury.toBase58()).to.equal(
-        protocolTreasury.publicKey.toBase58()
+        treasuryPDA.toBase58()
       );
       expect(platformState.maxSupply.toNumber()).to.equal(MAX_SUPPLY.toNumber());
       expect(platformState.currentSupply.toNumber()).to.equal(0);
       expect(platformState.tokenDecimals).to.equal(TOKEN_DECIMALS);
+
+      // Mint is created with the configured decimals, not a hardcoded value
+      const mint = await getMint(provider.connection, tokenMintPDA);
+      expect(mint.decimals).to.equal(TOKEN_DECIMALS);
       expect(platformState.merchantCount).to.equal(0);
       expect(platformState.isActive).to.be.true;
       expect(platformState.baseMintFee.toNumber()).to.equal(BASE_MINT_FEE.toNumber());
@@ -123,11 +237,12 @@ describe("loyalty_program", () => {
             MAX_SUPPLY,
             BASE_MINT_FEE,
             FEE_RATE_PER_THOUSAND,
-            SOL_TO_POINTS_RATIO
+            SOL_TO_POINTS_RATIO,
+            0
           )
           .accounts({
             admin: admin.publicKey,
-            protocolTreasury: protocolTreasury.publicKey,
+            protocolTreasury: treasuryPDA,
             platformState: platformStatePDA,
             tokenMint: tokenMintPDA,
             tokenProgram: TOKEN_PROGRAM_ID,
@@ -205,6 +320,356 @@ describe("loyalty_program", () => {
     });
   });
 
+  describe("update_platform_config", () => {
+    it("admin updates the conversion ratio and restores it", async () => {
+      const newRatio = new anchor.BN(250);
+
+      const tx = await program.methods
+        .updatePlatformConfig(platformConfig({ solToPointsRatio: newRatio }))
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+        })
+        .rpc();
+
+      console.log("Update platform config tx:", tx);
+
+      let platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.solToPointsRatio.toNumber()).to.equal(
+        newRatio.toNumber()
+      );
+      // Untouched fields keep their values
+      expect(platformState.maxSupply.toString()).to.equal(MAX_SUPPLY.toString());
+
+      await program.methods
+        .updatePlatformConfig(
+          platformConfig({ solToPointsRatio: SOL_TO_POINTS_RATIO })
+        )
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+        })
+        .rpc();
+
+      platformState = await program.account.platformState.fetch(platformStatePDA);
+      expect(platformState.solToPointsRatio.toNumber()).to.equal(
+        SOL_TO_POINTS_RATIO.toNumber()
+      );
+    });
+
+    it("admin updates the base mint fee and restores it", async () => {
+      const newBaseFee = new anchor.BN(10_000);
+
+      await program.methods
+        .updatePlatformConfig(platformConfig({ baseMintFee: newBaseFee }))
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+        })
+        .rpc();
+
+      let platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.baseMintFee.toNumber()).to.equal(newBaseFee.toNumber());
+      // Untouched fields keep their values
+      expect(platformState.feeRatePerThousand.toNumber()).to.equal(
+        FEE_RATE_PER_THOUSAND.toNumber()
+      );
+
+      await program.methods
+        .updatePlatformConfig(platformConfig({ baseMintFee: BASE_MINT_FEE }))
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+        })
+        .rpc();
+
+      platformState = await program.account.platformState.fetch(platformStatePDA);
+      expect(platformState.baseMintFee.toNumber()).to.equal(
+        BASE_MINT_FEE.toNumber()
+      );
+    });
+
+    it("fails when ratio is set to zero", async () => {
+      try {
+        await program.methods
+          .updatePlatformConfig(
+            platformConfig({ solToPointsRatio: new anchor.BN(0) })
+          )
+          .accounts({
+            admin: admin.publicKey,
+            platformState: platformStatePDA,
+          })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidRatio");
+      }
+    });
+
+    it("fails when non-admin tries to update config", async () => {
+      try {
+        await program.methods
+          .updatePlatformConfig(platformConfig({ maxSupply: new anchor.BN(0) }))
+          .accounts({
+            admin: merchant.publicKey,
+            platformState: platformStatePDA,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedAdmin");
+      }
+    });
+  });
+
+  describe("set_fee_config", () => {
+    const feeConfig = (mintFeeBps: number | null) => ({
+      mintFeeBps,
+      volumeFeeTiers: null,
+    });
+
+    it("admin updates the bps mint fee and restores it", async () => {
+      const tx = await program.methods
+        .setFeeConfig(feeConfig(50))
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+        })
+        .rpc();
+
+      console.log("Set fee config tx:", tx);
+
+      let platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.mintFeeBps).to.equal(50);
+      // The flat fee stays with update_platform_config
+      expect(platformState.baseMintFee.toNumber()).to.equal(
+        BASE_MINT_FEE.toNumber()
+      );
+
+      await program.methods
+        .setFeeConfig(feeConfig(0))
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+        })
+        .rpc();
+
+      platformState = await program.account.platformState.fetch(platformStatePDA);
+      expect(platformState.mintFeeBps).to.equal(0);
+    });
+
+    it("fails when mint fee bps exceed 100%", async () => {
+      try {
+        await program.methods
+          .setFeeConfig(feeConfig(10_001))
+          .accounts({
+            admin: admin.publicKey,
+            platformState: platformStatePDA,
+          })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidBasisPoints");
+      }
+    });
+
+    it("fails when non-admin tries to update fees", async () => {
+      try {
+        await program.methods
+          .setFeeConfig(feeConfig(0))
+          .accounts({
+            admin: merchant.publicKey,
+            platformState: platformStatePDA,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedAdmin");
+      }
+    });
+  });
+
+  describe("pause_platform", () => {
+    const PAUSE_MINT = 1 << 0;
+    const REASON_INCIDENT = 1;
+
+    const depositAccounts = async () => ({
+      merchant: merchant.publicKey,
+      protocolTreasury: treasuryPDA,
+      platformState: platformStatePDA,
+      merchantRecord: merchantRecordPDA,
+      tokenMint: tokenMintPDA,
+      merchantTokenAccount: await getAssociatedTokenAddress(
+        tokenMintPDA,
+        merchant.publicKey
+      ),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("pauses and unpauses the platform", async () => {
+      const tx = await program.methods
+        .pausePlatform(REASON_INCIDENT)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      console.log("Pause platform tx:", tx);
+
+      let platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.isActive).to.be.false;
+      expect(platformState.pauseReason).to.equal(REASON_INCIDENT);
+
+      try {
+        await program.methods
+          .depositSol(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL))
+          .accounts(await depositAccounts())
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PlatformInactive");
+      }
+
+      await program.methods
+        .unpausePlatform(0)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      platformState = await program.account.platformState.fetch(platformStatePDA);
+      expect(platformState.isActive).to.be.true;
+    });
+
+    it("pauses minting only via the operations bitmask", async () => {
+      await program.methods
+        .setPausedOperations(PAUSE_MINT, REASON_INCIDENT)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await program.methods
+          .depositSol(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL))
+          .accounts(await depositAccounts())
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("OperationPaused");
+      }
+
+      await program.methods
+        .setPausedOperations(0, 0)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.pausedOperations).to.equal(0);
+    });
+
+    it("fails when non-admin tries to pause", async () => {
+      try {
+        await program.methods
+          .pausePlatform(REASON_INCIDENT)
+          .accounts({ admin: merchant.publicKey, platformState: platformStatePDA })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedAdmin");
+      }
+    });
+  });
+
+  describe("admin transfer", () => {
+    const newAdmin = Keypair.generate();
+
+    it("cancels a pending admin transfer", async () => {
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      let platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.pendingAdmin.toBase58()).to.equal(
+        newAdmin.publicKey.toBase58()
+      );
+
+      const tx = await program.methods
+        .cancelAdminTransfer()
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      console.log("Cancel admin transfer tx:", tx);
+
+      platformState = await program.account.platformState.fetch(platformStatePDA);
+      expect(platformState.pendingAdmin).to.be.null;
+    });
+
+    it("fails when someone other than the pending admin accepts", async () => {
+      await program.methods
+        .proposeAdmin(newAdmin.publicKey)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAdmin()
+          .accounts({ newAdmin: merchant.publicKey, platformState: platformStatePDA })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedPendingAdmin");
+      }
+    });
+
+    it("transfers admin in two steps and back", async () => {
+      const tx = await program.methods
+        .acceptAdmin()
+        .accounts({ newAdmin: newAdmin.publicKey, platformState: platformStatePDA })
+        .signers([newAdmin])
+        .rpc();
+
+      console.log("Accept admin tx:", tx);
+
+      let platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.admin.toBase58()).to.equal(
+        newAdmin.publicKey.toBase58()
+      );
+      expect(platformState.pendingAdmin).to.be.null;
+
+      // Hand admin back to the provider wallet for the remaining tests
+      await program.methods
+        .proposeAdmin(admin.publicKey)
+        .accounts({ admin: newAdmin.publicKey, platformState: platformStatePDA })
+        .signers([newAdmin])
+        .rpc();
+      await program.methods
+        .acceptAdmin()
+        .accounts({ newAdmin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      platformState = await program.account.platformState.fetch(platformStatePDA);
+      expect(platformState.admin.toBase58()).to.equal(admin.publicKey.toBase58());
+    });
+  });
+
   describe("deposit_sol", () => {
     it("merchant deposits SOL and receives loyalty points", async () => {
       const depositAmount = new anchor.BN(0.5 * anchor.web3.LAMPORTS_PER_SOL); // 0.5 SOL
@@ -219,14 +684,14 @@ describe("loyalty_program", () => {
         merchant.publicKey
       );
       const initialTreasurySol = await provider.connection.getBalance(
-        protocolTreasury.publicKey
+        treasuryPDA
       );
 
       const tx = await program.methods
         .depositSol(depositAmount)
         .accounts({
           merchant: merchant.publicKey,
-          protocolTreasury: protocolTreasury.publicKey,
+          protocolTreasury: treasuryPDA,
           platformState: platformStatePDA,
           merchantRecord: merchantRecordPDA,
           tokenMint: tokenMintPDA,
@@ -251,7 +716,7 @@ describe("loyalty_program", () => {
 
       // Verify treasury received SOL
       const finalTreasurySol = await provider.connection.getBalance(
-        protocolTreasury.publicKey
+        treasuryPDA
       );
       expect(finalTreasurySol - initialTreasurySol).to.equal(
         depositAmount.toNumber()
@@ -262,6 +727,9 @@ describe("loyalty_program", () => {
         platformStatePDA
       );
       expect(platformState.currentSupply.toNumber()).to.equal(expectedPoints);
+      expect(platformState.totalSolDeposited.toNumber()).to.equal(
+        depositAmount.toNumber()
+      );
 
       // Verify merchant record updated
       const merchantRecord = await program.account.merchantRecord.fetch(
@@ -281,7 +749,7 @@ describe("loyalty_program", () => {
           .depositSol(new anchor.BN(0))
           .accounts({
             merchant: merchant.publicKey,
-            protocolTreasury: protocolTreasury.publicKey,
+            protocolTreasury: treasuryPDA,
             platformState: platformStatePDA,
             merchantRecord: merchantRecordPDA,
             tokenMint: tokenMintPDA,
@@ -309,13 +777,19 @@ describe("loyalty_program", () => {
       const tx = await program.methods
         .mintPoints(MINT_AMOUNT, "TEST-PURCHASE-001")
         .accounts({
+          authority: merchant.publicKey,
           merchant: merchant.publicKey,
-          protocolTreasury: protocolTreasury.publicKey,
+          protocolTreasury: treasuryPDA,
           platformState: platformStatePDA,
           merchantRecord: merchantRecordPDA,
           tokenMint: tokenMintPDA,
           consumer: consumer.publicKey,
           consumerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: null,
+          merchantDelegate: null,
+          mintReceipt: null,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
@@ -353,13 +827,19 @@ describe("loyalty_program", () => {
         await program.methods
           .mintPoints(MINT_AMOUNT, "UNAUTHORIZED-MINT")
           .accounts({
+            authority: unauthorizedMerchant.publicKey,
             merchant: unauthorizedMerchant.publicKey,
-            protocolTreasury: protocolTreasury.publicKey,
+            protocolTreasury: treasuryPDA,
             platformState: platformStatePDA,
             merchantRecord: unauthorizedMerchantPDA,
             tokenMint: tokenMintPDA,
             consumer: consumer.publicKey,
             consumerTokenAccount: consumerATA,
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
+            pointsLot: await nextLotPDA(consumer.publicKey),
+            campaign: null,
+            merchantDelegate: null,
+            mintReceipt: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -374,35 +854,280 @@ describe("loyalty_program", () => {
     });
   });
 
-  describe("purchase_product_with_points", () => {
-    it("consumer purchases product with loyalty points", async () => {
-      const consumerATA = await getAssociatedTokenAddress(
-        tokenMintPDA,
-        consumer.publicKey
+  describe("create_product", () => {
+    it("merchant lists a product with on-chain pricing", async () => {
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
       );
 
-      // Get initial balance
-      const initialBalance = await getAccount(provider.connection, consumerATA);
-      const initialConsumerBalance = Number(initialBalance.amount);
-
-      // Create a product ID hash
-      const productIdHash = createHash("sha256")
-        .update("PRODUCT-001")
-        .digest();
-      const productIdArray = Array.from(productIdHash);
-
-      const pointsToSpend = new anchor.BN(200 * 10 ** TOKEN_DECIMALS); // 200 points
-
-      // Derive purchase record PDA
-      const [purchaseRecordPDA] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("purchase"),
-          consumer.publicKey.toBuffer(),
-          productIdHash,
-        ],
+      const tx = await program.methods
+        .createProduct(
+          Array.from(PRODUCT_ID_HASH),
+          PRODUCT_PRICE_SOL,
+          PRODUCT_PRICE_POINTS,
+          PRODUCT_REWARD
+        )
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      console.log("Create product tx:", tx);
+
+      const product = await program.account.product.fetch(productPDA);
+      expect(product.merchant.toBase58()).to.equal(merchant.publicKey.toBase58());
+      expect(product.priceSol.toNumber()).to.equal(PRODUCT_PRICE_SOL.toNumber());
+      expect(product.pricePoints.toNumber()).to.equal(
+        PRODUCT_PRICE_POINTS.toNumber()
+      );
+      expect(product.loyaltyPointsReward.toNumber()).to.equal(
+        PRODUCT_REWARD.toNumber()
+      );
+      expect(product.isAvailable).to.be.true;
+    });
+
+    it("fails when product has no price", async () => {
+      const productIdHash = createHash("sha256").update("PRODUCT-FREE").digest();
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), productIdHash],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .createProduct(
+            Array.from(productIdHash),
+            new anchor.BN(0),
+            new anchor.BN(0),
+            PRODUCT_REWARD
+          )
+          .accounts({
+            merchant: merchant.publicKey,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            product: productPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidProductPrice");
+      }
+    });
+  });
+
+  describe("purchase_product_with_sol", () => {
+    it("charges the listed price and mints the listed reward", async () => {
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(consumer.publicKey);
+
+      const initialBalance = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+      const initialMerchantSol = await provider.connection.getBalance(
+        merchant.publicKey
+      );
+
+      const tx = await program.methods
+        .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+        .accounts({
+          customer: consumer.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: purchaseRecordPDA,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: null,
+          referralLink: referralLinkPDA(consumer.publicKey),
+          referrerTokenAccount: null,
+          referrerProfile: null,
+          referrerLot: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([consumer])
+        .rpc();
+
+      console.log("Purchase with SOL tx:", tx);
+
+      const finalBalance = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+      expect(finalBalance - initialBalance).to.equal(PRODUCT_REWARD.toNumber());
+
+      const finalMerchantSol = await provider.connection.getBalance(
+        merchant.publicKey
+      );
+      expect(finalMerchantSol - initialMerchantSol).to.equal(
+        PRODUCT_PRICE_SOL.toNumber()
+      );
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.paymentType).to.equal(0); // 0 = SOL
+      expect(purchaseRecord.amountPaid.toNumber()).to.equal(
+        PRODUCT_PRICE_SOL.toNumber()
+      );
+      expect(purchaseRecord.pointsEarned.toNumber()).to.equal(
+        PRODUCT_REWARD.toNumber()
+      );
+
+      // Records are indexed by the customer's purchase counter
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      expect(profile.purchaseCount.toNumber()).to.equal(
+        purchaseRecord.index.toNumber() + 1
+      );
+    });
+    it("fails when the reward exceeds the merchant's mint allowance", async () => {
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(consumer.publicKey);
+      const allowanceAccounts = {
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+        merchantWallet: merchant.publicKey,
+        merchantRecord: merchantRecordPDA,
+      };
+
+      // Cap the allowance at exactly what has been minted so far
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      await program.methods
+        .setMerchantAllowance(merchantRecord.totalMinted)
+        .accounts(allowanceAccounts)
+        .rpc();
+
+      try {
+        await program.methods
+          .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+          .accounts({
+            customer: consumer.publicKey,
+            merchant: merchant.publicKey,
+            protocolTreasury: treasuryPDA,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            product: productPDA,
+            purchaseRecord: purchaseRecordPDA,
+            tokenMint: tokenMintPDA,
+            customerTokenAccount: consumerATA,
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
+            pointsLot: await nextLotPDA(consumer.publicKey),
+            campaign: null,
+            referralLink: referralLinkPDA(consumer.publicKey),
+            referrerTokenAccount: null,
+            referrerProfile: null,
+            referrerLot: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([consumer])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("ExceedsMintAllowance");
+      } finally {
+        await program.methods
+          .setMerchantAllowance(MINT_ALLOWANCE)
+          .accounts(allowanceAccounts)
+          .rpc();
+      }
+    });
+
+    it("fails when the price is above the customer's max", async () => {
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+
+      try {
+        // The customer signed for less than the listed price
+        await program.methods
+          .purchaseProductWithSol(
+            Array.from(PRODUCT_ID_HASH),
+            PRODUCT_PRICE_SOL.subn(1)
+          )
+          .accounts({
+            customer: consumer.publicKey,
+            merchant: merchant.publicKey,
+            protocolTreasury: treasuryPDA,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            product: productPDA,
+            purchaseRecord: await nextPurchaseRecordPDA(consumer.publicKey),
+            tokenMint: tokenMintPDA,
+            customerTokenAccount: await getAssociatedTokenAddress(
+              tokenMintPDA,
+              consumer.publicKey
+            ),
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
+            pointsLot: await nextLotPDA(consumer.publicKey),
+            campaign: null,
+            referralLink: referralLinkPDA(consumer.publicKey),
+            referrerTokenAccount: null,
+            referrerProfile: null,
+            referrerLot: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([consumer])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PriceAboveMax");
+      }
+    });
+  });
+
+  describe("purchase_product_with_points", () => {
+    it("consumer purchases product with loyalty points", async () => {
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+
+      // Get initial balance
+      const initialBalance = await getAccount(provider.connection, consumerATA);
+      const initialConsumerBalance = Number(initialBalance.amount);
+
+      const productIdArray = Array.from(PRODUCT_ID_HASH);
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
         program.programId
       );
 
+      // Points price comes from the product listing, not the caller
+      const pointsToSpend = PRODUCT_PRICE_POINTS;
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(consumer.publicKey);
+
       const tx = await program.methods
         .purchaseProductWithPoints(productIdArray, pointsToSpend)
         .accounts({
@@ -410,12 +1135,15 @@ describe("loyalty_program", () => {
           merchant: merchant.publicKey,
           platformState: platformStatePDA,
           merchantRecord: merchantRecordPDA,
+          product: productPDA,
           purchaseRecord: purchaseRecordPDA,
           tokenMint: tokenMintPDA,
           customerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
           tokenProgram: TOKEN_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(await openLotAccounts(consumer.publicKey))
         .signers([consumer])
         .rpc();
 
@@ -447,10 +1175,11 @@ describe("loyalty_program", () => {
       const platformState = await program.account.platformState.fetch(
         platformStatePDA
       );
-      // Supply should be: deposit_points + mint_points - burned_points
+      // Supply should be: deposit_points + mint_points + sol_purchase_reward - burned_points
       const expectedSupply =
         50 * 10 ** TOKEN_DECIMALS + // from deposit_sol
-        MINT_AMOUNT.toNumber() - // from mint_points
+        MINT_AMOUNT.toNumber() + // from mint_points
+        PRODUCT_REWARD.toNumber() - // from purchase_product_with_sol
         pointsToSpend.toNumber(); // burned in purchase
       expect(platformState.currentSupply.toNumber()).to.equal(expectedSupply);
 
@@ -473,18 +1202,25 @@ describe("loyalty_program", () => {
         .update("PRODUCT-EXPENSIVE")
         .digest();
       const productIdArray = Array.from(productIdHash);
-
-      const [purchaseRecordPDA] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("purchase"),
-          consumer.publicKey.toBuffer(),
-          productIdHash,
-        ],
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), productIdHash],
         program.programId
       );
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(consumer.publicKey);
 
-      // Try to spend more points than available
+      // List a product priced above the consumer's balance
       const excessPoints = new anchor.BN(999_999 * 10 ** TOKEN_DECIMALS);
+      await program.methods
+        .createProduct(productIdArray, new anchor.BN(0), excessPoints, new anchor.BN(0))
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
 
       try {
         await program.methods
@@ -494,12 +1230,15 @@ describe("loyalty_program", () => {
             merchant: merchant.publicKey,
             platformState: platformStatePDA,
             merchantRecord: merchantRecordPDA,
+            product: productPDA,
             purchaseRecord: purchaseRecordPDA,
             tokenMint: tokenMintPDA,
             customerTokenAccount: consumerATA,
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
+          .remainingAccounts(await openLotAccounts(consumer.publicKey))
           .signers([consumer])
           .rpc();
         assert.fail("Should have thrown an error");
@@ -537,11 +1276,13 @@ describe("loyalty_program", () => {
           merchantRecord: merchantRecordPDA,
           tokenMint: tokenMintPDA,
           consumerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
           merchantTokenAccount: merchantATA,
           tokenProgram: TOKEN_PROGRAM_ID,
           associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
           systemProgram: SystemProgram.programId,
         })
+        .remainingAccounts(await openLotAccounts(consumer.publicKey))
         .signers([consumer])
         .rpc();
 
@@ -588,11 +1329,13 @@ describe("loyalty_program", () => {
             merchantRecord: merchantRecordPDA,
             tokenMint: tokenMintPDA,
             consumerTokenAccount: consumerATA,
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
             merchantTokenAccount: merchantATA,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
           })
+          .remainingAccounts(await openLotAccounts(consumer.publicKey))
           .signers([consumer])
           .rpc();
         assert.fail("Should have thrown an error");
@@ -639,13 +1382,19 @@ describe("loyalty_program", () => {
         await program.methods
           .mintPoints(MINT_AMOUNT, "SHOULD-FAIL")
           .accounts({
+            authority: merchant.publicKey,
             merchant: merchant.publicKey,
-            protocolTreasury: protocolTreasury.publicKey,
+            protocolTreasury: treasuryPDA,
             platformState: platformStatePDA,
             merchantRecord: merchantRecordPDA,
             tokenMint: tokenMintPDA,
             consumer: consumer.publicKey,
             consumerTokenAccount: consumerATA,
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
+            pointsLot: await nextLotPDA(consumer.publicKey),
+            campaign: null,
+            merchantDelegate: null,
+            mintReceipt: null,
             tokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
@@ -669,7 +1418,7 @@ describe("loyalty_program", () => {
           .depositSol(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL))
           .accounts({
             merchant: merchant.publicKey,
-            protocolTreasury: protocolTreasury.publicKey,
+            protocolTreasury: treasuryPDA,
             platformState: platformStatePDA,
             merchantRecord: merchantRecordPDA,
             tokenMint: tokenMintPDA,
@@ -686,4 +1435,3095 @@ describe("loyalty_program", () => {
       }
     });
   });
+
+  describe("reinstate_merchant", () => {
+    it("reinstates a revoked merchant and keeps its history", async () => {
+      const before = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+
+      const tx = await program.methods
+        .reinstateMerchant()
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          merchantWallet: merchant.publicKey,
+          merchantRecord: merchantRecordPDA,
+        })
+        .rpc();
+
+      console.log("Reinstate merchant tx:", tx);
+
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(merchantRecord.isAuthorized).to.be.true;
+      expect(merchantRecord.totalMinted.toNumber()).to.equal(
+        before.totalMinted.toNumber()
+      );
+      expect(merchantRecord.totalRedeemed.toNumber()).to.equal(
+        before.totalRedeemed.toNumber()
+      );
+
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(platformState.merchantCount).to.equal(1);
+    });
+
+    it("fails to reinstate an authorized merchant", async () => {
+      try {
+        await program.methods
+          .reinstateMerchant()
+          .accounts({
+            admin: admin.publicKey,
+            platformState: platformStatePDA,
+            merchantWallet: merchant.publicKey,
+            merchantRecord: merchantRecordPDA,
+          })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("MerchantAlreadyAuthorized");
+      }
+    });
+  });
+
+  describe("set_merchant_allowance", () => {
+    it("raises the merchant's mint allowance", async () => {
+      const newAllowance = MINT_ALLOWANCE.muln(2);
+
+      const tx = await program.methods
+        .setMerchantAllowance(newAllowance)
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          merchantWallet: merchant.publicKey,
+          merchantRecord: merchantRecordPDA,
+        })
+        .rpc();
+
+      console.log("Set merchant allowance tx:", tx);
+
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(merchantRecord.mintAllowance.toString()).to.equal(
+        newAllowance.toString()
+      );
+    });
+
+    it("fails when allowance is below the amount already minted", async () => {
+      try {
+        await program.methods
+          .setMerchantAllowance(new anchor.BN(1))
+          .accounts({
+            admin: admin.publicKey,
+            platformState: platformStatePDA,
+            merchantWallet: merchant.publicKey,
+            merchantRecord: merchantRecordPDA,
+          })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidMintAllowance");
+      }
+    });
+  });
+
+  describe("withdraw_treasury", () => {
+    it("admin withdraws collected fees to a destination wallet", async () => {
+      const destination = Keypair.generate();
+      const amount = new anchor.BN(0.1 * anchor.web3.LAMPORTS_PER_SOL);
+
+      const before = await program.account.platformState.fetch(platformStatePDA);
+
+      const tx = await program.methods
+        .withdrawTreasury(amount)
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          protocolTreasury: treasuryPDA,
+          destination: destination.publicKey,
+        })
+        .rpc();
+
+      console.log("Withdraw treasury tx:", tx);
+
+      const destinationBalance = await provider.connection.getBalance(
+        destination.publicKey
+      );
+      expect(destinationBalance).to.equal(amount.toNumber());
+
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(
+        platformState.totalWithdrawn.sub(before.totalWithdrawn).toNumber()
+      ).to.equal(amount.toNumber());
+    });
+
+    it("fails when withdrawing more than the treasury holds", async () => {
+      try {
+        await program.methods
+          .withdrawTreasury(new anchor.BN(1_000 * anchor.web3.LAMPORTS_PER_SOL))
+          .accounts({
+            admin: admin.publicKey,
+            platformState: platformStatePDA,
+            protocolTreasury: treasuryPDA,
+            destination: admin.publicKey,
+          })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InsufficientTreasuryBalance");
+      }
+    });
+  });
+
+  describe("expire_points", () => {
+    const holder = Keypair.generate();
+    const expiryConfig = (seconds: number) =>
+      platformConfig({ pointsExpirySeconds: new anchor.BN(seconds) });
+    const configAccounts = () => ({
+      admin: admin.publicKey,
+      platformState: platformStatePDA,
+    });
+    const expire = async () =>
+      program.methods
+        .expirePoints()
+        .accounts({
+          cranker: admin.publicKey,
+          platformState: platformStatePDA,
+          tokenMint: tokenMintPDA,
+          consumer: holder.publicKey,
+          consumerProfile: consumerProfilePDA(holder.publicKey),
+          pointsLot: lotPDA(holder.publicKey, new anchor.BN(0)),
+          consumerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            holder.publicKey
+          ),
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      const airdrop = await provider.connection.requestAirdrop(
+        holder.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    });
+
+    it("closes an expired lot and owes the points the holder didn't approve", async () => {
+      const holderATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        holder.publicKey
+      );
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(holder.publicKey);
+
+      await program.methods
+        .updatePlatformConfig(expiryConfig(1))
+        .accounts(configAccounts())
+        .rpc();
+
+      try {
+        // Lot 0: merchant-issued points
+        await program.methods
+          .mintPoints(MINT_AMOUNT, "EXPIRING-001")
+          .accounts({
+            authority: merchant.publicKey,
+            merchant: merchant.publicKey,
+            protocolTreasury: treasuryPDA,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            tokenMint: tokenMintPDA,
+            consumer: holder.publicKey,
+            consumerTokenAccount: holderATA,
+            consumerProfile: consumerProfilePDA(holder.publicKey),
+            pointsLot: await nextLotPDA(holder.publicKey),
+            campaign: null,
+            merchantDelegate: null,
+            mintReceipt: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+
+        // Lot 1: purchase reward
+        await program.methods
+          .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+          .accounts({
+            customer: holder.publicKey,
+            merchant: merchant.publicKey,
+            protocolTreasury: treasuryPDA,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            product: productPDA,
+            purchaseRecord: purchaseRecordPDA,
+            tokenMint: tokenMintPDA,
+            customerTokenAccount: holderATA,
+            consumerProfile: consumerProfilePDA(holder.publicKey),
+            pointsLot: await nextLotPDA(holder.publicKey),
+            campaign: null,
+            referralLink: referralLinkPDA(holder.publicKey),
+            referrerTokenAccount: null,
+            referrerProfile: null,
+            referrerLot: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([holder])
+          .rpc();
+      } finally {
+        await program.methods
+          .updatePlatformConfig(expiryConfig(0))
+          .accounts(configAccounts())
+          .rpc();
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      // Without a permanent delegate the crank only burns what the holder approved
+      const approved = MINT_AMOUNT.divn(2);
+      await approveExpiryBurns(holder, approved);
+
+      const headLot = lotPDA(holder.publicKey, new anchor.BN(0));
+      const supplyBefore = (
+        await program.account.platformState.fetch(platformStatePDA)
+      ).currentSupply;
+
+      const tx = await expire();
+      console.log("Expire points tx:", tx);
+
+      const tokenAccount = await getAccount(provider.connection, holderATA);
+      expect(Number(tokenAccount.amount)).to.equal(
+        MINT_AMOUNT.sub(approved).add(PRODUCT_REWARD).toNumber()
+      );
+
+      // The whole lot leaves supply; the unburned rest is owed on the profile
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(
+        supplyBefore.sub(platformState.currentSupply).toNumber()
+      ).to.equal(MINT_AMOUNT.toNumber());
+
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(holder.publicKey)
+      );
+      expect(profile.headLotIndex.toNumber()).to.equal(1);
+      expect(profile.lotBalance.toNumber()).to.equal(PRODUCT_REWARD.toNumber());
+      expect(profile.expiredUnburned.toNumber()).to.equal(
+        MINT_AMOUNT.sub(approved).toNumber()
+      );
+      expect(await provider.connection.getAccountInfo(headLot)).to.be.null;
+    });
+
+    it("spends past an expired lot and burns it with the owed points", async () => {
+      const holderATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        holder.publicKey
+      );
+
+      // Lot 2: fresh points that never expire
+      await program.methods
+        .mintPoints(MINT_AMOUNT, "EXPIRING-002")
+        .accounts({
+          authority: merchant.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: holder.publicKey,
+          consumerTokenAccount: holderATA,
+          consumerProfile: consumerProfilePDA(holder.publicKey),
+          pointsLot: await nextLotPDA(holder.publicKey),
+          campaign: null,
+          merchantDelegate: null,
+          mintReceipt: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      // Lot 1 (the purchase reward) has expired but nobody cranked it
+      const redeemAmount = new anchor.BN(100 * 10 ** TOKEN_DECIMALS);
+      await program.methods
+        .redeemPoints(redeemAmount, "REWARD-EXPIRED")
+        .accounts({
+          consumer: holder.publicKey,
+          platformState: platformStatePDA,
+          merchant: merchant.publicKey,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumerTokenAccount: holderATA,
+          consumerProfile: consumerProfilePDA(holder.publicKey),
+          merchantTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            merchant.publicKey
+          ),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(await openLotAccounts(holder.publicKey))
+        .signers([holder])
+        .rpc();
+
+      // The expired reward and the points owed from lot 0 are burned, and
+      // the spend comes out of lot 2
+      const tokenAccount = await getAccount(provider.connection, holderATA);
+      expect(Number(tokenAccount.amount)).to.equal(
+        MINT_AMOUNT.sub(redeemAmount).toNumber()
+      );
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(holder.publicKey)
+      );
+      expect(profile.headLotIndex.toNumber()).to.equal(2);
+      expect(profile.expiredUnburned.toNumber()).to.equal(0);
+      expect(profile.lotBalance.toNumber()).to.equal(
+        MINT_AMOUNT.sub(redeemAmount).toNumber()
+      );
+    });
+
+    it("fails when the lot has not expired", async () => {
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+
+      try {
+        await program.methods
+          .expirePoints()
+          .accounts({
+            cranker: admin.publicKey,
+            platformState: platformStatePDA,
+            tokenMint: tokenMintPDA,
+            consumer: consumer.publicKey,
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
+            pointsLot: lotPDA(consumer.publicKey, profile.headLotIndex),
+            consumerTokenAccount: consumerATA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PointsNotExpired");
+      }
+    });
+  });
+
+  describe("set_tier_config", () => {
+    const member = Keypair.generate();
+    const TIERS_DISABLED = {
+      silverThreshold: new anchor.BN(0),
+      goldThreshold: new anchor.BN(0),
+      multipliersBps: [10_000, 10_000, 10_000],
+    };
+    const tierAccounts = () => ({
+      admin: admin.publicKey,
+      platformState: platformStatePDA,
+    });
+
+    const mintTo = async (amount: anchor.BN, reference: string) => {
+      const memberATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        member.publicKey
+      );
+      await program.methods
+        .mintPoints(amount, reference)
+        .accounts({
+          authority: merchant.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: member.publicKey,
+          consumerTokenAccount: memberATA,
+          consumerProfile: consumerProfilePDA(member.publicKey),
+          pointsLot: await nextLotPDA(member.publicKey),
+          campaign: null,
+          merchantDelegate: null,
+          mintReceipt: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+      return Number((await getAccount(provider.connection, memberATA)).amount);
+    };
+
+    it("promotes a consumer and boosts later rewards", async () => {
+      const tx = await program.methods
+        .setTierConfig({
+          silverThreshold: MINT_AMOUNT,
+          goldThreshold: MINT_AMOUNT.muln(10),
+          multipliersBps: [10_000, 15_000, 20_000],
+        })
+        .accounts(tierAccounts())
+        .rpc();
+
+      console.log("Set tier config tx:", tx);
+
+      try {
+        // Bronze earns 1x and reaches the Silver threshold
+        expect(await mintTo(MINT_AMOUNT, "TIER-001")).to.equal(
+          MINT_AMOUNT.toNumber()
+        );
+        let profile = await program.account.consumerProfile.fetch(
+          consumerProfilePDA(member.publicKey)
+        );
+        expect(profile.tier).to.equal(1); // Silver
+        expect(profile.lifetimeEarned.toNumber()).to.equal(MINT_AMOUNT.toNumber());
+
+        // Silver earns 1.5x
+        expect(await mintTo(MINT_AMOUNT, "TIER-002")).to.equal(
+          MINT_AMOUNT.toNumber() * 2.5
+        );
+        profile = await program.account.consumerProfile.fetch(
+          consumerProfilePDA(member.publicKey)
+        );
+        expect(profile.lifetimeEarned.toNumber()).to.equal(
+          MINT_AMOUNT.toNumber() * 2.5
+        );
+      } finally {
+        await program.methods
+          .setTierConfig(TIERS_DISABLED)
+          .accounts(tierAccounts())
+          .rpc();
+      }
+    });
+
+    it("fails when multipliers decrease with tier", async () => {
+      try {
+        await program.methods
+          .setTierConfig({
+            silverThreshold: MINT_AMOUNT,
+            goldThreshold: MINT_AMOUNT.muln(10),
+            multipliersBps: [10_000, 20_000, 15_000],
+          })
+          .accounts(tierAccounts())
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidTierConfig");
+      }
+    });
+
+    it("fails when non-admin tries to set tiers", async () => {
+      try {
+        await program.methods
+          .setTierConfig(TIERS_DISABLED)
+          .accounts({
+            admin: consumer.publicKey,
+            platformState: platformStatePDA,
+          })
+          .signers([consumer])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedAdmin");
+      }
+    });
+  });
+
+  describe("campaigns", () => {
+    const campaignId = new anchor.BN(1);
+    let campaignPDA: PublicKey;
+
+    before(() => {
+      [campaignPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("campaign"),
+          merchant.publicKey.toBuffer(),
+          campaignId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+    });
+
+    it("adds campaign bonus points up to the budget", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      const budget = new anchor.BN(15 * 10 ** TOKEN_DECIMALS);
+
+      // Double points on every issuance, with a budget smaller than one bonus
+      const tx = await program.methods
+        .createCampaign(
+          campaignId,
+          new anchor.BN(now - 60),
+          new anchor.BN(now + 3600),
+          null,
+          10_000,
+          budget
+        )
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          campaign: campaignPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      console.log("Create campaign tx:", tx);
+
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const initialBalance = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+
+      await program.methods
+        .mintPoints(MINT_AMOUNT, "CAMPAIGN-001")
+        .accounts({
+          authority: merchant.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: consumer.publicKey,
+          consumerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: campaignPDA,
+          merchantDelegate: null,
+          mintReceipt: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      const finalBalance = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+      expect(finalBalance - initialBalance).to.equal(
+        MINT_AMOUNT.toNumber() + budget.toNumber()
+      );
+
+      const campaign = await program.account.campaign.fetch(campaignPDA);
+      expect(campaign.bonusIssued.toNumber()).to.equal(budget.toNumber());
+    });
+
+    it("merchant closes the campaign", async () => {
+      await program.methods
+        .closeCampaign()
+        .accounts({
+          merchant: merchant.publicKey,
+          campaign: campaignPDA,
+        })
+        .signers([merchant])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(campaignPDA)).to.be.null;
+    });
+
+    it("fails when the campaign ends before it starts", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      try {
+        await program.methods
+          .createCampaign(
+            new anchor.BN(2),
+            new anchor.BN(now + 3600),
+            new anchor.BN(now + 60),
+            null,
+            10_000,
+            new anchor.BN(1)
+          )
+          .accounts({
+            merchant: merchant.publicKey,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            campaign: PublicKey.findProgramAddressSync(
+              [
+                Buffer.from("campaign"),
+                merchant.publicKey.toBuffer(),
+                new anchor.BN(2).toArrayLike(Buffer, "le", 8),
+              ],
+              program.programId
+            )[0],
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidCampaignWindow");
+      }
+    });
+  });
+
+  describe("referrals", () => {
+    const referee = Keypair.generate();
+    const REFERRER_BONUS = new anchor.BN(25 * 10 ** TOKEN_DECIMALS);
+    const REFEREE_BONUS = new anchor.BN(5 * 10 ** TOKEN_DECIMALS);
+    const referralLink = referralLinkPDA(referee.publicKey);
+
+    const bonusConfig = (referrerBonus: anchor.BN, refereeBonus: anchor.BN) =>
+      platformConfig({ referrerBonus, refereeBonus });
+
+    const purchase = async (link: PublicKey = referralLink) => {
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(referee.publicKey);
+      await program.methods
+        .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+        .accounts({
+          customer: referee.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: purchaseRecordPDA,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            referee.publicKey
+          ),
+          consumerProfile: consumerProfilePDA(referee.publicKey),
+          pointsLot: await nextLotPDA(referee.publicKey),
+          campaign: null,
+          referralLink: link,
+          referrerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            consumer.publicKey
+          ),
+          referrerProfile: consumerProfilePDA(consumer.publicKey),
+          referrerLot: await nextLotPDA(consumer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([referee])
+        .rpc();
+    };
+
+    before(async () => {
+      const airdrop = await provider.connection.requestAirdrop(
+        referee.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      // The purchase mints into the referee's existing token account
+      await createAssociatedTokenAccount(
+        provider.connection,
+        referee,
+        tokenMintPDA,
+        referee.publicKey
+      );
+    });
+
+    it("fails when a consumer refers themselves", async () => {
+      try {
+        await program.methods
+          .registerReferrer()
+          .accounts({
+            referee: referee.publicKey,
+            referrer: referee.publicKey,
+            platformState: platformStatePDA,
+            consumerProfile: consumerProfilePDA(referee.publicKey),
+            referralLink,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([referee])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("SelfReferral");
+      }
+    });
+
+    it("pays both parties once on the referee's first purchase", async () => {
+      const tx = await program.methods
+        .registerReferrer()
+        .accounts({
+          referee: referee.publicKey,
+          referrer: consumer.publicKey,
+          platformState: platformStatePDA,
+          consumerProfile: consumerProfilePDA(referee.publicKey),
+          referralLink,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([referee])
+        .rpc();
+
+      console.log("Register referrer tx:", tx);
+
+      const refereeATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        referee.publicKey
+      );
+      const referrerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const referrerBefore = Number(
+        (await getAccount(provider.connection, referrerATA)).amount
+      );
+      const referrerProfileBefore = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      const referrerLot = await nextLotPDA(consumer.publicKey);
+
+      await program.methods
+        .updatePlatformConfig(bonusConfig(REFERRER_BONUS, REFEREE_BONUS))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await purchase();
+
+        expect(
+          Number((await getAccount(provider.connection, refereeATA)).amount)
+        ).to.equal(PRODUCT_REWARD.toNumber() + REFEREE_BONUS.toNumber());
+        expect(
+          Number((await getAccount(provider.connection, referrerATA)).amount) -
+            referrerBefore
+        ).to.equal(REFERRER_BONUS.toNumber());
+
+        // The referrer's bonus is lot-backed and counts toward their tier
+        const lot = await program.account.pointsLot.fetch(referrerLot);
+        expect(lot.amount.toNumber()).to.equal(REFERRER_BONUS.toNumber());
+        const referrerProfile = await program.account.consumerProfile.fetch(
+          consumerProfilePDA(consumer.publicKey)
+        );
+        expect(
+          referrerProfile.lifetimeEarned
+            .sub(referrerProfileBefore.lifetimeEarned)
+            .toNumber()
+        ).to.equal(REFERRER_BONUS.toNumber());
+
+        // A second purchase earns only the product reward
+        await purchase();
+
+        expect(
+          Number((await getAccount(provider.connection, refereeATA)).amount)
+        ).to.equal(PRODUCT_REWARD.toNumber() * 2 + REFEREE_BONUS.toNumber());
+        expect(
+          Number((await getAccount(provider.connection, referrerATA)).amount) -
+            referrerBefore
+        ).to.equal(REFERRER_BONUS.toNumber());
+      } finally {
+        await program.methods
+          .updatePlatformConfig(bonusConfig(new anchor.BN(0), new anchor.BN(0)))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(referee.publicKey)
+      );
+      expect(profile.firstPurchaseDone).to.be.true;
+    });
+
+    it("fails when the referral link passed isn't the customer's", async () => {
+      try {
+        await purchase(referralLinkPDA(consumer.publicKey));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("ConstraintSeeds");
+      }
+    });
+  });
+
+  describe("refund_purchase", () => {
+    const buyer = Keypair.generate();
+    let buyerATA: PublicKey;
+    let purchaseRecordPDA: PublicKey;
+
+    const refundAccounts = (customer: PublicKey, customerATA: PublicKey, record: PublicKey) => ({
+      merchant: merchant.publicKey,
+      platformState: platformStatePDA,
+      merchantRecord: merchantRecordPDA,
+      customer,
+      purchaseRecord: record,
+      consumerProfile: consumerProfilePDA(customer),
+      campaign: null,
+      tokenMint: tokenMintPDA,
+      customerTokenAccount: customerATA,
+      merchantPaymentAccount: null,
+      customerPaymentAccount: null,
+      paymentTokenMint: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      paymentTokenProgram: null,
+      systemProgram: SystemProgram.programId,
+    });
+
+    // SOL purchase of the test product, returning its purchase record
+    const purchaseAs = async (wallet: Keypair, campaign: PublicKey | null = null) => {
+      const record = await nextPurchaseRecordPDA(wallet.publicKey);
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+
+      await program.methods
+        .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+        .accounts({
+          customer: wallet.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: record,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            wallet.publicKey
+          ),
+          consumerProfile: consumerProfilePDA(wallet.publicKey),
+          pointsLot: await nextLotPDA(wallet.publicKey),
+          campaign,
+          referralLink: referralLinkPDA(wallet.publicKey),
+          referrerTokenAccount: null,
+          referrerProfile: null,
+          referrerLot: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([wallet])
+        .rpc();
+      return record;
+    };
+
+    before(async () => {
+      const airdrop = await provider.connection.requestAirdrop(
+        buyer.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      buyerATA = await createAssociatedTokenAccount(
+        provider.connection,
+        buyer,
+        tokenMintPDA,
+        buyer.publicKey
+      );
+      purchaseRecordPDA = await purchaseAs(buyer);
+
+      // Lets the merchant-signed refund claw back the reward
+      await approveExpiryBurns(buyer, PRODUCT_REWARD);
+    });
+
+    it("returns SOL and burns the reward for a SOL purchase", async () => {
+      const buyerSolBefore = await provider.connection.getBalance(buyer.publicKey);
+      const supplyBefore = (
+        await program.account.platformState.fetch(platformStatePDA)
+      ).currentSupply;
+
+      const tx = await program.methods
+        .refundPurchase()
+        .accounts(refundAccounts(buyer.publicKey, buyerATA, purchaseRecordPDA))
+        .remainingAccounts(await openLotAccounts(buyer.publicKey))
+        .signers([merchant])
+        .rpc();
+
+      console.log("Refund purchase tx:", tx);
+
+      const buyerSolAfter = await provider.connection.getBalance(buyer.publicKey);
+      expect(buyerSolAfter - buyerSolBefore).to.equal(PRODUCT_PRICE_SOL.toNumber());
+      expect(
+        Number((await getAccount(provider.connection, buyerATA)).amount)
+      ).to.equal(0);
+
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      expect(
+        supplyBefore.sub(platformState.currentSupply).toNumber()
+      ).to.equal(PRODUCT_REWARD.toNumber());
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.status).to.equal(4); // 4 = refunded
+    });
+
+    it("returns SOL and records a shortfall when the reward can't be clawed back", async () => {
+      const spender = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        spender.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+      const spenderATA = await createAssociatedTokenAccount(
+        provider.connection,
+        spender,
+        tokenMintPDA,
+        spender.publicKey
+      );
+      // No expiry opt-in, so the platform can't burn the reward
+      const record = await purchaseAs(spender);
+      const spenderSolBefore = await provider.connection.getBalance(
+        spender.publicKey
+      );
+
+      await program.methods
+        .refundPurchase()
+        .accounts(refundAccounts(spender.publicKey, spenderATA, record))
+        .remainingAccounts(await openLotAccounts(spender.publicKey))
+        .signers([merchant])
+        .rpc();
+
+      expect(
+        (await provider.connection.getBalance(spender.publicKey)) -
+          spenderSolBefore
+      ).to.equal(PRODUCT_PRICE_SOL.toNumber());
+      expect(
+        Number((await getAccount(provider.connection, spenderATA)).amount)
+      ).to.equal(PRODUCT_REWARD.toNumber());
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(record);
+      expect(purchaseRecord.clawbackShortfall.toNumber()).to.equal(
+        PRODUCT_REWARD.toNumber()
+      );
+    });
+
+    it("fails to re-mint spent points while minting is paused", async () => {
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const record = await nextPurchaseRecordPDA(consumer.publicKey);
+      await program.methods
+        .purchaseProductWithPoints(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_POINTS)
+        .accounts({
+          customer: consumer.publicKey,
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: record,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(await openLotAccounts(consumer.publicKey))
+        .signers([consumer])
+        .rpc();
+
+      await program.methods
+        .setPausedOperations(1 << 0, 0)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await program.methods
+          .refundPurchase()
+          .accounts(refundAccounts(consumer.publicKey, consumerATA, record))
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("OperationPaused");
+      } finally {
+        await program.methods
+          .setPausedOperations(0, 0)
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+    });
+
+    it("re-mints the points for a points purchase", async () => {
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      // Latest points purchase that hasn't been refunded yet
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      let pointsRecordPDA: PublicKey;
+      for (let i = profile.purchaseCount.toNumber() - 1; i >= 0; i--) {
+        const candidate = purchaseRecordAt(consumer.publicKey, new anchor.BN(i));
+        const record = await program.account.purchaseRecord.fetchNullable(
+          candidate
+        );
+        if (record && record.paymentType === 1 && record.status !== 4) {
+          pointsRecordPDA = candidate;
+          break;
+        }
+      }
+      const balanceBefore = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+      const mintedBefore = (
+        await program.account.merchantRecord.fetch(merchantRecordPDA)
+      ).totalMinted;
+
+      await program.methods
+        .refundPurchase()
+        .accounts(refundAccounts(consumer.publicKey, consumerATA, pointsRecordPDA))
+        .signers([merchant])
+        .rpc();
+
+      expect(
+        Number((await getAccount(provider.connection, consumerATA)).amount) -
+          balanceBefore
+      ).to.equal(PRODUCT_PRICE_POINTS.toNumber());
+
+      // The re-mint is issued on the merchant's account
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(
+        merchantRecord.totalMinted.sub(mintedBefore).toNumber()
+      ).to.equal(PRODUCT_PRICE_POINTS.toNumber());
+    });
+
+    it("returns the clawed-back campaign bonus to the campaign budget", async () => {
+      const campaignId = new anchor.BN(3);
+      const [campaignPDA] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("campaign"),
+          merchant.publicKey.toBuffer(),
+          campaignId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createCampaign(
+          campaignId,
+          new anchor.BN(now - 60),
+          new anchor.BN(now + 3600),
+          null,
+          10_000,
+          PRODUCT_REWARD
+        )
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          campaign: campaignPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      const shopper = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        shopper.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+      const shopperATA = await createAssociatedTokenAccount(
+        provider.connection,
+        shopper,
+        tokenMintPDA,
+        shopper.publicKey
+      );
+      const record = await purchaseAs(shopper, campaignPDA);
+      const purchaseRecord = await program.account.purchaseRecord.fetch(record);
+      expect(purchaseRecord.campaign.toBase58()).to.equal(campaignPDA.toBase58());
+      expect(purchaseRecord.campaignBonus.toNumber()).to.equal(
+        PRODUCT_REWARD.toNumber()
+      );
+      await approveExpiryBurns(shopper, purchaseRecord.pointsEarned);
+
+      await program.methods
+        .refundPurchase()
+        .accounts({
+          ...refundAccounts(shopper.publicKey, shopperATA, record),
+          campaign: campaignPDA,
+        })
+        .remainingAccounts(await openLotAccounts(shopper.publicKey))
+        .signers([merchant])
+        .rpc();
+
+      const campaign = await program.account.campaign.fetch(campaignPDA);
+      expect(campaign.bonusIssued.toNumber()).to.equal(0);
+    });
+
+    it("fails to refund the same purchase twice", async () => {
+      try {
+        await program.methods
+          .refundPurchase()
+          .accounts(refundAccounts(buyer.publicKey, buyerATA, purchaseRecordPDA))
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("AlreadyRefunded");
+      }
+    });
+  });
+
+  describe("purchase_product_mixed", () => {
+    const [productPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+      program.programId
+    );
+    const mixedAccounts = async (purchaseRecord: PublicKey) => ({
+      customer: consumer.publicKey,
+      merchant: merchant.publicKey,
+      platformState: platformStatePDA,
+      merchantRecord: merchantRecordPDA,
+      product: productPDA,
+      purchaseRecord,
+      tokenMint: tokenMintPDA,
+      customerTokenAccount: await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      ),
+      consumerProfile: consumerProfilePDA(consumer.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    it("burns the points portion and charges the SOL remainder", async () => {
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(consumer.publicKey);
+      // 4 points at 100 points/SOL cover 0.04 SOL of the 0.1 SOL price
+      const pointsAmount = new anchor.BN(4 * 10 ** TOKEN_DECIMALS);
+      const expectedSol = PRODUCT_PRICE_SOL.toNumber() - 0.04 * anchor.web3.LAMPORTS_PER_SOL;
+
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const pointsBefore = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+      const merchantSolBefore = await provider.connection.getBalance(
+        merchant.publicKey
+      );
+
+      const tx = await program.methods
+        .purchaseProductMixed(
+          Array.from(PRODUCT_ID_HASH),
+          pointsAmount,
+          new anchor.BN(expectedSol)
+        )
+        .accounts(await mixedAccounts(purchaseRecordPDA))
+        .remainingAccounts(await openLotAccounts(consumer.publicKey))
+        .signers([consumer])
+        .rpc();
+
+      console.log("Mixed purchase tx:", tx);
+
+      expect(
+        pointsBefore -
+          Number((await getAccount(provider.connection, consumerATA)).amount)
+      ).to.equal(pointsAmount.toNumber());
+      expect(
+        (await provider.connection.getBalance(merchant.publicKey)) -
+          merchantSolBefore
+      ).to.equal(expectedSol);
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.paymentType).to.equal(2); // 2 = Mixed
+      expect(purchaseRecord.amountPaid.toNumber()).to.equal(expectedSol);
+      expect(purchaseRecord.pointsPaid.toNumber()).to.equal(
+        pointsAmount.toNumber()
+      );
+    });
+
+    it("fails when the points cover the whole price", async () => {
+      try {
+        await program.methods
+          .purchaseProductMixed(
+            Array.from(PRODUCT_ID_HASH),
+            new anchor.BN(10 * 10 ** TOKEN_DECIMALS),
+            PRODUCT_PRICE_SOL
+          )
+          .accounts(
+            await mixedAccounts(await nextPurchaseRecordPDA(consumer.publicKey))
+          )
+          .remainingAccounts(await openLotAccounts(consumer.publicKey))
+          .signers([consumer])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidPaymentSplit");
+      }
+    });
+  });
+
+  describe("merchant_cash_out", () => {
+    const cashOutConfig = (buybackRatio: anchor.BN, cashOutSpreadBps: number) => ({
+      buybackRatio,
+      cashOutSpreadBps,
+    });
+    const cashOutAccounts = async () => ({
+      merchant: merchant.publicKey,
+      protocolTreasury: treasuryPDA,
+      platformState: platformStatePDA,
+      merchantRecord: merchantRecordPDA,
+      tokenMint: tokenMintPDA,
+      merchantTokenAccount: await getAssociatedTokenAddress(
+        tokenMintPDA,
+        merchant.publicKey
+      ),
+      tokenProgram: TOKEN_PROGRAM_ID,
+    });
+
+    // Deposit SOL until the merchant holds at least `amount` points
+    const topUpMerchantPoints = async (amount: anchor.BN) => {
+      const merchantATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        merchant.publicKey
+      );
+      const balance = new anchor.BN(
+        (await getAccount(provider.connection, merchantATA)).amount.toString()
+      );
+      if (balance.gte(amount)) {
+        return;
+      }
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      const lamports = amount
+        .sub(balance)
+        .mul(new anchor.BN(anchor.web3.LAMPORTS_PER_SOL))
+        .div(platformState.solToPointsRatio.mul(new anchor.BN(10 ** TOKEN_DECIMALS)))
+        .add(new anchor.BN(0.01 * anchor.web3.LAMPORTS_PER_SOL));
+      await program.methods
+        .depositSol(lamports)
+        .accounts({
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          merchantTokenAccount: merchantATA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+    };
+
+    it("burns merchant points and pays SOL minus the spread", async () => {
+      // 200 points per SOL with a 5% spread
+      await program.methods
+        .setBuybackConfig(cashOutConfig(new anchor.BN(200), 500))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      const pointsAmount = new anchor.BN(10 * 10 ** TOKEN_DECIMALS);
+      const gross = 0.05 * anchor.web3.LAMPORTS_PER_SOL;
+      const expectedPayout = gross - gross * 0.05;
+
+      const merchantATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        merchant.publicKey
+      );
+      const pointsBefore = Number(
+        (await getAccount(provider.connection, merchantATA)).amount
+      );
+      const solBefore = await provider.connection.getBalance(merchant.publicKey);
+
+      try {
+        const tx = await program.methods
+          .merchantCashOut(pointsAmount)
+          .accounts(await cashOutAccounts())
+          .signers([merchant])
+          .rpc();
+
+        console.log("Merchant cash out tx:", tx);
+      } finally {
+        await program.methods
+          .setBuybackConfig(cashOutConfig(new anchor.BN(0), 0))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+
+      expect(
+        pointsBefore -
+          Number((await getAccount(provider.connection, merchantATA)).amount)
+      ).to.equal(pointsAmount.toNumber());
+      expect(
+        (await provider.connection.getBalance(merchant.publicKey)) - solBefore
+      ).to.equal(expectedPayout);
+
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(merchantRecord.totalCashedOut.toNumber()).to.equal(expectedPayout);
+      expect(merchantRecord.pointsCashedOut.toNumber()).to.equal(
+        pointsAmount.toNumber()
+      );
+    });
+
+    it("fails to cash out self-minted points beyond what was redeemed", async () => {
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      const overAllowance = merchantRecord.totalRedeemed
+        .sub(merchantRecord.pointsCashedOut)
+        .add(new anchor.BN(1));
+
+      // Top the merchant up with deposited points so the balance isn't what stops it
+      await topUpMerchantPoints(overAllowance);
+
+      await program.methods
+        .setBuybackConfig(cashOutConfig(new anchor.BN(200), 500))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await program.methods
+          .merchantCashOut(overAllowance)
+          .accounts(await cashOutAccounts())
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("CashOutExceedsRedeemed");
+      } finally {
+        await program.methods
+          .setBuybackConfig(cashOutConfig(new anchor.BN(0), 0))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+    });
+
+    it("fails to refund a points purchase whose redeemed points were cashed out", async () => {
+      // A colluding customer pays with points the merchant minted to it
+      const colluder = Keypair.generate();
+      const airdrop = await provider.connection.requestAirdrop(
+        colluder.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+      const colluderATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        colluder.publicKey
+      );
+      await program.methods
+        .mintPoints(PRODUCT_PRICE_POINTS, "CASH-OUT-LOOP")
+        .accounts({
+          authority: merchant.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: colluder.publicKey,
+          consumerTokenAccount: colluderATA,
+          consumerProfile: consumerProfilePDA(colluder.publicKey),
+          pointsLot: await nextLotPDA(colluder.publicKey),
+          campaign: null,
+          merchantDelegate: null,
+          mintReceipt: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const record = await nextPurchaseRecordPDA(colluder.publicKey);
+      await program.methods
+        .purchaseProductWithPoints(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_POINTS)
+        .accounts({
+          customer: colluder.publicKey,
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: record,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: colluderATA,
+          consumerProfile: consumerProfilePDA(colluder.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(await openLotAccounts(colluder.publicKey))
+        .signers([colluder])
+        .rpc();
+
+      // The merchant cashes out enough that the redeemed points are no longer covered
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      const cashOut = merchantRecord.totalRedeemed
+        .sub(merchantRecord.pointsCashedOut)
+        .sub(PRODUCT_PRICE_POINTS)
+        .add(new anchor.BN(1));
+      await topUpMerchantPoints(cashOut);
+      await program.methods
+        .setBuybackConfig(cashOutConfig(new anchor.BN(200), 500))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+      try {
+        await program.methods
+          .merchantCashOut(cashOut)
+          .accounts(await cashOutAccounts())
+          .signers([merchant])
+          .rpc();
+      } finally {
+        await program.methods
+          .setBuybackConfig(cashOutConfig(new anchor.BN(0), 0))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+
+      try {
+        await program.methods
+          .refundPurchase()
+          .accounts({
+            merchant: merchant.publicKey,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            customer: colluder.publicKey,
+            purchaseRecord: record,
+            consumerProfile: consumerProfilePDA(colluder.publicKey),
+            campaign: null,
+            tokenMint: tokenMintPDA,
+            customerTokenAccount: colluderATA,
+            merchantPaymentAccount: null,
+            customerPaymentAccount: null,
+            paymentTokenMint: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            paymentTokenProgram: null,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("RefundedPointsCashedOut");
+      }
+    });
+
+    it("fails with a buyback rate paying more per point than minting costs", async () => {
+      const platformState = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      try {
+        await program.methods
+          .setBuybackConfig(
+            cashOutConfig(platformState.solToPointsRatio.div(new anchor.BN(2)), 0)
+          )
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("BuybackAboveMintPrice");
+      }
+    });
+
+    it("fails when cash-out is disabled", async () => {
+      try {
+        await program.methods
+          .merchantCashOut(new anchor.BN(1 * 10 ** TOKEN_DECIMALS))
+          .accounts(await cashOutAccounts())
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("CashOutDisabled");
+      }
+    });
+  });
+
+  describe("set_merchant_fee_override", () => {
+    const mintWithFee = async (reference: string) => {
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const treasuryBefore = await provider.connection.getBalance(treasuryPDA);
+
+      await program.methods
+        .mintPoints(MINT_AMOUNT, reference)
+        .accounts({
+          authority: merchant.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: consumer.publicKey,
+          consumerTokenAccount: consumerATA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: null,
+          merchantDelegate: null,
+          mintReceipt: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      return (await provider.connection.getBalance(treasuryPDA)) - treasuryBefore;
+    };
+
+    const setOverride = (feeOverride: any) =>
+      program.methods
+        .setMerchantFeeOverride(feeOverride)
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          merchantWallet: merchant.publicKey,
+          merchantRecord: merchantRecordPDA,
+        })
+        .rpc();
+
+    it("charges the merchant's negotiated schedule instead of the platform one", async () => {
+      const feeOverride = {
+        baseFee: new anchor.BN(1_000),
+        ratePerThousand: new anchor.BN(0),
+        rateBps: 0,
+      };
+      const tx = await setOverride(feeOverride);
+      console.log("Set merchant fee override tx:", tx);
+
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(merchantRecord.feeOverride.baseFee.toNumber()).to.equal(1_000);
+
+      try {
+        expect(await mintWithFee("FEE-OVERRIDE-001")).to.equal(1_000);
+      } finally {
+        await setOverride(null);
+      }
+
+      const cleared = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(cleared.feeOverride).to.be.null;
+    });
+
+    it("fails with a basis point rate above 100%", async () => {
+      try {
+        await setOverride({
+          baseFee: new anchor.BN(0),
+          ratePerThousand: new anchor.BN(0),
+          rateBps: 10_001,
+        });
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidBasisPoints");
+      }
+    });
+
+    it("fails when non-admin sets an override", async () => {
+      try {
+        await program.methods
+          .setMerchantFeeOverride(null)
+          .accounts({
+            admin: merchant.publicKey,
+            platformState: platformStatePDA,
+            merchantWallet: merchant.publicKey,
+            merchantRecord: merchantRecordPDA,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("UnauthorizedAdmin");
+      }
+    });
+  });
+
+  describe("spl_payments", () => {
+    const buyer = Keypair.generate();
+    const POINTS_PER_TOKEN = new anchor.BN(100);
+    const USDC_DECIMALS = 6;
+    let usdcMint: PublicKey;
+    let paymentMintPDA: PublicKey;
+    let buyerUsdc: PublicKey;
+    let merchantUsdc: PublicKey;
+    let treasuryUsdc: PublicKey;
+    let buyerATA: PublicKey;
+    let purchaseRecordPDA: PublicKey;
+
+    const tokenBalance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    // Merchant's price PDA for a product in the payment mint (passed even if never set)
+    const productTokenPricePDA = (product: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("product_token_price"), product.toBuffer(), usdcMint.toBuffer()],
+        program.programId
+      )[0];
+
+    const setPaymentMint = (isEnabled: boolean) =>
+      program.methods
+        .setPaymentMint(POINTS_PER_TOKEN, isEnabled)
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          protocolTreasury: treasuryPDA,
+          paymentTokenMint: usdcMint,
+          paymentMint: paymentMintPDA,
+          treasuryPaymentAccount: treasuryUsdc,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const depositAccounts = async () => ({
+      merchant: merchant.publicKey,
+      protocolTreasury: treasuryPDA,
+      platformState: platformStatePDA,
+      merchantRecord: merchantRecordPDA,
+      paymentMint: paymentMintPDA,
+      paymentTokenMint: usdcMint,
+      merchantPaymentAccount: merchantUsdc,
+      treasuryPaymentAccount: treasuryUsdc,
+      tokenMint: tokenMintPDA,
+      merchantTokenAccount: await getAssociatedTokenAddress(
+        tokenMintPDA,
+        merchant.publicKey
+      ),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      paymentTokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    before(async () => {
+      const airdrop = await provider.connection.requestAirdrop(
+        buyer.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+
+      // Locally created stand-in for a stablecoin
+      const payer = (provider.wallet as anchor.Wallet).payer;
+      usdcMint = await createMint(
+        provider.connection,
+        payer,
+        admin.publicKey,
+        null,
+        USDC_DECIMALS
+      );
+      [paymentMintPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("payment_mint"), usdcMint.toBuffer()],
+        program.programId
+      );
+      treasuryUsdc = await getAssociatedTokenAddress(usdcMint, treasuryPDA, true);
+
+      buyerUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        payer,
+        usdcMint,
+        buyer.publicKey
+      );
+      merchantUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        payer,
+        usdcMint,
+        merchant.publicKey
+      );
+      await mintSplTokens(
+        provider.connection,
+        payer,
+        usdcMint,
+        buyerUsdc,
+        payer,
+        1_000 * 10 ** USDC_DECIMALS
+      );
+      await mintSplTokens(
+        provider.connection,
+        payer,
+        usdcMint,
+        merchantUsdc,
+        payer,
+        100 * 10 ** USDC_DECIMALS
+      );
+
+      buyerATA = await createAssociatedTokenAccount(
+        provider.connection,
+        buyer,
+        tokenMintPDA,
+        buyer.publicKey
+      );
+      purchaseRecordPDA = await nextPurchaseRecordPDA(buyer.publicKey);
+    });
+
+    it("admin allowlists a payment mint", async () => {
+      const tx = await setPaymentMint(true);
+      console.log("Set payment mint tx:", tx);
+
+      const paymentMint = await program.account.paymentMint.fetch(paymentMintPDA);
+      expect(paymentMint.mint.toString()).to.equal(usdcMint.toString());
+      expect(paymentMint.decimals).to.equal(USDC_DECIMALS);
+      expect(paymentMint.pointsPerToken.toNumber()).to.equal(100);
+      expect(paymentMint.isEnabled).to.be.true;
+    });
+
+    it("purchases a product with the payment mint and pays the fee in it", async () => {
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const product = await program.account.product.fetch(productPDA);
+      // Points price at 100 points per token, in 6-decimal token units
+      const expectedPrice = Math.ceil(
+        (product.pricePoints.toNumber() * 10 ** USDC_DECIMALS) /
+          (100 * 10 ** TOKEN_DECIMALS)
+      );
+
+      const buyerUsdcBefore = await tokenBalance(buyerUsdc);
+      const merchantUsdcBefore = await tokenBalance(merchantUsdc);
+
+      const tx = await program.methods
+        .purchaseProductWithToken(
+          Array.from(PRODUCT_ID_HASH),
+          new anchor.BN(expectedPrice)
+        )
+        .accounts({
+          customer: buyer.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          paymentMint: paymentMintPDA,
+          paymentTokenMint: usdcMint,
+          productTokenPrice: productTokenPricePDA(productPDA),
+          customerPaymentAccount: buyerUsdc,
+          merchantPaymentAccount: merchantUsdc,
+          treasuryPaymentAccount: treasuryUsdc,
+          purchaseRecord: purchaseRecordPDA,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: buyerATA,
+          consumerProfile: consumerProfilePDA(buyer.publicKey),
+          pointsLot: await nextLotPDA(buyer.publicKey),
+          campaign: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+
+      console.log("Purchase with token tx:", tx);
+
+      const paymentMint = await program.account.paymentMint.fetch(paymentMintPDA);
+      const fee = paymentMint.totalFeesCollected.toNumber();
+      expect(fee).to.be.greaterThan(0);
+      expect(await tokenBalance(treasuryUsdc)).to.equal(fee);
+      expect((await tokenBalance(merchantUsdc)) - merchantUsdcBefore).to.equal(
+        expectedPrice
+      );
+      expect(buyerUsdcBefore - (await tokenBalance(buyerUsdc))).to.equal(
+        expectedPrice + fee
+      );
+      expect(await tokenBalance(buyerATA)).to.equal(product.loyaltyPointsReward.toNumber());
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.paymentType).to.equal(3); // 3 = SPL token
+      expect(purchaseRecord.paymentMint.toString()).to.equal(usdcMint.toString());
+      expect(purchaseRecord.amountPaid.toNumber()).to.equal(expectedPrice);
+    });
+
+    it("merchant refunds the token purchase in the same mint", async () => {
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      const buyerUsdcBefore = await tokenBalance(buyerUsdc);
+      await approveExpiryBurns(buyer, purchaseRecord.pointsEarned);
+
+      await program.methods
+        .refundPurchase()
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          customer: buyer.publicKey,
+          purchaseRecord: purchaseRecordPDA,
+          consumerProfile: consumerProfilePDA(buyer.publicKey),
+          campaign: null,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: buyerATA,
+          merchantPaymentAccount: merchantUsdc,
+          customerPaymentAccount: buyerUsdc,
+          paymentTokenMint: usdcMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(await openLotAccounts(buyer.publicKey))
+        .signers([merchant])
+        .rpc();
+
+      expect((await tokenBalance(buyerUsdc)) - buyerUsdcBefore).to.equal(
+        purchaseRecord.amountPaid.toNumber()
+      );
+      expect(await tokenBalance(buyerATA)).to.equal(0);
+    });
+
+    it("charges the merchant's own price for the payment mint when set", async () => {
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      const setPrice = (price: anchor.BN) =>
+        program.methods
+          .setProductTokenPrice(price)
+          .accounts({
+            merchant: merchant.publicKey,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            product: productPDA,
+            paymentMint: paymentMintPDA,
+            paymentTokenMint: usdcMint,
+            productTokenPrice: productTokenPricePDA(productPDA),
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+      const purchase = async (
+        record: PublicKey,
+        productTokenPrice: PublicKey,
+        maxPrice: anchor.BN
+      ) =>
+        program.methods
+          .purchaseProductWithToken(Array.from(PRODUCT_ID_HASH), maxPrice)
+          .accounts({
+            customer: buyer.publicKey,
+            merchant: merchant.publicKey,
+            protocolTreasury: treasuryPDA,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            product: productPDA,
+            paymentMint: paymentMintPDA,
+            paymentTokenMint: usdcMint,
+            productTokenPrice,
+            customerPaymentAccount: buyerUsdc,
+            merchantPaymentAccount: merchantUsdc,
+            treasuryPaymentAccount: treasuryUsdc,
+            purchaseRecord: record,
+            tokenMint: tokenMintPDA,
+            customerTokenAccount: buyerATA,
+            consumerProfile: consumerProfilePDA(buyer.publicKey),
+            pointsLot: await nextLotPDA(buyer.publicKey),
+            campaign: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            paymentTokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+
+      // 3 tokens, regardless of the points price conversion
+      const price = new anchor.BN(3 * 10 ** USDC_DECIMALS);
+      await setPrice(price);
+      const merchantUsdcBefore = await tokenBalance(merchantUsdc);
+      const record = await nextPurchaseRecordPDA(buyer.publicKey);
+
+      try {
+        // Leaving the price account out can't fall back to the points conversion
+        try {
+          await purchase(record, Keypair.generate().publicKey, price);
+          assert.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.error.errorCode.code).to.equal("ConstraintSeeds");
+        }
+
+        // A buyer who expects the 2-token conversion price won't pay 3
+        try {
+          await purchase(
+            record,
+            productTokenPricePDA(productPDA),
+            new anchor.BN(2 * 10 ** USDC_DECIMALS)
+          );
+          assert.fail("Should have thrown an error");
+        } catch (error: any) {
+          expect(error.error.errorCode.code).to.equal("PriceAboveMax");
+        }
+
+        await purchase(record, productTokenPricePDA(productPDA), price);
+      } finally {
+        await setPrice(new anchor.BN(0));
+      }
+
+      expect((await tokenBalance(merchantUsdc)) - merchantUsdcBefore).to.equal(
+        price.toNumber()
+      );
+      const purchaseRecord = await program.account.purchaseRecord.fetch(record);
+      expect(purchaseRecord.amountPaid.toNumber()).to.equal(price.toNumber());
+    });
+
+    it("merchant deposits the payment mint for points", async () => {
+      const depositAmount = new anchor.BN(1 * 10 ** USDC_DECIMALS);
+      const accounts = await depositAccounts();
+      const pointsBefore = await tokenBalance(accounts.merchantTokenAccount);
+      const treasuryBefore = await tokenBalance(treasuryUsdc);
+
+      const tx = await program.methods
+        .depositToken(depositAmount)
+        .accounts(accounts)
+        .signers([merchant])
+        .rpc();
+
+      console.log("Deposit token tx:", tx);
+
+      // 1 token at 100 points per token
+      expect(
+        (await tokenBalance(accounts.merchantTokenAccount)) - pointsBefore
+      ).to.equal(100 * 10 ** TOKEN_DECIMALS);
+      expect((await tokenBalance(treasuryUsdc)) - treasuryBefore).to.equal(
+        depositAmount.toNumber()
+      );
+
+      const paymentMint = await program.account.paymentMint.fetch(paymentMintPDA);
+      expect(paymentMint.totalDeposited.toNumber()).to.equal(
+        depositAmount.toNumber()
+      );
+    });
+
+    it("admin withdraws payment tokens from the treasury", async () => {
+      const adminUsdc = await createAssociatedTokenAccount(
+        provider.connection,
+        (provider.wallet as anchor.Wallet).payer,
+        usdcMint,
+        admin.publicKey
+      );
+      const treasuryBalance = await tokenBalance(treasuryUsdc);
+
+      await program.methods
+        .withdrawTreasuryTokens(new anchor.BN(treasuryBalance))
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          protocolTreasury: treasuryPDA,
+          paymentTokenMint: usdcMint,
+          treasuryPaymentAccount: treasuryUsdc,
+          destination: adminUsdc,
+          paymentTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      expect(await tokenBalance(treasuryUsdc)).to.equal(0);
+      expect(await tokenBalance(adminUsdc)).to.equal(treasuryBalance);
+    });
+
+    it("fails to deposit a disabled payment mint", async () => {
+      await setPaymentMint(false);
+
+      try {
+        await program.methods
+          .depositToken(new anchor.BN(1 * 10 ** USDC_DECIMALS))
+          .accounts(await depositAccounts())
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PaymentMintDisabled");
+      }
+    });
+  });
+
+  describe("gift_points", () => {
+    const PAUSE_GIFT = 1 << 5;
+    const recipient = Keypair.generate();
+    const giftConfig = (
+      giftFee: anchor.BN | null,
+      giftDailyCap: anchor.BN | null,
+      giftMinAccountAge: anchor.BN | null
+    ) => ({
+      giftFee,
+      giftDailyCap,
+      giftMinAccountAge,
+      allowNonTransferableGifts: null,
+    });
+    const giftAccounts = async () => ({
+      sender: consumer.publicKey,
+      recipient: recipient.publicKey,
+      platformState: platformStatePDA,
+      protocolTreasury: treasuryPDA,
+      tokenMint: tokenMintPDA,
+      senderTokenAccount: await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      ),
+      recipientTokenAccount: await getAssociatedTokenAddress(
+        tokenMintPDA,
+        recipient.publicKey
+      ),
+      senderProfile: consumerProfilePDA(consumer.publicKey),
+      recipientProfile: consumerProfilePDA(recipient.publicKey),
+      recipientLot: await nextLotPDA(recipient.publicKey),
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+    const gift = async (amount: anchor.BN) =>
+      program.methods
+        .giftPoints(amount)
+        .accounts(await giftAccounts())
+        .remainingAccounts(await openLotAccounts(consumer.publicKey))
+        .signers([consumer])
+        .rpc();
+
+    const giftFee = new anchor.BN(0.001 * anchor.web3.LAMPORTS_PER_SOL);
+    const dailyCap = new anchor.BN(2 * 10 ** TOKEN_DECIMALS);
+
+    before(async () => {
+      await program.methods
+        .setGiftConfig(giftConfig(giftFee, dailyCap, new anchor.BN(0)))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+    });
+
+    after(async () => {
+      await program.methods
+        .setGiftConfig(
+          giftConfig(new anchor.BN(0), new anchor.BN(0), new anchor.BN(0))
+        )
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+    });
+
+    it("gifts points and charges the gift fee", async () => {
+      const amount = new anchor.BN(1 * 10 ** TOKEN_DECIMALS);
+      const treasuryBefore = await provider.connection.getBalance(treasuryPDA);
+      const recipientLot = await nextLotPDA(recipient.publicKey);
+
+      // The gift is drawn from the sender's head lot, so it inherits that expiry
+      const senderProfile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      const headLot = await program.account.pointsLot.fetch(
+        lotPDA(consumer.publicKey, senderProfile.headLotIndex)
+      );
+
+      const tx = await gift(amount);
+      console.log("Gift points tx:", tx);
+
+      const recipientATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        recipient.publicKey
+      );
+      expect(
+        Number((await getAccount(provider.connection, recipientATA)).amount)
+      ).to.equal(amount.toNumber());
+      expect(
+        (await provider.connection.getBalance(treasuryPDA)) - treasuryBefore
+      ).to.equal(giftFee.toNumber());
+
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      expect(profile.giftedToday.toNumber()).to.equal(amount.toNumber());
+
+      const lot = await program.account.pointsLot.fetch(recipientLot);
+      expect(lot.remaining.toNumber()).to.equal(amount.toNumber());
+      expect(lot.expiresAt.toNumber()).to.equal(headLot.expiresAt.toNumber());
+      const recipientProfile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(recipient.publicKey)
+      );
+      expect(recipientProfile.lotBalance.toNumber()).to.equal(amount.toNumber());
+    });
+
+    it("fails to gift beyond the daily cap", async () => {
+      try {
+        await gift(dailyCap);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("GiftDailyCapExceeded");
+      }
+    });
+
+    it("fails when the sender's account is too new", async () => {
+      await program.methods
+        .setGiftConfig(giftConfig(null, null, new anchor.BN(365 * 86400)))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await gift(new anchor.BN(1));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("GiftAccountTooNew");
+      } finally {
+        await program.methods
+          .setGiftConfig(giftConfig(null, null, new anchor.BN(0)))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+    });
+
+    it("fails when gifting is disabled", async () => {
+      await program.methods
+        .setPausedOperations(PAUSE_GIFT, 0)
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        await gift(new anchor.BN(1));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("OperationPaused");
+      } finally {
+        await program.methods
+          .setPausedOperations(0, 0)
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+    });
+  });
+
+  describe("batch_mint_points", () => {
+    const batchAccounts = {
+      merchant: merchant.publicKey,
+      protocolTreasury: treasuryPDA,
+      platformState: platformStatePDA,
+      merchantRecord: merchantRecordPDA,
+      tokenMint: tokenMintPDA,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+    const tokenAccountMeta = async (wallet: PublicKey) => ({
+      pubkey: await getAssociatedTokenAddress(tokenMintPDA, wallet),
+      isSigner: false,
+      isWritable: true,
+    });
+    // Token account, profile and next lot for one entry
+    const entryAccounts = async (wallet: PublicKey) => [
+      await tokenAccountMeta(wallet),
+      { pubkey: consumerProfilePDA(wallet), isSigner: false, isWritable: true },
+      { pubkey: await nextLotPDA(wallet), isSigner: false, isWritable: true },
+    ];
+    // Entry amount after the wallet's current tier multiplier
+    const boosted = async (wallet: PublicKey, amount: anchor.BN) => {
+      const { tierConfig } = await program.account.platformState.fetch(
+        platformStatePDA
+      );
+      const profile = await program.account.consumerProfile.fetchNullable(
+        consumerProfilePDA(wallet)
+      );
+      const multiplier = tierConfig.multipliersBps[profile ? profile.tier : 0];
+      return amount.mul(new anchor.BN(multiplier)).div(new anchor.BN(10_000));
+    };
+
+    it("credits several consumers for one aggregated fee", async () => {
+      const second = Keypair.generate();
+      await createAssociatedTokenAccount(
+        provider.connection,
+        (provider.wallet as anchor.Wallet).payer,
+        tokenMintPDA,
+        second.publicKey
+      );
+
+      const entries = [
+        {
+          consumer: consumer.publicKey,
+          amount: new anchor.BN(3 * 10 ** TOKEN_DECIMALS),
+          purchaseReference: "BATCH-001",
+        },
+        {
+          consumer: second.publicKey,
+          amount: new anchor.BN(5 * 10 ** TOKEN_DECIMALS),
+          purchaseReference: "BATCH-002",
+        },
+      ];
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const consumerBefore = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+      const recordBefore = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      const consumerAmount = await boosted(consumer.publicKey, entries[0].amount);
+      const secondAmount = await boosted(second.publicKey, entries[1].amount);
+      const consumerLot = await nextLotPDA(consumer.publicKey);
+      const secondLot = await nextLotPDA(second.publicKey);
+
+      const tx = await program.methods
+        .batchMintPoints(entries)
+        .accounts(batchAccounts)
+        .remainingAccounts([
+          ...(await entryAccounts(consumer.publicKey)),
+          ...(await entryAccounts(second.publicKey)),
+        ])
+        .signers([merchant])
+        .rpc();
+
+      console.log("Batch mint points tx:", tx);
+
+      expect(
+        Number((await getAccount(provider.connection, consumerATA)).amount) -
+          consumerBefore
+      ).to.equal(consumerAmount.toNumber());
+      expect(
+        Number(
+          (
+            await getAccount(
+              provider.connection,
+              await getAssociatedTokenAddress(tokenMintPDA, second.publicKey)
+            )
+          ).amount
+        )
+      ).to.equal(secondAmount.toNumber());
+
+      // Each entry is lot-backed; the second consumer's profile was created by the batch
+      expect(
+        (await program.account.pointsLot.fetch(consumerLot)).amount.toNumber()
+      ).to.equal(consumerAmount.toNumber());
+      expect(
+        (await program.account.pointsLot.fetch(secondLot)).amount.toNumber()
+      ).to.equal(secondAmount.toNumber());
+      const secondProfile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(second.publicKey)
+      );
+      expect(secondProfile.lotBalance.toNumber()).to.equal(secondAmount.toNumber());
+      expect(secondProfile.lifetimeEarned.toNumber()).to.equal(
+        secondAmount.toNumber()
+      );
+
+      const recordAfter = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(
+        recordAfter.totalMinted.sub(recordBefore.totalMinted).toNumber()
+      ).to.equal(consumerAmount.add(secondAmount).toNumber());
+    });
+
+    it("fails when token accounts don't match the entries", async () => {
+      try {
+        await program.methods
+          .batchMintPoints([
+            {
+              consumer: consumer.publicKey,
+              amount: new anchor.BN(1 * 10 ** TOKEN_DECIMALS),
+              purchaseReference: "BATCH-003",
+            },
+          ])
+          .accounts(batchAccounts)
+          .remainingAccounts([
+            await tokenAccountMeta(merchant.publicKey),
+            ...(await entryAccounts(consumer.publicKey)).slice(1),
+          ])
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidBatchAccounts");
+      }
+    });
+
+    it("fails when a consumer appears twice in one batch", async () => {
+      const entry = {
+        consumer: consumer.publicKey,
+        amount: new anchor.BN(1 * 10 ** TOKEN_DECIMALS),
+        purchaseReference: "BATCH-004",
+      };
+      const accounts = await entryAccounts(consumer.publicKey);
+      try {
+        await program.methods
+          .batchMintPoints([entry, { ...entry, purchaseReference: "BATCH-005" }])
+          .accounts(batchAccounts)
+          .remainingAccounts([...accounts, ...accounts])
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidBatchAccounts");
+      }
+    });
+
+    it("fails with an empty batch", async () => {
+      try {
+        await program.methods
+          .batchMintPoints([])
+          .accounts(batchAccounts)
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidBatchSize");
+      }
+    });
+  });
+
+  describe("merchant_delegates", () => {
+    const cashier = Keypair.generate();
+    const PER_TX_CAP = new anchor.BN(2000 * 10 ** TOKEN_DECIMALS);
+    const merchantDelegatePDA = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("merchant_delegate"),
+        merchant.publicKey.toBuffer(),
+        cashier.publicKey.toBuffer(),
+      ],
+      program.programId
+    )[0];
+    const delegateMint = async (
+      amount: anchor.BN,
+      reference: string,
+      to: PublicKey = consumer.publicKey
+    ) =>
+      program.methods
+        .mintPoints(amount, reference)
+        .accounts({
+          authority: cashier.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: to,
+          consumerTokenAccount: await getAssociatedTokenAddress(tokenMintPDA, to),
+          consumerProfile: consumerProfilePDA(to),
+          pointsLot: await nextLotPDA(to),
+          campaign: null,
+          merchantDelegate: merchantDelegatePDA,
+          mintReceipt: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([cashier])
+        .rpc();
+
+    before(async () => {
+      const airdrop = await provider.connection.requestAirdrop(
+        cashier.publicKey,
+        anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    });
+
+    it("creates a delegate with caps and a fee deposit", async () => {
+      const tx = await program.methods
+        .createMerchantDelegate(
+          PER_TX_CAP,
+          new anchor.BN(5000 * 10 ** TOKEN_DECIMALS),
+          new anchor.BN(0),
+          new anchor.BN(0.1 * anchor.web3.LAMPORTS_PER_SOL)
+        )
+        .accounts({
+          merchant: merchant.publicKey,
+          merchantRecord: merchantRecordPDA,
+          delegate: cashier.publicKey,
+          merchantDelegate: merchantDelegatePDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      console.log("Create merchant delegate tx:", tx);
+
+      const merchantDelegate = await program.account.merchantDelegate.fetch(
+        merchantDelegatePDA
+      );
+      expect(merchantDelegate.delegate.toString()).to.equal(
+        cashier.publicKey.toString()
+      );
+      expect(merchantDelegate.perTxCap.toNumber()).to.equal(
+        PER_TX_CAP.toNumber()
+      );
+    });
+
+    it("mints as the delegate with the fee paid from the deposit", async () => {
+      const depositBefore = await provider.connection.getBalance(
+        merchantDelegatePDA
+      );
+      const recordBefore = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      const lot = await nextLotPDA(consumer.publicKey);
+
+      await delegateMint(MINT_AMOUNT, "POS-001");
+
+      const recordAfter = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      const feePaid = recordAfter.totalFeesPaid
+        .sub(recordBefore.totalFeesPaid)
+        .toNumber();
+      expect(feePaid).to.be.greaterThan(0);
+      // The deposit also pays back the new lot's rent the cashier fronted
+      const lotRent = (await provider.connection.getAccountInfo(lot)).lamports;
+      expect(
+        depositBefore -
+          (await provider.connection.getBalance(merchantDelegatePDA))
+      ).to.equal(feePaid + lotRent);
+
+      const merchantDelegate = await program.account.merchantDelegate.fetch(
+        merchantDelegatePDA
+      );
+      expect(merchantDelegate.mintedToday.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("reimburses the delegate for every account it creates", async () => {
+      const newcomer = Keypair.generate().publicKey;
+      const cashierBefore = await provider.connection.getBalance(
+        cashier.publicKey
+      );
+      const depositBefore = await provider.connection.getBalance(
+        merchantDelegatePDA
+      );
+      const feesBefore = (
+        await program.account.merchantRecord.fetch(merchantRecordPDA)
+      ).totalFeesPaid;
+
+      await delegateMint(MINT_AMOUNT, "POS-NEW", newcomer);
+
+      // The deposit pays the fee plus the rent of the token account, profile
+      // and lot the cashier created, leaving it only the transaction fee
+      const feePaid = (
+        await program.account.merchantRecord.fetch(merchantRecordPDA)
+      ).totalFeesPaid
+        .sub(feesBefore)
+        .toNumber();
+      const rent = await Promise.all(
+        [
+          await getAssociatedTokenAddress(tokenMintPDA, newcomer),
+          consumerProfilePDA(newcomer),
+          lotPDA(newcomer, new anchor.BN(0)),
+        ].map(
+          async (account) =>
+            (await provider.connection.getAccountInfo(account)).lamports
+        )
+      );
+      expect(
+        depositBefore -
+          (await provider.connection.getBalance(merchantDelegatePDA))
+      ).to.equal(feePaid + rent.reduce((sum, lamports) => sum + lamports, 0));
+      expect(
+        cashierBefore - (await provider.connection.getBalance(cashier.publicKey))
+      ).to.equal(5000);
+    });
+
+    it("caps the requested amount, not the tier-boosted one", async () => {
+      const tierAccounts = {
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+      };
+      const consumerATA = await getAssociatedTokenAddress(
+        tokenMintPDA,
+        consumer.publicKey
+      );
+      const before = await program.account.merchantDelegate.fetch(
+        merchantDelegatePDA
+      );
+      const balanceBefore = Number(
+        (await getAccount(provider.connection, consumerATA)).amount
+      );
+
+      // Every tier earns 1.5x, so the cap-sized request mints above the cap
+      await program.methods
+        .setTierConfig({
+          silverThreshold: new anchor.BN(0),
+          goldThreshold: new anchor.BN(0),
+          multipliersBps: [15_000, 15_000, 15_000],
+        })
+        .accounts(tierAccounts)
+        .rpc();
+      try {
+        await delegateMint(PER_TX_CAP, "POS-BOOSTED");
+      } finally {
+        await program.methods
+          .setTierConfig({
+            silverThreshold: new anchor.BN(0),
+            goldThreshold: new anchor.BN(0),
+            multipliersBps: [10_000, 10_000, 10_000],
+          })
+          .accounts(tierAccounts)
+          .rpc();
+      }
+
+      expect(
+        Number((await getAccount(provider.connection, consumerATA)).amount) -
+          balanceBefore
+      ).to.equal(PER_TX_CAP.muln(3).divn(2).toNumber());
+      const after = await program.account.merchantDelegate.fetch(
+        merchantDelegatePDA
+      );
+      expect(after.mintedToday.sub(before.mintedToday).toNumber()).to.equal(
+        PER_TX_CAP.toNumber()
+      );
+    });
+
+    it("fails to mint above the per-transaction cap", async () => {
+      try {
+        await delegateMint(PER_TX_CAP.addn(1), "POS-002");
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("DelegateTxCapExceeded");
+      }
+    });
+
+    it("tops up the delegate's fee deposit", async () => {
+      const topUp = 0.05 * anchor.web3.LAMPORTS_PER_SOL;
+      const depositBefore = await provider.connection.getBalance(
+        merchantDelegatePDA
+      );
+
+      const tx = await program.methods
+        .fundMerchantDelegate(new anchor.BN(topUp))
+        .accounts({
+          merchant: merchant.publicKey,
+          merchantRecord: merchantRecordPDA,
+          delegate: cashier.publicKey,
+          merchantDelegate: merchantDelegatePDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+      console.log("Fund merchant delegate tx:", tx);
+
+      expect(
+        (await provider.connection.getBalance(merchantDelegatePDA)) -
+          depositBefore
+      ).to.equal(topUp);
+    });
+
+    it("fails to mint once revoked", async () => {
+      await program.methods
+        .revokeMerchantDelegate()
+        .accounts({
+          merchant: merchant.publicKey,
+          delegate: cashier.publicKey,
+          merchantDelegate: merchantDelegatePDA,
+        })
+        .signers([merchant])
+        .rpc();
+
+      try {
+        await delegateMint(MINT_AMOUNT, "POS-003");
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("AccountNotInitialized");
+      }
+    });
+  });
+
+  describe("mint_receipts", () => {
+    const REFERENCE = "RECEIPT-001";
+    const mintReceiptPDA = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("mint_receipt"),
+        merchant.publicKey.toBuffer(),
+        createHash("sha256").update(REFERENCE).digest(),
+      ],
+      program.programId
+    )[0];
+    const mintWithReceipt = async () =>
+      program.methods
+        .mintPoints(MINT_AMOUNT, REFERENCE)
+        .accounts({
+          authority: merchant.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          tokenMint: tokenMintPDA,
+          consumer: consumer.publicKey,
+          consumerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            consumer.publicKey
+          ),
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: null,
+          merchantDelegate: null,
+          mintReceipt: mintReceiptPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([merchant])
+        .rpc();
+
+    it("records a receipt for the purchase reference", async () => {
+      const tx = await mintWithReceipt();
+      console.log("Mint points with receipt tx:", tx);
+
+      const receipt = await program.account.mintReceipt.fetch(mintReceiptPDA);
+      expect(receipt.consumer.toString()).to.equal(
+        consumer.publicKey.toString()
+      );
+      expect(receipt.amount.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("fails to mint the same reference twice", async () => {
+      try {
+        await mintWithReceipt();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal(
+          "DuplicatePurchaseReference"
+        );
+      }
+    });
+
+    it("closes the receipt and returns the rent", async () => {
+      const merchantBefore = await provider.connection.getBalance(
+        merchant.publicKey
+      );
+
+      await program.methods
+        .closeMintReceipt()
+        .accounts({
+          merchant: merchant.publicKey,
+          mintReceipt: mintReceiptPDA,
+        })
+        .signers([merchant])
+        .rpc();
+
+      expect(
+        await program.account.mintReceipt.fetchNullable(mintReceiptPDA)
+      ).to.be.null;
+      expect(
+        await provider.connection.getBalance(merchant.publicKey)
+      ).to.be.greaterThan(merchantBefore);
+    });
+
+    it("still records a receipt when the PDA was pre-funded", async () => {
+      // Anyone can send lamports to the derivable address ahead of the mint
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          SystemProgram.transfer({
+            fromPubkey: admin.publicKey,
+            toPubkey: mintReceiptPDA,
+            lamports: 1_000_000,
+          })
+        )
+      );
+
+      await mintWithReceipt();
+
+      const receipt = await program.account.mintReceipt.fetch(mintReceiptPDA);
+      expect(receipt.consumer.toString()).to.equal(
+        consumer.publicKey.toString()
+      );
+    });
+  });
+
+  describe("close_purchase_record", () => {
+    const retentionConfig = (seconds: number) => ({
+      purchaseRetentionSeconds: new anchor.BN(seconds),
+      disputeWindowSeconds: null,
+    });
+    const purchase = async () => {
+      const purchaseRecordPDA = await nextPurchaseRecordPDA(consumer.publicKey);
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      await program.methods
+        .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+        .accounts({
+          customer: consumer.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: purchaseRecordPDA,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            consumer.publicKey
+          ),
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: null,
+          referralLink: referralLinkPDA(consumer.publicKey),
+          referrerTokenAccount: null,
+          referrerProfile: null,
+          referrerLot: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([consumer])
+        .rpc();
+      return purchaseRecordPDA;
+    };
+    const closeRecord = (purchaseRecord: PublicKey) =>
+      program.methods
+        .closePurchaseRecord()
+        .accounts({
+          customer: consumer.publicKey,
+          platformState: platformStatePDA,
+          purchaseRecord,
+        })
+        .signers([consumer])
+        .rpc();
+
+    it("fails to close a purchase that is neither final nor past retention", async () => {
+      const purchaseRecordPDA = await purchase();
+
+      try {
+        await closeRecord(purchaseRecordPDA);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PurchaseRecordRetained");
+      }
+    });
+
+    it("closes a record once the merchant marks it final", async () => {
+      const purchaseRecordPDA = await purchase();
+
+      await program.methods
+        .fulfillPurchase()
+        .accounts({
+          merchant: merchant.publicKey,
+          purchaseRecord: purchaseRecordPDA,
+        })
+        .signers([merchant])
+        .rpc();
+      // Disputes are disabled, so there is no window to wait out
+      await program.methods
+        .finalizePurchase()
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          purchaseRecord: purchaseRecordPDA,
+        })
+        .signers([merchant])
+        .rpc();
+
+      const customerBefore = await provider.connection.getBalance(
+        consumer.publicKey
+      );
+      const tx = await closeRecord(purchaseRecordPDA);
+      console.log("Close purchase record tx:", tx);
+
+      expect(
+        await program.account.purchaseRecord.fetchNullable(purchaseRecordPDA)
+      ).to.be.null;
+      expect(
+        await provider.connection.getBalance(consumer.publicKey)
+      ).to.be.greaterThan(customerBefore);
+    });
+
+    it("closes a record after the retention period", async () => {
+      await program.methods
+        .setRetentionConfig(retentionConfig(1))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      try {
+        const purchaseRecordPDA = await purchase();
+        await new Promise((resolve) => setTimeout(resolve, 2000));
+
+        await closeRecord(purchaseRecordPDA);
+        expect(
+          await program.account.purchaseRecord.fetchNullable(purchaseRecordPDA)
+        ).to.be.null;
+      } finally {
+        await program.methods
+          .setRetentionConfig(retentionConfig(0))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+    });
+  });
+
+  describe("order_lifecycle", () => {
+    const STATUS_FULFILLED = 1;
+    const STATUS_DISPUTED = 2;
+    const STATUS_REFUND_ORDERED = 3;
+    const STATUS_REFUNDED = 4;
+    const disputeConfig = (seconds: number) => ({
+      purchaseRetentionSeconds: null,
+      disputeWindowSeconds: new anchor.BN(seconds),
+    });
+    let purchaseRecordPDA: PublicKey;
+
+    const disputePurchase = (record: PublicKey = purchaseRecordPDA) =>
+      program.methods
+        .disputePurchase(1)
+        .accounts({
+          customer: consumer.publicKey,
+          platformState: platformStatePDA,
+          purchaseRecord: record,
+        })
+        .signers([consumer])
+        .rpc();
+    const fulfill = (record: PublicKey) =>
+      program.methods
+        .fulfillPurchase()
+        .accounts({
+          merchant: merchant.publicKey,
+          purchaseRecord: record,
+        })
+        .signers([merchant])
+        .rpc();
+    const finalize = (record: PublicKey) =>
+      program.methods
+        .finalizePurchase()
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          purchaseRecord: record,
+        })
+        .signers([merchant])
+        .rpc();
+
+    // SOL purchase by the consumer, returning its purchase record
+    const purchase = async () => {
+      const record = await nextPurchaseRecordPDA(consumer.publicKey);
+      const [productPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("product"), merchant.publicKey.toBuffer(), PRODUCT_ID_HASH],
+        program.programId
+      );
+      await program.methods
+        .purchaseProductWithSol(Array.from(PRODUCT_ID_HASH), PRODUCT_PRICE_SOL)
+        .accounts({
+          customer: consumer.publicKey,
+          merchant: merchant.publicKey,
+          protocolTreasury: treasuryPDA,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          product: productPDA,
+          purchaseRecord: record,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            consumer.publicKey
+          ),
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          pointsLot: await nextLotPDA(consumer.publicKey),
+          campaign: null,
+          referralLink: referralLinkPDA(consumer.publicKey),
+          referrerTokenAccount: null,
+          referrerProfile: null,
+          referrerLot: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([consumer])
+        .rpc();
+      return record;
+    };
+
+    before(async () => {
+      purchaseRecordPDA = await purchase();
+    });
+
+    after(async () => {
+      await program.methods
+        .setRetentionConfig(disputeConfig(0))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+    });
+
+    it("marks the purchase fulfilled", async () => {
+      const tx = await fulfill(purchaseRecordPDA);
+
+      console.log("Fulfill purchase tx:", tx);
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.status).to.equal(STATUS_FULFILLED);
+      expect(purchaseRecord.fulfilledAt.toNumber()).to.be.greaterThan(0);
+    });
+
+    it("fails to dispute while disputes are disabled", async () => {
+      try {
+        await disputePurchase();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("DisputeWindowClosed");
+      }
+    });
+
+    it("disputes within the window and blocks closing", async () => {
+      await program.methods
+        .setRetentionConfig(disputeConfig(3600))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      await disputePurchase();
+
+      const purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.status).to.equal(STATUS_DISPUTED);
+
+      try {
+        await program.methods
+          .closePurchaseRecord()
+          .accounts({
+            customer: consumer.publicKey,
+            platformState: platformStatePDA,
+            purchaseRecord: purchaseRecordPDA,
+          })
+          .signers([consumer])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PurchaseDisputed");
+      }
+    });
+
+    it("orders a refund that the merchant then settles", async () => {
+      await program.methods
+        .resolveDispute(true)
+        .accounts({
+          admin: admin.publicKey,
+          platformState: platformStatePDA,
+          purchaseRecord: purchaseRecordPDA,
+          merchantRecord: merchantRecordPDA,
+        })
+        .rpc();
+
+      let purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.status).to.equal(STATUS_REFUND_ORDERED);
+
+      // The merchant can't mint until the ordered refund is settled
+      try {
+        await program.methods
+          .mintPoints(MINT_AMOUNT, "BLOCKED-BY-REFUND")
+          .accounts({
+            authority: merchant.publicKey,
+            merchant: merchant.publicKey,
+            protocolTreasury: treasuryPDA,
+            platformState: platformStatePDA,
+            merchantRecord: merchantRecordPDA,
+            tokenMint: tokenMintPDA,
+            consumer: consumer.publicKey,
+            consumerTokenAccount: await getAssociatedTokenAddress(
+              tokenMintPDA,
+              consumer.publicKey
+            ),
+            consumerProfile: consumerProfilePDA(consumer.publicKey),
+            pointsLot: await nextLotPDA(consumer.publicKey),
+            campaign: null,
+            merchantDelegate: null,
+            mintReceipt: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([merchant])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("RefundOutstanding");
+      }
+
+      // ...nor keep selling
+      try {
+        await purchase();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("RefundOutstanding");
+      }
+
+      await approveExpiryBurns(consumer, purchaseRecord.pointsEarned);
+      await program.methods
+        .refundPurchase()
+        .accounts({
+          merchant: merchant.publicKey,
+          platformState: platformStatePDA,
+          merchantRecord: merchantRecordPDA,
+          customer: consumer.publicKey,
+          purchaseRecord: purchaseRecordPDA,
+          consumerProfile: consumerProfilePDA(consumer.publicKey),
+          campaign: null,
+          tokenMint: tokenMintPDA,
+          customerTokenAccount: await getAssociatedTokenAddress(
+            tokenMintPDA,
+            consumer.publicKey
+          ),
+          merchantPaymentAccount: null,
+          customerPaymentAccount: null,
+          paymentTokenMint: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          paymentTokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(await openLotAccounts(consumer.publicKey))
+        .signers([merchant])
+        .rpc();
+
+      purchaseRecord = await program.account.purchaseRecord.fetch(
+        purchaseRecordPDA
+      );
+      expect(purchaseRecord.status).to.equal(STATUS_REFUNDED);
+
+      const merchantRecord = await program.account.merchantRecord.fetch(
+        merchantRecordPDA
+      );
+      expect(merchantRecord.pendingRefunds).to.equal(0);
+    });
+
+    it("fails to finalize an unfulfilled order or one inside its dispute window", async () => {
+      const record = await purchase();
+
+      try {
+        await finalize(record);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("InvalidPurchaseStatus");
+      }
+
+      await fulfill(record);
+      try {
+        await finalize(record);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("DisputeWindowOpen");
+      }
+    });
+
+    it("fails to dispute a purchase the merchant marked final", async () => {
+      await program.methods
+        .setRetentionConfig(disputeConfig(1))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+      const record = await purchase();
+      await fulfill(record);
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+      await finalize(record);
+
+      try {
+        await disputePurchase(record);
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("PurchaseAlreadyFinal");
+      }
+    });
+  });
 });
@@ -0,0 +1,410 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  getAccount,
+  getMint,
+  getTokenMetadata,
+  transferChecked,
+} from "@solana/spl-token";
+import { assert, expect } from "chai";
+import { LoyaltyProgram } from "../../target/types/loyalty_program";
+
+// Runs on its own validator (see Test.toml): the platform is a singleton, so a
+// Token-2022 loyalty mint needs a fresh deployment.
+describe("loyalty_program (Token-2022 mint)", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.LoyaltyProgram as Program<LoyaltyProgram>;
+
+  const admin = provider.wallet;
+  const merchant = Keypair.generate();
+  const consumer = Keypair.generate();
+  const friend = Keypair.generate();
+
+  const TOKEN_DECIMALS = 6;
+  // NonTransferable | PermanentDelegate | MetadataPointer
+  const EXT_ALL = 0b111;
+  const MINT_AMOUNT = new anchor.BN(100 * 10 ** TOKEN_DECIMALS);
+
+  const [platformStatePDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("platform_state")],
+    program.programId
+  );
+  const [tokenMintPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("loyalty_mint")],
+    program.programId
+  );
+  const [treasuryPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("treasury")],
+    program.programId
+  );
+  const [merchantRecordPDA] = PublicKey.findProgramAddressSync(
+    [Buffer.from("merchant"), merchant.publicKey.toBuffer()],
+    program.programId
+  );
+
+  const consumerProfilePDA = (wallet: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("consumer"), wallet.toBuffer()],
+      program.programId
+    )[0];
+  const lotPDA = (wallet: PublicKey, index: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("lot"), wallet.toBuffer(), index.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+  const nextLotPDA = async (wallet: PublicKey) => {
+    const profile = await program.account.consumerProfile.fetchNullable(
+      consumerProfilePDA(wallet)
+    );
+    return lotPDA(wallet, profile ? profile.nextLotIndex : new anchor.BN(0));
+  };
+  const ata = (wallet: PublicKey) =>
+    getAssociatedTokenAddressSync(
+      tokenMintPDA,
+      wallet,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+  const balance = async (wallet: PublicKey) =>
+    Number(
+      (
+        await getAccount(
+          provider.connection,
+          ata(wallet),
+          undefined,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount
+    );
+
+  const mintPoints = async (wallet: PublicKey, reference: string) =>
+    program.methods
+      .mintPoints(MINT_AMOUNT, reference)
+      .accounts({
+        authority: merchant.publicKey,
+        merchant: merchant.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        merchantRecord: merchantRecordPDA,
+        tokenMint: tokenMintPDA,
+        consumer: wallet,
+        consumerTokenAccount: ata(wallet),
+        consumerProfile: consumerProfilePDA(wallet),
+        pointsLot: await nextLotPDA(wallet),
+        campaign: null,
+        merchantDelegate: null,
+        mintReceipt: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([merchant])
+      .rpc();
+
+  before(async () => {
+    for (const wallet of [merchant, consumer, friend]) {
+      const airdrop = await provider.connection.requestAirdrop(
+        wallet.publicKey,
+        5 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    }
+
+    await program.methods
+      .initializePlatform(
+        TOKEN_DECIMALS,
+        new anchor.BN(1_000_000_000 * 10 ** TOKEN_DECIMALS),
+        new anchor.BN(5000),
+        new anchor.BN(1000),
+        new anchor.BN(100),
+        EXT_ALL
+      )
+      .accounts({
+        admin: admin.publicKey,
+        protocolTreasury: treasuryPDA,
+        platformState: platformStatePDA,
+        tokenMint: tokenMintPDA,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .registerMerchant(new anchor.BN(100_000 * 10 ** TOKEN_DECIMALS))
+      .accounts({
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+        merchantWallet: merchant.publicKey,
+        merchantRecord: merchantRecordPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  });
+
+  it("stores the token metadata on the mint itself", async () => {
+    await program.methods
+      .setTokenMetadata("Loyalty Points", "LOYAL", "https://example.com/loyal.json")
+      .accounts({
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+        tokenMint: tokenMintPDA,
+        metadataAccount: null,
+        tokenMetadataProgram: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const metadata = await getTokenMetadata(
+      provider.connection,
+      tokenMintPDA,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(metadata.name).to.equal("Loyalty Points");
+    expect(metadata.symbol).to.equal("LOYAL");
+    expect(metadata.uri).to.equal("https://example.com/loyal.json");
+    // Held by the program so it follows admin transfers
+    expect(metadata.updateAuthority.toBase58()).to.equal(
+      platformStatePDA.toBase58()
+    );
+
+    const mint = await getMint(
+      provider.connection,
+      tokenMintPDA,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(mint.decimals).to.equal(TOKEN_DECIMALS);
+  });
+
+  it("updates the embedded metadata when it is set again", async () => {
+    await program.methods
+      .setTokenMetadata("Loyalty Rewards", "LRWD", "https://example.com/lrwd.json")
+      .accounts({
+        admin: admin.publicKey,
+        platformState: platformStatePDA,
+        tokenMint: tokenMintPDA,
+        metadataAccount: null,
+        tokenMetadataProgram: null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    const metadata = await getTokenMetadata(
+      provider.connection,
+      tokenMintPDA,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    expect(metadata.name).to.equal("Loyalty Rewards");
+    expect(metadata.symbol).to.equal("LRWD");
+    expect(metadata.uri).to.equal("https://example.com/lrwd.json");
+    expect(metadata.updateAuthority.toBase58()).to.equal(
+      platformStatePDA.toBase58()
+    );
+  });
+
+  it("rejects a direct transfer of non-transferable points", async () => {
+    await mintPoints(consumer.publicKey, "T22-TRANSFER");
+    await mintPoints(friend.publicKey, "T22-FRIEND");
+
+    let transferred = true;
+    try {
+      await transferChecked(
+        provider.connection,
+        consumer,
+        ata(consumer.publicKey),
+        tokenMintPDA,
+        ata(friend.publicKey),
+        consumer,
+        1,
+        TOKEN_DECIMALS,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+    } catch (error) {
+      // Token-2022 rejects the transfer itself - NonTransferable mint
+      transferred = false;
+    }
+    expect(transferred).to.be.false;
+    expect(await balance(consumer.publicKey)).to.equal(MINT_AMOUNT.toNumber());
+  });
+
+  describe("gifts", () => {
+    const giftConfig = (allowNonTransferableGifts: boolean | null) => ({
+      giftFee: null,
+      giftDailyCap: null,
+      giftMinAccountAge: null,
+      allowNonTransferableGifts,
+    });
+    const gift = async (amount: anchor.BN) => {
+      const profile = await program.account.consumerProfile.fetch(
+        consumerProfilePDA(consumer.publicKey)
+      );
+      const lots = [];
+      for (
+        let i = profile.headLotIndex.toNumber();
+        i < profile.nextLotIndex.toNumber();
+        i++
+      ) {
+        lots.push({
+          pubkey: lotPDA(consumer.publicKey, new anchor.BN(i)),
+          isSigner: false,
+          isWritable: true,
+        });
+      }
+      return program.methods
+        .giftPoints(amount)
+        .accounts({
+          sender: consumer.publicKey,
+          recipient: friend.publicKey,
+          platformState: platformStatePDA,
+          protocolTreasury: treasuryPDA,
+          tokenMint: tokenMintPDA,
+          senderTokenAccount: ata(consumer.publicKey),
+          recipientTokenAccount: ata(friend.publicKey),
+          senderProfile: consumerProfilePDA(consumer.publicKey),
+          recipientProfile: consumerProfilePDA(friend.publicKey),
+          recipientLot: await nextLotPDA(friend.publicKey),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(lots)
+        .signers([consumer])
+        .rpc();
+    };
+
+    it("fails to gift until the admin allows non-transferable gifts", async () => {
+      try {
+        await gift(new anchor.BN(1 * 10 ** TOKEN_DECIMALS));
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal(
+          "NonTransferableGiftsDisabled"
+        );
+      }
+    });
+
+    it("burns and re-mints the gift once allowed", async () => {
+      await program.methods
+        .setGiftConfig(giftConfig(true))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+
+      const amount = new anchor.BN(1 * 10 ** TOKEN_DECIMALS);
+      const senderBefore = await balance(consumer.publicKey);
+      const recipientBefore = await balance(friend.publicKey);
+      try {
+        await gift(amount);
+      } finally {
+        await program.methods
+          .setGiftConfig(giftConfig(false))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+
+      expect(senderBefore - (await balance(consumer.publicKey))).to.equal(
+        amount.toNumber()
+      );
+      expect((await balance(friend.publicKey)) - recipientBefore).to.equal(
+        amount.toNumber()
+      );
+    });
+  });
+
+  describe("permanent delegate", () => {
+    const holder = Keypair.generate();
+
+    before(async () => {
+      const airdrop = await provider.connection.requestAirdrop(
+        holder.publicKey,
+        1 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(airdrop);
+    });
+
+    it("doesn't need the holder's approval to burn expired points", async () => {
+      try {
+        await program.methods
+          .setExpiryDelegate(MINT_AMOUNT)
+          .accounts({
+            consumer: consumer.publicKey,
+            platformState: platformStatePDA,
+            tokenMint: tokenMintPDA,
+            consumerTokenAccount: ata(consumer.publicKey),
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([consumer])
+          .rpc();
+        assert.fail("Should have thrown an error");
+      } catch (error: any) {
+        expect(error.error.errorCode.code).to.equal("ExpiryDelegateNotNeeded");
+      }
+    });
+
+    it("burns an expired lot without any approval", async () => {
+      const expiryConfig = (seconds: number) => ({
+        baseMintFee: null,
+        feeRatePerThousand: null,
+        solToPointsRatio: null,
+        maxSupply: null,
+        pointsExpirySeconds: new anchor.BN(seconds),
+        referrerBonus: null,
+        refereeBonus: null,
+      });
+
+      await program.methods
+        .updatePlatformConfig(expiryConfig(1))
+        .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+        .rpc();
+      try {
+        await mintPoints(holder.publicKey, "T22-EXPIRING");
+      } finally {
+        await program.methods
+          .updatePlatformConfig(expiryConfig(0))
+          .accounts({ admin: admin.publicKey, platformState: platformStatePDA })
+          .rpc();
+      }
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const supplyBefore = (
+        await program.account.platformState.fetch(platformStatePDA)
+      ).currentSupply;
+
+      await program.methods
+        .expirePoints()
+        .accounts({
+          cranker: admin.publicKey,
+          platformState: platformStatePDA,
+          tokenMint: tokenMintPDA,
+          consumer: holder.publicKey,
+          consumerProfile: consumerProfilePDA(holder.publicKey),
+          pointsLot: lotPDA(holder.publicKey, new anchor.BN(0)),
+          consumerTokenAccount: ata(holder.publicKey),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+      expect(await balance(holder.publicKey)).to.equal(0);
+      const supplyAfter = (
+        await program.account.platformState.fetch(platformStatePDA)
+      ).currentSupply;
+      expect(supplyBefore.sub(supplyAfter).toNumber()).to.equal(
+        MINT_AMOUNT.toNumber()
+      );
+    });
+  });
+});